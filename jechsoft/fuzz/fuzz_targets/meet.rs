@@ -0,0 +1,14 @@
+//! Parse arbitrary meet setup files in any encoding. Seed the fuzzer with the parser corpus:
+//! `cargo +nightly fuzz run meet ../tests/corpus`.
+#![no_main]
+
+use jechsoft::meet_setup::meet::Meet;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(meet) = Meet::from_reader(bytes) {
+        let _ = meet.validate();
+        let _ = meet.deadlines();
+        let _ = meet.heat_list_config();
+    }
+});
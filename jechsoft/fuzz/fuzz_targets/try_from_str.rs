@@ -0,0 +1,42 @@
+//! Run every `TryFrom<&str>` of the crate on arbitrary strings: `cargo +nightly fuzz run
+//! try_from_str`.
+#![no_main]
+
+use jechsoft::heat_list::Entries;
+use jechsoft::meet_result::MeetResult;
+use jechsoft::meet_setup::{
+    classification::Classification, competition_type::CompetitionType, distance::Distance,
+    gender_class::GenderClass, gender_group::GenderGroup, handicap::Handicap,
+    individual_distance::Individual, pool_length::PoolLength, style::Style, team_distance::Team,
+    touch_pad_set::TouchPadSet,
+};
+use jechsoft::points::BaseTimes;
+use jechsoft::records::{AgeClass, Records, Scope};
+use jechsoft::roster::Roster;
+use jechsoft::swim_time;
+use jechsoft::trygg_i_vann::{Export, Outcome};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: &str| {
+    let _ = Distance::try_from(value);
+    let _ = Individual::try_from(value);
+    let _ = Team::try_from(value);
+    let _ = Style::try_from(value);
+    let _ = GenderGroup::try_from(value);
+    let _ = GenderClass::try_from(value);
+    let _ = Handicap::try_from(value);
+    let _ = Classification::try_from(value);
+    let _ = CompetitionType::try_from(value);
+    let _ = PoolLength::try_from(value);
+    let _ = TouchPadSet::try_from(value);
+    let _ = swim_time::parse(value);
+    let _ = Entries::try_from(value);
+    let _ = BaseTimes::try_from(value);
+    let _ = Records::try_from(value);
+    let _ = Records::from_lenex(value, Scope::National);
+    let _ = AgeClass::try_from(value);
+    let _ = Roster::try_from(value);
+    let _ = MeetResult::try_from(value);
+    let _ = Export::try_from(value);
+    let _ = Outcome::try_from(value);
+});
@@ -0,0 +1,260 @@
+//! # Calendar
+//!
+//! Exports a `Meet` as an iCalendar (`.ics`, RFC 5545) file with one calendar event per
+//! `Session` and one all day calendar event per entry deadline. Times are written as floating
+//! local time because `meetsetup.xml` does not contain a time zone.
+extern crate chrono;
+extern crate time;
+use self::chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use self::time::Time;
+use crate::meet_setup::{meet::Meet, session::Session};
+use std::fmt::Write;
+
+const PRODUCT_ID: &str = "-//moisty//jechsoft calendar//EN";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const DATE_FORMAT: &str = "%Y%m%d";
+/// Max line length in octets before a line has to be folded.
+const LINE_LENGTH: usize = 75;
+
+/// Calendar export settings.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Add the list of events in each session to the session description.
+    pub include_events: bool,
+    /// Length of a session. Sessions are cut short if the next session on the same day starts
+    /// earlier.
+    pub session_length: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            include_events: true,
+            session_length: Duration::hours(3),
+        }
+    }
+}
+
+/// Create an iCalendar document for `meet`.
+#[must_use]
+pub fn to_ical(meet: &Meet, options: &Options) -> String {
+    let time_stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let uid_prefix = meet.nsf_meet_id.map_or_else(
+        || {
+            meet.name
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase()
+        },
+        |id| format!("{id:0>10}"),
+    );
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(&meet.name)),
+    ];
+
+    let mut sessions: Vec<&Session> = meet.sessions.iter().collect();
+    sessions.sort_by_key(|session| (session.date, session.start_time));
+
+    for (index, session) in sessions.iter().enumerate() {
+        let start = date_time(session.date, session.start_time);
+        let next_start = sessions
+            .get(index + 1)
+            .filter(|next| next.date == session.date)
+            .map(|next| date_time(next.date, next.start_time));
+        let end = next_start.map_or(start + options.session_length, |next_start| {
+            next_start.min(start + options.session_length)
+        });
+
+        let summary = if session.name.is_empty() {
+            format!("{} - session {}", meet.name, session.id)
+        } else {
+            format!("{} - {}", meet.name, session.name)
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{uid_prefix}-session-{}@moisty", session.id));
+        lines.push(format!("DTSTAMP:{time_stamp}"));
+        lines.push(format!("DTSTART:{}", start.format(DATE_TIME_FORMAT)));
+        lines.push(format!("DTEND:{}", end.format(DATE_TIME_FORMAT)));
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        lines.push(format!("LOCATION:{}", escape(&meet.location)));
+        if let Some(home_page) = &meet.home_page {
+            lines.push(format!("URL:{home_page}"));
+        }
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape(&session_description(meet, session, options))
+        ));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    let mut deadlines = vec![("entry deadline", meet.last_entry_date)];
+    if meet.final_entry_date != meet.last_entry_date {
+        deadlines.push(("last date for entry times", meet.final_entry_date));
+    }
+    for (name, date) in deadlines {
+        let uid = name.replace(' ', "-");
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{uid_prefix}-{uid}@moisty"));
+        lines.push(format!("DTSTAMP:{time_stamp}"));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format(DATE_FORMAT)));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + Duration::days(1)).format(DATE_FORMAT)
+        ));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape(&format!("{}: {name}", meet.name))
+        ));
+        if let Some(home_page) = &meet.home_page {
+            lines.push(format!("URL:{home_page}"));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().fold(String::new(), |mut ical, line| {
+        ical.push_str(&fold(line));
+        ical.push_str("\r\n");
+        ical
+    })
+}
+
+fn date_time(date: NaiveDate, time: Time) -> NaiveDateTime {
+    let time = NaiveTime::from_hms_opt(time.hour().into(), time.minute().into(), 0)
+        .unwrap_or(NaiveTime::MIN);
+    date.and_time(time)
+}
+
+fn session_description(meet: &Meet, session: &Session, options: &Options) -> String {
+    let mut description = String::new();
+
+    if options.include_events {
+        for event in meet
+            .events
+            .iter()
+            .filter(|event| event.ses_id == Some(session.id))
+        {
+            let _ = writeln!(
+                description,
+                "{}. {} {} {}",
+                event.id, event.distance, event.style, event.gender_group
+            );
+        }
+        if !description.is_empty() {
+            description.push('\n');
+        }
+    }
+
+    let _ = writeln!(description, "Entry deadline: {}", meet.last_entry_date);
+    if meet.final_entry_date != meet.last_entry_date {
+        let _ = writeln!(
+            description,
+            "Last date for entry times: {}",
+            meet.final_entry_date
+        );
+    }
+    if let Some(home_page) = &meet.home_page {
+        let _ = writeln!(description, "{home_page}");
+    }
+
+    description.trim_end().to_string()
+}
+
+/// Escape text values. See RFC 5545 section 3.3.11.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold lines longer than `LINE_LENGTH` octets. See RFC 5545 section 3.1.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for character in line.chars() {
+        if length + character.len_utf8() > LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(character);
+        length += character.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unfold(folded: &str) -> String {
+        folded.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn escape_text_values() {
+        assert_eq!(
+            escape("Lørdag, økt 1; 09:00\nBassenget\\Hall"),
+            r"Lørdag\, økt 1\; 09:00\nBassenget\\Hall"
+        );
+        assert_eq!(escape("a\r\nb"), r"a\nb");
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        let line = format!("SUMMARY:{}", "a".repeat(LINE_LENGTH - "SUMMARY:".len()));
+        assert_eq!(fold(&line), line);
+    }
+
+    #[test]
+    fn fold_on_character_boundary() {
+        // 74 octets followed by a two octet 'ø' that would end at octet 76
+        let line = format!("SUMMARY:{}øæå", "a".repeat(66));
+        let folded = fold(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines[0].len(), 74);
+        assert_eq!(lines[1], " øæå");
+        assert_eq!(unfold(&folded), line);
+    }
+
+    #[test]
+    fn long_utf8_summary_is_folded_to_75_octets() {
+        let line = format!(
+            "SUMMARY:{}",
+            escape(&"Årsklassemesterskap i Ålesund, ".repeat(8))
+        );
+        let folded = fold(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert!(lines.len() > 3);
+        assert!(lines.iter().all(|line| line.len() <= LINE_LENGTH));
+        assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+        assert_eq!(unfold(&folded), line);
+    }
+
+    #[test]
+    fn calendar_lines_are_folded() {
+        let mut meet =
+            Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet");
+        meet.name =
+            "Årsklassemesterskap for æøå-klubbene, Sør- og Vestlandet; langbane".to_string();
+        let ical = to_ical(&meet, &Options::default());
+
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert!(ical.split("\r\n").all(|line| line.len() <= LINE_LENGTH));
+        assert!(unfold(&ical).contains(
+            r"X-WR-CALNAME:Årsklassemesterskap for æøå-klubbene\, Sør- og Vestlandet\; langbane"
+        ));
+        assert_eq!(unfold(&ical).matches("BEGIN:VEVENT").count(), 4);
+    }
+}
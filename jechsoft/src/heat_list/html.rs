@@ -0,0 +1,90 @@
+use super::{event_title, heat_start, heat_title, record_lines, Column, StartList};
+use crate::meet_setup::{heat_list_config::HeatListConfig, meet::Meet};
+use crate::printable::{escape_html as escape, printed};
+use crate::records::Records;
+use std::fmt::Write;
+
+const STYLE: &str = "body { font-family: sans-serif; font-size: 11pt; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1em; }
+th, td { text-align: left; padding: 2px 6px; }
+th { border-bottom: 1px solid black; }
+.heat { break-inside: avoid; }
+.header, .footer, .printed { color: #555; }
+.records { font-size: 9pt; }";
+
+/// Render start lists as a standalone HTML document. Pass `Records::default()` to leave out
+/// records.
+#[must_use]
+pub fn to_html(
+    meet: &Meet,
+    config: &HeatListConfig,
+    start_lists: &[StartList],
+    records: &Records,
+) -> String {
+    let mut html = String::new();
+
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, "<html>\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(html, "<title>{}</title>", escape(&meet.name));
+    let _ = writeln!(html, "<style>\n{STYLE}\n</style>\n</head>\n<body>");
+
+    if let Some(header) = &config.header {
+        let _ = writeln!(html, "<p class=\"header\">{}</p>", escape(header));
+    }
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&meet.name));
+    let _ = writeln!(
+        html,
+        "<p>{}, {}</p>",
+        escape(&meet.location),
+        escape(&meet.date)
+    );
+
+    for start_list in start_lists {
+        let event = start_list.event;
+        let _ = writeln!(html, "<h2>{}</h2>", escape(&event_title(event, config)));
+        if let Some(sponsor) = event.sponsor.as_ref().filter(|sponsor| !sponsor.is_empty()) {
+            let _ = writeln!(html, "<p>{}</p>", escape(sponsor));
+        }
+        let lines = record_lines(event, config, records);
+        if !lines.is_empty() {
+            let lines: Vec<String> = lines.iter().map(|line| escape(line)).collect();
+            let _ = writeln!(html, "<p class=\"records\">{}</p>", lines.join("<br>"));
+        }
+
+        let columns = Column::for_event(event, config);
+        for heat in &start_list.heats {
+            let _ = writeln!(html, "<div class=\"heat\">");
+            let _ = writeln!(
+                html,
+                "<h3>{}</h3>",
+                escape(&heat_title(heat, start_list.heats.len(), config))
+            );
+            if let Some(start) = heat_start(meet, event, heat, config) {
+                let _ = writeln!(html, "<p>{}</p>", escape(&start));
+            }
+            let _ = write!(html, "<table>\n<tr>");
+            for column in &columns {
+                let _ = write!(html, "<th>{}</th>", column.header());
+            }
+            let _ = writeln!(html, "</tr>");
+            for lane in &heat.lanes {
+                let _ = write!(html, "<tr>");
+                for column in &columns {
+                    let _ = write!(html, "<td>{}</td>", escape(&column.cell(lane)));
+                }
+                let _ = writeln!(html, "</tr>");
+            }
+            let _ = writeln!(html, "</table>\n</div>");
+        }
+    }
+
+    if let Some(footer) = &config.footer {
+        let _ = writeln!(html, "<p class=\"footer\">{}</p>", escape(footer));
+    }
+    if config.write_date_time {
+        let _ = writeln!(html, "<p class=\"printed\">Printed {}</p>", printed());
+    }
+    let _ = writeln!(html, "</body>\n</html>");
+
+    html
+}
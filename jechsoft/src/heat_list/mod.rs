@@ -0,0 +1,584 @@
+//! # Heat list
+//!
+//! Seeds entries into heats and renders printable heat lists (start lists) as HTML or PDF.
+//! Presentation is controlled by `HeatListConfig`, see `Meet::heat_list_config`. Records of each
+//! event are printed above the heats when the meet asks for it and `Records` are given.
+//!
+//! Entries are read from plain text files with one entry per line:
+//!
+//! ```text
+//! # event, name, club, birth year, entry time, nation, first leg
+//! 1,Kari Nordmann,Bergen Svømmeklubb,2008,1:02.34,NOR
+//! 1,Ola Nordmann,Bergen Svømmeklubb,2009,,NOR
+//! 12,Bergen Svømmeklubb 1,Bergen Svømmeklubb,,4:01.22,,Kari Nordmann
+//! ```
+//!
+//! Birth year, entry time, nation and the swimmer of the first relay leg are optional. Entries without an entry time are seeded as
+//! the slowest. Empty lines and lines starting with `#` are ignored.
+extern crate gregorian;
+use self::gregorian::Year;
+use crate::meet_setup::{event::Event, heat_list_config::HeatListConfig, meet::Meet};
+use crate::records::Records;
+use crate::swim_time;
+use std::{
+    collections::HashMap, convert::TryFrom, fmt::Display, fmt::Write, fs, path::Path,
+    time::Duration,
+};
+
+mod html;
+mod pdf;
+
+pub use self::html::to_html;
+pub use self::pdf::to_pdf;
+
+/// Least number of swimmers in the first heat of an event with more than one heat.
+const MIN_FIRST_HEAT: usize = 3;
+
+/// Athlete or relay team entered in an event.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Athlete name or relay team name.
+    pub name: String,
+    pub club: String,
+    pub birth_year: Option<Year>,
+    /// `None` means no time (NT).
+    pub entry_time: Option<Duration>,
+    pub nation: Option<String>,
+    /// Swimmer of the first leg of a relay team.
+    pub first_leg: Option<String>,
+}
+
+/// Entries of a meet by event number.
+#[derive(Debug, Clone, Default)]
+pub struct Entries {
+    events: HashMap<u32, Vec<Entry>>,
+}
+
+/// Entry swimming in a lane.
+#[derive(Debug, Clone)]
+pub struct Lane {
+    pub lane: u8,
+    pub entry: Entry,
+}
+
+/// Lanes of a heat, ordered by lane number.
+#[derive(Debug, Clone)]
+pub struct Heat {
+    /// Heat number. Starts with 1.
+    pub number: usize,
+    pub lanes: Vec<Lane>,
+}
+
+/// Seeded heats of one event.
+#[derive(Debug, Clone)]
+pub struct StartList<'a> {
+    pub event: &'a Event,
+    pub heats: Vec<Heat>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Event,
+    Name,
+    Club,
+    BirthYear,
+    EntryTime,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    /// Line number and field that is missing.
+    Missing(usize, Field),
+    /// Line number and field that could not be parsed.
+    Invalid(usize, Field),
+    /// The meet has no lanes to seed entries into.
+    NoLanes,
+    Pdf(#[from] printpdf::Error),
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Event => write!(f, "event"),
+                Self::Name => write!(f, "name"),
+                Self::Club => write!(f, "club"),
+                Self::BirthYear => write!(f, "birth year"),
+                Self::EntryTime => write!(f, "entry time"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Missing(line, field) => write!(f, "line {line}: missing field: {field}"),
+                Self::Invalid(line, field) => write!(f, "line {line}: invalid field: {field}"),
+                Self::NoLanes => write!(f, "cannot seed heats in a pool without lanes"),
+                Self::Pdf(err) => write!(f, "could not create pdf: {err}"),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for Entries {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut events: HashMap<u32, Vec<Entry>> = HashMap::new();
+
+        for (index, line) in value.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split(',').map(str::trim);
+            let mut next = |field| fields.next().ok_or(Error::Missing(line_number, field));
+
+            let event = next(Field::Event)?
+                .parse::<u32>()
+                .map_err(invalid(line_number, Field::Event))?;
+            let name = next(Field::Name)?;
+            if name.is_empty() {
+                return Err(Error::Missing(line_number, Field::Name));
+            }
+            let club = next(Field::Club)?.to_string();
+            let birth_year = match next(Field::BirthYear).unwrap_or_default() {
+                "" => None,
+                year => Some(Year::new(
+                    year.parse::<i16>()
+                        .map_err(invalid(line_number, Field::BirthYear))?,
+                )),
+            };
+            let entry_time = match next(Field::EntryTime).unwrap_or_default() {
+                "" | "NT" => None,
+                time => {
+                    Some(swim_time::parse(time).map_err(invalid(line_number, Field::EntryTime))?)
+                }
+            };
+            let mut optional = || {
+                fields
+                    .next()
+                    .filter(|field| !field.is_empty())
+                    .map(ToString::to_string)
+            };
+            let nation = optional();
+            let first_leg = optional();
+
+            events.entry(event).or_default().push(Entry {
+                name: name.to_string(),
+                club,
+                birth_year,
+                entry_time,
+                nation,
+                first_leg,
+            });
+        }
+
+        Ok(Self { events })
+    }
+}
+
+fn invalid<E>(line_number: usize, field: Field) -> impl FnOnce(E) -> Error {
+    move |_| Error::Invalid(line_number, field)
+}
+
+impl Entries {
+    /// # Errors
+    /// Returns an error if the file cannot be read or if any of the lines are invalid.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        Self::try_from(content.as_str())
+    }
+
+    /// Entries of event number `event`.
+    #[must_use]
+    pub fn get(&self, event: u32) -> &[Entry] {
+        self.events.get(&event).map_or(&[], Vec::as_slice)
+    }
+
+    /// Seed the entries of every event in `meet` that has entries.
+    ///
+    /// # Errors
+    /// Returns an error if the meet has no lanes.
+    pub fn start_lists<'a>(
+        &self,
+        meet: &'a Meet,
+        config: &HeatListConfig,
+    ) -> Result<Vec<StartList<'a>>, Error> {
+        meet.events
+            .iter()
+            .filter(|event| !self.get(event.id).is_empty())
+            .map(|event| {
+                Ok(StartList {
+                    event,
+                    heats: seed(self.get(event.id), config.lanes, config.first_lane)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Seed `entries` into heats with the fastest entries in the last heat and in the center lanes.
+///
+/// The first heat is filled up to at least three swimmers by taking swimmers from the second
+/// heat. This is the seeding used for `Sorting::Final`.
+///
+/// # Errors
+/// Returns an error if `lanes` is zero.
+pub fn seed(entries: &[Entry], lanes: u8, first_lane: u8) -> Result<Vec<Heat>, Error> {
+    let lane_count = usize::from(lanes);
+    if lane_count == 0 {
+        return Err(Error::NoLanes);
+    }
+
+    let mut entries = entries.to_vec();
+    entries.sort_by_key(|entry| (entry.entry_time.is_none(), entry.entry_time));
+
+    let heat_count = entries.len().div_ceil(lane_count);
+    let mut sizes = vec![lane_count; heat_count];
+    if let Some(first) = sizes.first_mut() {
+        *first = entries.len() - (heat_count - 1) * lane_count;
+    }
+    if heat_count > 1 && sizes[0] < MIN_FIRST_HEAT {
+        let moved = (MIN_FIRST_HEAT - sizes[0]).min(sizes[1] - 1);
+        sizes[0] += moved;
+        sizes[1] -= moved;
+    }
+
+    let order = lane_order(lanes);
+    let mut remaining = entries.into_iter();
+    let mut heats: Vec<Heat> = sizes
+        .iter()
+        .rev()
+        .zip((1..=heat_count).rev())
+        .map(|(&size, number)| {
+            let mut lanes: Vec<Lane> = remaining
+                .by_ref()
+                .take(size)
+                .zip(&order)
+                .map(|(entry, &offset)| Lane {
+                    lane: first_lane.saturating_add(offset),
+                    entry,
+                })
+                .collect();
+            lanes.sort_by_key(|lane| lane.lane);
+            Heat { number, lanes }
+        })
+        .collect();
+    heats.reverse();
+
+    Ok(heats)
+}
+
+/// Lane offsets from the center of the pool and outwards: 3, 4, 2, 5, 1, 6, 0, 7 for 8 lanes.
+fn lane_order(lanes: u8) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..lanes).collect();
+    order.sort_by_key(|&lane| (u16::from(lane) * 2).abs_diff(u16::from(lanes) - 1));
+    order
+}
+
+/// Columns printed for every lane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Column {
+    Lane,
+    Name,
+    BirthYear,
+    Club,
+    Nation,
+    FirstLeg,
+    EntryTime,
+    FirstLap,
+}
+
+impl Column {
+    pub(crate) fn for_event(event: &Event, config: &HeatListConfig) -> Vec<Self> {
+        let mut columns = vec![Self::Lane, Self::Name];
+        if !event.dont_show_age_group && !event.distance.is_team() {
+            columns.push(Self::BirthYear);
+        }
+        columns.push(Self::Club);
+        if config.write_country {
+            columns.push(Self::Nation);
+        }
+        if config.write_first_stage && event.distance.is_team() {
+            columns.push(Self::FirstLeg);
+        }
+        if event.show_entry_times {
+            columns.push(Self::EntryTime);
+        }
+        if config.write_first_lap && event.distance.meters() > u32::from(event.pool_length.meters())
+        {
+            columns.push(Self::FirstLap);
+        }
+        columns
+    }
+
+    pub(crate) const fn header(self) -> &'static str {
+        match self {
+            Self::Lane => "Lane",
+            Self::Name => "Name",
+            Self::BirthYear => "Born",
+            Self::Club => "Club",
+            Self::Nation => "Nation",
+            Self::FirstLeg => "First leg",
+            Self::EntryTime => "Entry time",
+            Self::FirstLap => "First lap",
+        }
+    }
+
+    pub(crate) fn cell(self, lane: &Lane) -> String {
+        match self {
+            Self::Lane => lane.lane.to_string(),
+            Self::Name => lane.entry.name.clone(),
+            Self::BirthYear => lane
+                .entry
+                .birth_year
+                .map_or_else(String::new, |year| year.to_string()),
+            Self::Club => lane.entry.club.clone(),
+            Self::Nation => lane.entry.nation.clone().unwrap_or_default(),
+            Self::FirstLeg => lane.entry.first_leg.clone().unwrap_or_default(),
+            Self::EntryTime => lane
+                .entry
+                .entry_time
+                .map_or_else(|| "NT".to_string(), swim_time::format),
+            // Left empty for the timekeepers to write the split.
+            Self::FirstLap => String::new(),
+        }
+    }
+}
+
+/// Event title as printed above the heats.
+#[must_use]
+pub fn event_title(event: &Event, config: &HeatListConfig) -> String {
+    if config.use_group_text && !event.description.is_empty() {
+        format!("Event {} - {}", event.id, event.description)
+    } else {
+        format!(
+            "Event {} - {} {} {}",
+            event.id, event.distance, event.style, event.gender_group
+        )
+    }
+}
+
+/// Heat title as printed above the lanes.
+#[must_use]
+pub fn heat_title(heat: &Heat, heat_count: usize, config: &HeatListConfig) -> String {
+    if config.show_heat_text {
+        format!("Heat {} of {heat_count}", heat.number)
+    } else {
+        format!("Heat {}", heat.number)
+    }
+}
+
+/// Records printed above the heats of `event` if `HeatListConfig::records_in_heat_list` is set.
+pub(crate) fn record_lines(
+    event: &Event,
+    config: &HeatListConfig,
+    records: &Records,
+) -> Vec<String> {
+    if !config.records_in_heat_list {
+        return Vec::new();
+    }
+    records
+        .for_event(event)
+        .into_iter()
+        .map(|(key, record)| {
+            let mut line = format!(
+                "{}: {} {}, {}",
+                key.label(),
+                swim_time::format(record.time),
+                record.name,
+                record.club
+            );
+            if let Some(date) = record.date {
+                let _ = write!(line, " {date}");
+            }
+            line
+        })
+        .collect()
+}
+
+/// Session start printed next to the heat title if `HeatListConfig::show_time_schedule` is set.
+/// Only the first heat of each event gets the time with `HeatListConfig::show_time_only_heat_one`.
+pub(crate) fn heat_start(
+    meet: &Meet,
+    event: &Event,
+    heat: &Heat,
+    config: &HeatListConfig,
+) -> Option<String> {
+    if !config.show_time_schedule || (config.show_time_only_heat_one && heat.number != 1) {
+        return None;
+    }
+    let session = meet
+        .sessions
+        .iter()
+        .find(|session| Some(session.id) == event.ses_id)?;
+    Some(format!(
+        "{} {:02}:{:02}",
+        session.date,
+        session.start_time.hour(),
+        session.start_time.minute()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRIES: &str = "1,Kari Nordmann,Bergen Svømmeklubb,2008,1:02.34
+1,Ola Nordmann,Bergen Svømmeklubb,2009,1:05.00
+1,Per Hansen,Askøy Svømmeklubb,2008,1:07.00
+1,Anne Hansen,Askøy Svømmeklubb,2010,1:09.00
+1,Eva Olsen,Askøy Svømmeklubb,2011,
+2,Bergen Svømmeklubb 1,Bergen Svømmeklubb,,1:50.00,,Ola Nordmann
+";
+
+    fn meet() -> Meet {
+        Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet")
+    }
+
+    fn config() -> HeatListConfig {
+        HeatListConfig {
+            first_lane: 1,
+            lanes: 4,
+            ..HeatListConfig::default()
+        }
+    }
+
+    fn html(meet: &Meet, config: &HeatListConfig) -> String {
+        let entries = Entries::try_from(ENTRIES).expect("valid entries");
+        let start_lists = entries.start_lists(meet, config).expect("start lists");
+        to_html(meet, config, &start_lists, &Records::default())
+    }
+
+    #[test]
+    fn time_schedule_is_printed_for_every_heat() {
+        let meet = meet();
+        let config = HeatListConfig {
+            show_time_schedule: true,
+            ..config()
+        };
+        let start = format!("<p>{}</p>", session_start(&meet, 1));
+
+        assert_eq!(html(&meet, &config).matches(&start).count(), 2);
+    }
+
+    #[test]
+    fn time_schedule_only_for_heat_one() {
+        let meet = meet();
+        let config = HeatListConfig {
+            show_time_schedule: true,
+            show_time_only_heat_one: true,
+            ..config()
+        };
+        let html = html(&meet, &config);
+        let start = format!("<p>{}</p>", session_start(&meet, 1));
+
+        assert_eq!(html.matches(&start).count(), 1);
+        assert!(html.find(&start) < html.find("<h3>Heat 2</h3>"));
+    }
+
+    #[test]
+    fn first_lap_column_for_events_longer_than_the_pool() {
+        let meet = meet();
+        assert!(!html(&meet, &config()).contains("First lap"));
+
+        let config = HeatListConfig {
+            write_first_lap: true,
+            ..config()
+        };
+        // The 100m event gets the column, the 4x50m relay in a 50m pool as well.
+        assert_eq!(
+            html(&meet, &config).matches("<th>First lap</th>").count(),
+            3
+        );
+    }
+
+    #[test]
+    fn first_leg_of_relays() {
+        let meet = meet();
+        assert!(!html(&meet, &config()).contains("First leg"));
+
+        let config = HeatListConfig {
+            write_first_stage: true,
+            ..config()
+        };
+        let html = html(&meet, &config);
+        assert_eq!(html.matches("<th>First leg</th>").count(), 1);
+        assert!(html.contains("<td>Ola Nordmann</td><td>"));
+    }
+
+    #[test]
+    fn pdf_with_every_flag() {
+        let meet = meet();
+        let config = HeatListConfig {
+            show_time_schedule: true,
+            show_time_only_heat_one: true,
+            write_first_lap: true,
+            write_first_stage: true,
+            ..config()
+        };
+        let entries = Entries::try_from(ENTRIES).expect("valid entries");
+        let start_lists = entries.start_lists(&meet, &config).expect("start lists");
+        let pdf = to_pdf(&meet, &config, &start_lists, &Records::default()).expect("pdf");
+
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn records_of_the_event_above_the_heats() {
+        let meet = meet();
+        let records = Records::try_from(
+            "\
+NATIONAL,FEMALE,50,100,FREESTYLE,SR,53.52,Kari Nordmann,Bergen Svømmeklubb,2021-07-25,NM Langbane
+CLUB,FEMALE,50,100,FREESTYLE,14,1:01.20,Lise Nordmann,Bergen Svømmeklubb,,
+CLUB,FEMALE,25,100,FREESTYLE,SR,58.00,Anne Nordmann,Bergen Svømmeklubb,,
+CLUB,MALE,50,100,FREESTYLE,SR,52.00,Ola Nordmann,Bergen Svømmeklubb,,
+",
+        )
+        .expect("valid records");
+        let event = meet
+            .events
+            .iter()
+            .find(|event| event.id == 1)
+            .expect("event");
+        assert!(record_lines(event, &config(), &records).is_empty());
+
+        let config = HeatListConfig {
+            records_in_heat_list: true,
+            ..config()
+        };
+        assert_eq!(
+            record_lines(event, &config, &records),
+            [
+                "CR 14 years: 1:01.20 Lise Nordmann, Bergen Svømmeklubb",
+                "NR: 53.52 Kari Nordmann, Bergen Svømmeklubb 2021-07-25",
+            ]
+        );
+    }
+
+    fn session_start(meet: &Meet, session: u8) -> String {
+        let session = meet
+            .sessions
+            .iter()
+            .find(|candidate| candidate.id == session)
+            .expect("session");
+        format!(
+            "{} {:02}:{:02}",
+            session.date,
+            session.start_time.hour(),
+            session.start_time.minute()
+        )
+    }
+}
@@ -0,0 +1,87 @@
+use super::{event_title, heat_start, heat_title, record_lines, Column, Error, StartList};
+use crate::meet_setup::{heat_list_config::HeatListConfig, meet::Meet};
+use crate::printable::{row, Layout, PageSettings, HEADING_SIZE, TEXT_SIZE, TITLE_SIZE};
+use crate::records::Records;
+
+const fn column_width(column: Column) -> f32 {
+    match column {
+        Column::Lane => 12.0,
+        Column::Name => 58.0,
+        Column::BirthYear | Column::Nation => 15.0,
+        Column::Club | Column::FirstLeg => 55.0,
+        Column::EntryTime | Column::FirstLap => 22.0,
+    }
+}
+
+/// Render start lists as an A4 PDF document. Pass `Records::default()` to leave out records.
+///
+/// # Errors
+/// Returns an error if the PDF document cannot be created.
+pub fn to_pdf(
+    meet: &Meet,
+    config: &HeatListConfig,
+    start_lists: &[StartList],
+    records: &Records,
+) -> Result<Vec<u8>, Error> {
+    let mut layout = Layout::new();
+
+    layout.text(meet.name.clone(), TITLE_SIZE, true);
+    layout.text(
+        format!("{}, {}", meet.location, meet.date),
+        TEXT_SIZE,
+        false,
+    );
+
+    for start_list in start_lists {
+        let event = start_list.event;
+        let columns = Column::for_event(event, config);
+        let record_lines = record_lines(event, config, records);
+
+        layout.space();
+        layout.reserve(4 + record_lines.len());
+        layout.text(event_title(event, config), HEADING_SIZE, true);
+        if let Some(sponsor) = event.sponsor.as_ref().filter(|sponsor| !sponsor.is_empty()) {
+            layout.text(sponsor.clone(), TEXT_SIZE, false);
+        }
+        for line in record_lines {
+            layout.text(line, TEXT_SIZE, false);
+        }
+
+        for heat in &start_list.heats {
+            layout.space();
+            layout.reserve(heat.lanes.len() + 3);
+            layout.text(
+                heat_title(heat, start_list.heats.len(), config),
+                TEXT_SIZE,
+                true,
+            );
+            if let Some(start) = heat_start(meet, event, heat, config) {
+                layout.text(start, TEXT_SIZE, false);
+            }
+            layout.line(
+                row(columns
+                    .iter()
+                    .map(|&column| (column_width(column), column.header().to_string()))),
+                TEXT_SIZE,
+                true,
+            );
+            for lane in &heat.lanes {
+                layout.line(
+                    row(columns
+                        .iter()
+                        .map(|&column| (column_width(column), column.cell(lane)))),
+                    TEXT_SIZE,
+                    false,
+                );
+            }
+        }
+    }
+
+    Ok(layout.render(&PageSettings {
+        title: &meet.name,
+        header: config.header.as_deref(),
+        footer: config.footer.as_deref(),
+        write_date_time: config.write_date_time,
+        page_number: config.page_number,
+    })?)
+}
@@ -1,6 +1,4 @@
 #![feature(iter_next_chunk)]
-#![feature(array_chunks)]
-#![feature(iter_array_chunks)]
 #![warn(clippy::cargo)]
 #![warn(clippy::complexity)]
 #![warn(clippy::correctness)]
@@ -17,6 +15,17 @@
 #![allow(clippy::blanket_clippy_restriction_lints)]
 #![allow(clippy::missing_inline_in_public_items)]
 #![allow(clippy::print_stdout)]
+pub mod calendar;
+pub mod heat_list;
 pub mod medley;
+pub mod meet_result;
 pub mod meet_setup;
+pub mod points;
+mod printable;
+pub mod qualification;
+pub mod records;
+pub mod relay;
+pub mod roster;
+pub mod swim_time;
+pub mod trygg_i_vann;
 pub mod uni_p;
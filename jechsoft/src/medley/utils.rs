@@ -1,18 +1,16 @@
 extern crate chrono;
-extern crate encoding;
-extern crate serde_xml_rs;
 extern crate log;
+extern crate serde_xml_rs;
 
 use super::{meet_info::MeetInfo, Entries};
+use crate::meet_setup::decode;
 
 use self::chrono::NaiveDate;
-use self::encoding::all::ISO_8859_1;
-use self::encoding::Encoding;
 use self::serde_xml_rs::from_str;
 use std::error::Error;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// # Errors
 /// returns error if:
@@ -34,77 +32,85 @@ pub fn get_meet_list(search_date_start: NaiveDate) -> Result<Vec<MeetInfo>, Box<
     Ok(tmp.meet_setup_entries)
 }
 
+/// What happened to a meet in `download_meets`.
+#[derive(Debug)]
+pub enum DownloadStatus {
+    /// The meet was downloaded and saved.
+    Downloaded,
+    /// The meet was already in the directory and was not downloaded again.
+    Cached,
+    /// The meet could not be downloaded or saved.
+    Failed(String),
+}
+
+/// Result of downloading one meet. See `download_meets`.
+#[derive(Debug)]
+pub struct Download {
+    pub meet_info: MeetInfo,
+    /// Path the meet is saved to.
+    pub path: PathBuf,
+    pub status: DownloadStatus,
+}
+
 /// Download all `meetsetup.xml` files into specified directory.
-/// Skips meet on error and prints the error message to `stderr`.
+/// Skips meet on error and prints the error message to `stderr`. Returns what happened to each
+/// meet in the same order as `meet_infos`.
 /// # Panics
 /// will panic when unable to create a cache directory
-pub fn download_meets(meets_directory: &Path, meet_infos: Vec<MeetInfo>) {
+#[must_use]
+pub fn download_meets(meets_directory: &Path, meet_infos: Vec<MeetInfo>) -> Vec<Download> {
     if !meets_directory.exists() {
         fs::create_dir_all(meets_directory).expect("could not create requested directory");
     }
     let web_client = reqwest::blocking::Client::new();
+    let mut downloads = Vec::new();
     for meet_info in meet_infos {
         let meet_path = meets_directory.join(meet_info.get_filename());
 
-        match fs::exists(&meet_path) {
+        let status = match fs::exists(&meet_path) {
             Err(why) => {
                 panic!("{why}");
             }
             Ok(true) => {
                 log::debug!(
                     "skipping {} {} beacuse it already exists in the cache directory",
-                    meet_info.name, meet_info.id
+                    meet_info.name,
+                    meet_info.id
                 );
-                continue;
+                DownloadStatus::Cached
             }
-            Ok(false) => {
-                const XML_ENCODING_HEADER_ISO_5589_1: &str =
-                    r#"<?xml version="1.0" encoding="ISO-8859-1" ?>"#;
-                const XML_ENCODING_HEADER_UTF8: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>"#;
-
-                // fetch the remote meet_setup.xml
-                let response = match web_client.get(meet_info.meet_setup).send() {
-                    Ok(response) => response,
-                    Err(why) => {
-                        log::error!("[{}] {}", &meet_info.name, why);
-                        continue;
-                    }
-                };
-
-                let content = response.bytes();
-                let content = match &content {
-                    Ok(content) => content,
-                    Err(why) => {
-                        log::error!("[{}] {}", &meet_info.name, why);
-                        continue;
-                    }
-                };
-
-                let content = match ISO_8859_1.decode(content, encoding::DecoderTrap::Strict) {
-                    Ok(content) => content,
-                    Err(why) => {
-                        log::error!("[{}] {}", &meet_info.name, why);
-                        continue;
-                    }
-                };
-
-                let content: String =
-                    content.replace(XML_ENCODING_HEADER_ISO_5589_1, XML_ENCODING_HEADER_UTF8);
-
-                // write to file
-                let mut meet_config_file = match File::create(&meet_path) {
-                    Ok(file) => file,
-                    Err(why) => {
-                        log::error!("[{}] {} {}", &meet_info.name, why, &meet_path.display());
-                        continue;
-                    }
-                };
-
-                if let Err(why) = meet_config_file.write(content.as_bytes()) {
+            Ok(false) => match download_meet(&web_client, &meet_info, &meet_path) {
+                Ok(()) => DownloadStatus::Downloaded,
+                Err(why) => {
                     log::error!("[{}] {}", &meet_info.name, why);
-                    continue;
-                };
-            }
+                    DownloadStatus::Failed(why.to_string())
+                }
+            },
         };
+        downloads.push(Download {
+            meet_info,
+            path: meet_path,
+            status,
+        });
     }
+    downloads
+}
+
+fn download_meet(
+    web_client: &reqwest::blocking::Client,
+    meet_info: &MeetInfo,
+    meet_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    // fetch the remote meet_setup.xml
+    let content = web_client
+        .get(meet_info.meet_setup.clone())
+        .send()?
+        .bytes()?;
+    let content = decode::to_utf8(&content);
+
+    // write to file
+    let mut meet_config_file =
+        File::create(meet_path).map_err(|why| format!("{why} {}", meet_path.display()))?;
+    meet_config_file.write_all(content.as_bytes())?;
+    Ok(())
 }
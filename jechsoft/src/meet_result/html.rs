@@ -0,0 +1,82 @@
+use super::{class_title, details, Column, Error, MeetResult};
+use crate::heat_list::event_title;
+use crate::meet_setup::meet::Meet;
+use crate::printable::{escape_html as escape, printed};
+use std::fmt::Write;
+
+const STYLE: &str = "body { font-family: sans-serif; font-size: 11pt; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1em; }
+th, td { text-align: left; padding: 2px 6px; }
+th { border-bottom: 1px solid black; }
+td.details { color: #555; font-size: 9pt; padding-top: 0; }
+.header, .footer, .printed { color: #555; }";
+
+/// Render results as a standalone HTML document.
+///
+/// # Errors
+/// Returns an error if the results contain an event that is not in `meet`.
+pub fn to_html(meet: &Meet, results: &MeetResult) -> Result<String, Error> {
+    let config = meet.heat_list_config();
+    let mut html = String::new();
+
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, "<html>\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(html, "<title>{}</title>", escape(&meet.name));
+    let _ = writeln!(html, "<style>\n{STYLE}\n</style>\n</head>\n<body>");
+
+    if let Some(header) = &config.header {
+        let _ = writeln!(html, "<p class=\"header\">{}</p>", escape(header));
+    }
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&meet.name));
+    let _ = writeln!(
+        html,
+        "<p>{}, {}</p>",
+        escape(&meet.location),
+        escape(&meet.date)
+    );
+
+    for (event, event_result) in results.with_events(meet)? {
+        let columns = Column::for_event(event, config.write_country);
+        let _ = writeln!(html, "<h2>{}</h2>", escape(&event_title(event, &config)));
+        if let Some(sponsor) = event.sponsor.as_ref().filter(|sponsor| !sponsor.is_empty()) {
+            let _ = writeln!(html, "<p>{}</p>", escape(sponsor));
+        }
+
+        for class_result in &event_result.classes {
+            if let Some(title) = class_title(event, class_result.class) {
+                let _ = writeln!(html, "<h3>{}</h3>", escape(&title));
+            }
+            let _ = write!(html, "<table>\n<tr>");
+            for column in &columns {
+                let _ = write!(html, "<th>{}</th>", column.header());
+            }
+            let _ = writeln!(html, "</tr>");
+            for placed in class_result.placed() {
+                let _ = write!(html, "<tr>");
+                for column in &columns {
+                    let _ = write!(html, "<td>{}</td>", escape(&column.cell(&placed)));
+                }
+                let _ = writeln!(html, "</tr>");
+                if let Some(details) = details(placed.result) {
+                    let _ = writeln!(
+                        html,
+                        "<tr><td></td><td class=\"details\" colspan=\"{}\">{}</td></tr>",
+                        columns.len() - 1,
+                        escape(&details)
+                    );
+                }
+            }
+            let _ = writeln!(html, "</table>");
+        }
+    }
+
+    if let Some(footer) = &config.footer {
+        let _ = writeln!(html, "<p class=\"footer\">{}</p>", escape(footer));
+    }
+    if config.write_date_time {
+        let _ = writeln!(html, "<p class=\"printed\">Printed {}</p>", printed());
+    }
+    let _ = writeln!(html, "</body>\n</html>");
+
+    Ok(html)
+}
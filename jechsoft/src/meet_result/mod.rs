@@ -0,0 +1,502 @@
+//! # Meet result
+//!
+//! Results of a meet grouped by event and class, and renderers for publishable result lists as
+//! plain text, HTML and PDF.
+//!
+//! `meetresult.xml` is not parsed yet, so results are loaded from JSON files:
+//!
+//! ```json
+//! {
+//!   "events": [
+//!     {
+//!       "event": 1,
+//!       "classes": [
+//!         {
+//!           "class": "SR",
+//!           "results": [
+//!             {
+//!               "name": "Kari Nordmann",
+//!               "club": "Bergen Svømmeklubb",
+//!               "birth_year": 2008,
+//!               "gender": "FEMALE",
+//!               "time": "1:01.23",
+//!               "splits": [{ "distance": 50, "time": "29.80" }],
+//!               "records": ["NR"]
+//!             },
+//!             { "name": "Ola Nordmann", "club": "Fana IL", "status": "DSQ", "dq_code": "SW 7.4" }
+//!           ]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Classes use the same codes as `meetsetup.xml`: "SR", "JR", a birth year or a sport class like
+//! "S8".
+extern crate gregorian;
+extern crate serde;
+extern crate serde_json;
+use self::gregorian::Year;
+use self::serde::Deserialize;
+use crate::meet_setup::{class::Class, event::Event, gender_group::GenderGroup, meet::Meet};
+use crate::points::BaseTimes;
+use crate::swim_time;
+use std::{convert::TryFrom, fmt::Display, fs, path::Path, time::Duration};
+
+mod html;
+mod pdf;
+mod text;
+
+pub use self::html::to_html;
+pub use self::pdf::to_pdf;
+pub use self::text::to_text;
+
+/// Results of a whole meet.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeetResult {
+    pub events: Vec<EventResult>,
+}
+
+/// Results of a single event.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventResult {
+    /// Event number. See `Event::id`.
+    pub event: u32,
+    pub classes: Vec<ClassResult>,
+}
+
+/// Results of athletes competing against each other in one class of an event.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClassResult {
+    pub class: Class,
+    pub results: Vec<SwimResult>,
+}
+
+/// Result of a single athlete or relay team.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwimResult {
+    /// Athlete name or relay team name.
+    pub name: String,
+    pub club: String,
+    #[serde(default, deserialize_with = "birth_year")]
+    pub birth_year: Option<Year>,
+    #[serde(default)]
+    pub nation: Option<String>,
+    /// Gender of the athlete. Points of individual swims in mixed events need it, since there
+    /// are no base times for mixed individual events.
+    #[serde(default)]
+    pub gender: Option<GenderGroup>,
+    /// Final time. Missing for athletes that did not start or finish.
+    #[serde(default, deserialize_with = "option_swim_time")]
+    pub time: Option<Duration>,
+    #[serde(default)]
+    pub status: Status,
+    /// Disqualification code, for example "SW 7.4".
+    #[serde(default)]
+    pub dq_code: Option<String>,
+    /// Intermediate times in the order they were taken.
+    #[serde(default)]
+    pub splits: Vec<Split>,
+    /// World Aquatics points. See `MeetResult::add_points`.
+    #[serde(default)]
+    pub points: Option<u16>,
+    /// Records set with this swim.
+    #[serde(default)]
+    pub records: Vec<RecordFlag>,
+    /// The time waits for referee review, for example because the pads and backup buttons
+    /// disagree. Set by `timing::reconcile::apply`.
+    #[serde(default)]
+    pub review: bool,
+}
+
+/// Intermediate time.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Split {
+    /// Distance swum in meters.
+    pub distance: u16,
+    #[serde(deserialize_with = "swim_time")]
+    pub time: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Status {
+    /// Finished with a valid time.
+    #[default]
+    #[serde(rename = "OK")]
+    Finished,
+    #[serde(rename = "DSQ")]
+    Disqualified,
+    #[serde(rename = "DNS")]
+    DidNotStart,
+    #[serde(rename = "DNF")]
+    DidNotFinish,
+}
+
+/// Kind of record set with a swim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum RecordFlag {
+    #[serde(rename = "WR")]
+    World,
+    #[serde(rename = "ER")]
+    European,
+    #[serde(rename = "NR")]
+    National,
+    #[serde(rename = "NJR")]
+    NationalJunior,
+    #[serde(rename = "DR")]
+    District,
+    #[serde(rename = "MR")]
+    Meet,
+    #[serde(rename = "CR")]
+    Club,
+}
+
+/// Result with its place in the class. Athletes with equal times share the same place.
+#[derive(Debug, Clone, Copy)]
+pub struct Placed<'a> {
+    /// `None` for athletes without a valid time.
+    pub place: Option<usize>,
+    pub result: &'a SwimResult,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    Json(#[from] serde_json::Error),
+    /// Results reference an event number that does not exist in the meet.
+    UnknownEvent(u32),
+    Pdf(#[from] printpdf::Error),
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Json(err) => write!(f, "invalid results file: {err}"),
+                Self::UnknownEvent(event) => {
+                    write!(f, "results for event {event} which is not in the meet")
+                }
+                Self::Pdf(err) => write!(f, "could not create pdf: {err}"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Finished => write!(f, "OK"),
+                Self::Disqualified => write!(f, "DSQ"),
+                Self::DidNotStart => write!(f, "DNS"),
+                Self::DidNotFinish => write!(f, "DNF"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for RecordFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::World => write!(f, "WR"),
+                Self::European => write!(f, "ER"),
+                Self::National => write!(f, "NR"),
+                Self::NationalJunior => write!(f, "NJR"),
+                Self::District => write!(f, "DR"),
+                Self::Meet => write!(f, "MR"),
+                Self::Club => write!(f, "CR"),
+            },
+        }
+    }
+}
+
+fn birth_year<'de, D>(deserializer: D) -> Result<Option<Year>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let year: Option<i16> = Deserialize::deserialize(deserializer)?;
+    Ok(year.map(Year::new))
+}
+
+fn swim_time<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let time: String = Deserialize::deserialize(deserializer)?;
+    swim_time::parse(&time).map_err(serde::de::Error::custom)
+}
+
+fn option_swim_time<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let time: Option<String> = Deserialize::deserialize(deserializer)?;
+    time.filter(|time| !time.is_empty())
+        .map(|time| swim_time::parse(&time).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+impl TryFrom<&str> for MeetResult {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(serde_json::from_str(value)?)
+    }
+}
+
+impl MeetResult {
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid results file.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        Self::try_from(content.as_str())
+    }
+
+    /// Pair every event result with its `Event` in `meet`, in the order of the results.
+    ///
+    /// # Errors
+    /// Returns an error if the results contain an event that is not in `meet`.
+    pub fn with_events<'a>(
+        &'a self,
+        meet: &'a Meet,
+    ) -> Result<Vec<(&'a Event, &'a EventResult)>, Error> {
+        self.events
+            .iter()
+            .map(|event_result| {
+                meet.events
+                    .iter()
+                    .find(|event| event.id == event_result.event)
+                    .map(|event| (event, event_result))
+                    .ok_or(Error::UnknownEvent(event_result.event))
+            })
+            .collect()
+    }
+
+    /// Calculate World Aquatics points for every valid swim that does not have points yet.
+    /// Individual swims use the base times of the gender of the athlete when it is known and
+    /// relays the gender group of the event. Swims without a base time are left without points.
+    ///
+    /// # Errors
+    /// Returns an error if the results contain an event that is not in `meet`.
+    pub fn add_points(&mut self, meet: &Meet, base_times: &BaseTimes) -> Result<(), Error> {
+        for event_result in &mut self.events {
+            let event = meet
+                .events
+                .iter()
+                .find(|event| event.id == event_result.event)
+                .ok_or(Error::UnknownEvent(event_result.event))?;
+
+            let results = event_result
+                .classes
+                .iter_mut()
+                .flat_map(|class| class.results.iter_mut());
+            for result in results {
+                if result.points.is_some() || result.status != Status::Finished {
+                    continue;
+                }
+                let gender_group = result
+                    .gender
+                    .filter(|_| !event.distance.is_team())
+                    .unwrap_or(event.gender_group);
+                result.points = result.time.and_then(|time| {
+                    base_times
+                        .points(
+                            time,
+                            event.distance,
+                            event.style,
+                            gender_group,
+                            event.pool_length,
+                        )
+                        .ok()
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ClassResult {
+    /// Results in result list order: finished swims by place, followed by swims without a
+    /// valid time in the order they are stored.
+    #[must_use]
+    pub fn placed(&self) -> Vec<Placed<'_>> {
+        let mut finished: Vec<&SwimResult> = self
+            .results
+            .iter()
+            .filter(|result| result.status == Status::Finished && result.time.is_some())
+            .collect();
+        finished.sort_by_key(|result| result.time);
+
+        let mut placed: Vec<Placed> = finished
+            .iter()
+            .map(|result| Placed {
+                place: Some(
+                    1 + finished
+                        .iter()
+                        .filter(|other| other.time < result.time)
+                        .count(),
+                ),
+                result,
+            })
+            .collect();
+        placed.extend(
+            self.results
+                .iter()
+                .filter(|result| result.status != Status::Finished || result.time.is_none())
+                .map(|result| Placed {
+                    place: None,
+                    result,
+                }),
+        );
+        placed
+    }
+}
+
+/// Columns printed for every result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Place,
+    Name,
+    BirthYear,
+    Club,
+    Nation,
+    Time,
+    Points,
+    Records,
+}
+
+impl Column {
+    fn for_event(event: &Event, write_country: bool) -> Vec<Self> {
+        let mut columns = vec![Self::Place, Self::Name];
+        if !event.dont_show_age_group && !event.distance.is_team() {
+            columns.push(Self::BirthYear);
+        }
+        columns.push(Self::Club);
+        if write_country {
+            columns.push(Self::Nation);
+        }
+        columns.extend([Self::Time, Self::Points, Self::Records]);
+        columns
+    }
+
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Place => "Place",
+            Self::Name => "Name",
+            Self::BirthYear => "Born",
+            Self::Club => "Club",
+            Self::Nation => "Nation",
+            Self::Time => "Time",
+            Self::Points => "Points",
+            Self::Records => "",
+        }
+    }
+
+    fn cell(self, placed: &Placed) -> String {
+        let result = placed.result;
+        match self {
+            Self::Place => placed
+                .place
+                .map_or_else(String::new, |place| format!("{place}.")),
+            Self::Name => result.name.clone(),
+            Self::BirthYear => result
+                .birth_year
+                .map_or_else(String::new, |year| year.to_string()),
+            Self::Club => result.club.clone(),
+            Self::Nation => result.nation.clone().unwrap_or_default(),
+            Self::Time => match (result.status, result.time) {
+                (Status::Finished, Some(time)) => swim_time::format(time),
+                (status, _) => status.to_string(),
+            },
+            Self::Points => result
+                .points
+                .map_or_else(String::new, |points| points.to_string()),
+            Self::Records => result
+                .records
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// Second line below a result with splits and the disqualification code.
+fn details(result: &SwimResult) -> Option<String> {
+    let mut details: Vec<String> = result
+        .splits
+        .iter()
+        .map(|split| format!("{}m {}", split.distance, swim_time::format(split.time)))
+        .collect();
+    if let Some(dq_code) = &result.dq_code {
+        details.push(dq_code.clone());
+    }
+    if result.review {
+        details.push("under referee review".to_owned());
+    }
+    (!details.is_empty()).then(|| details.join("  "))
+}
+
+/// Class heading. Age classes are left out for events that hide age groups.
+fn class_title(event: &Event, class: Class) -> Option<String> {
+    match class {
+        Class::Handicap(_) => Some(class.to_string()),
+        _ if event.dont_show_age_group => None,
+        _ => Some(capitalize(&class.to_string())),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut characters = text.chars();
+    characters.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(characters).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Corpus meet with a 100m freestyle in a 50m pool as event 1, open to both genders.
+    fn mixed_meet() -> Meet {
+        let xml = include_str!("../../tests/corpus/basic.xml").replacen(
+            "<Sex>FEMALE</Sex>",
+            "<Sex>MIXED</Sex>",
+            1,
+        );
+        Meet::from_str(&xml).expect("valid meet")
+    }
+
+    #[test]
+    fn individual_swims_in_mixed_events_use_the_gender_of_the_athlete() {
+        let meet = mixed_meet();
+        let base_times = BaseTimes::try_from("FEMALE,50,100,FREESTYLE,50.00").expect("base times");
+        let mut results = MeetResult::try_from(
+            r#"{ "events": [{ "event": 1, "classes": [{ "class": "SR", "results": [
+                { "name": "Kari Nordmann", "club": "Bergen", "gender": "FEMALE", "time": "53.00" },
+                { "name": "Ola Nordmann", "club": "Bergen", "time": "53.00" }
+            ]}]}]}"#,
+        )
+        .expect("valid results");
+
+        results
+            .add_points(&meet, &base_times)
+            .expect("known events");
+
+        let points: Vec<Option<u16>> = results.events[0].classes[0]
+            .results
+            .iter()
+            .map(|result| result.points)
+            .collect();
+        assert_eq!(points, [Some(839), None]);
+    }
+}
@@ -0,0 +1,83 @@
+use super::{class_title, details, Column, Error, MeetResult};
+use crate::heat_list::event_title;
+use crate::meet_setup::meet::Meet;
+use crate::printable::{row, Layout, PageSettings, HEADING_SIZE, MARGIN, TEXT_SIZE, TITLE_SIZE};
+
+const fn column_width(column: Column) -> f32 {
+    match column {
+        Column::Place | Column::Records => 12.0,
+        Column::Name => 50.0,
+        Column::BirthYear | Column::Nation | Column::Points => 14.0,
+        Column::Club => 46.0,
+        Column::Time => 18.0,
+    }
+}
+
+/// Render results as an A4 PDF document.
+///
+/// # Errors
+/// Returns an error if the results contain an event that is not in `meet` or if the PDF document
+/// cannot be created.
+pub fn to_pdf(meet: &Meet, results: &MeetResult) -> Result<Vec<u8>, Error> {
+    let config = meet.heat_list_config();
+    let mut layout = Layout::new();
+
+    layout.text(meet.name.clone(), TITLE_SIZE, true);
+    layout.text(
+        format!("{}, {}", meet.location, meet.date),
+        TEXT_SIZE,
+        false,
+    );
+
+    for (event, event_result) in results.with_events(meet)? {
+        let columns = Column::for_event(event, config.write_country);
+
+        layout.space();
+        layout.reserve(5);
+        layout.text(event_title(event, &config), HEADING_SIZE, true);
+        if let Some(sponsor) = event.sponsor.as_ref().filter(|sponsor| !sponsor.is_empty()) {
+            layout.text(sponsor.clone(), TEXT_SIZE, false);
+        }
+
+        for class_result in &event_result.classes {
+            layout.space();
+            layout.reserve(3);
+            if let Some(title) = class_title(event, class_result.class) {
+                layout.text(title, TEXT_SIZE, true);
+            }
+            layout.line(
+                row(columns
+                    .iter()
+                    .map(|&column| (column_width(column), column.header().to_string()))),
+                TEXT_SIZE,
+                true,
+            );
+            for placed in class_result.placed() {
+                let details = details(placed.result);
+                layout.reserve(1 + usize::from(details.is_some()));
+                layout.line(
+                    row(columns
+                        .iter()
+                        .map(|&column| (column_width(column), column.cell(&placed)))),
+                    TEXT_SIZE,
+                    false,
+                );
+                if let Some(details) = details {
+                    layout.line(
+                        vec![(MARGIN + column_width(Column::Place), details)],
+                        TEXT_SIZE - 1.0,
+                        false,
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(layout.render(&PageSettings {
+        title: &meet.name,
+        header: config.header.as_deref(),
+        footer: config.footer.as_deref(),
+        write_date_time: config.write_date_time,
+        page_number: config.page_number,
+    })?)
+}
@@ -0,0 +1,82 @@
+use super::{class_title, details, Column, Error, MeetResult};
+use crate::heat_list::event_title;
+use crate::meet_setup::meet::Meet;
+use crate::printable::printed;
+use std::fmt::Write;
+
+const fn column_width(column: Column) -> usize {
+    match column {
+        Column::Place | Column::BirthYear => 6,
+        Column::Name => 28,
+        Column::Club => 26,
+        Column::Nation | Column::Points => 7,
+        Column::Time => 10,
+        Column::Records => 0,
+    }
+}
+
+fn line(columns: &[Column], cell: impl Fn(Column) -> String) -> String {
+    let mut line = String::new();
+    for &column in columns {
+        let width = column_width(column);
+        let _ = match column {
+            Column::Time | Column::Points => write!(line, "{:>width$} ", cell(column)),
+            _ => write!(line, "{:<width$}", cell(column)),
+        };
+    }
+    line.trim_end().to_string()
+}
+
+/// Render results as a plain text result list.
+///
+/// # Errors
+/// Returns an error if the results contain an event that is not in `meet`.
+pub fn to_text(meet: &Meet, results: &MeetResult) -> Result<String, Error> {
+    let config = meet.heat_list_config();
+    let mut text = String::new();
+
+    if let Some(header) = &config.header {
+        let _ = writeln!(text, "{header}\n");
+    }
+    let _ = writeln!(text, "{}", meet.name);
+    let _ = writeln!(text, "{}, {}", meet.location, meet.date);
+
+    for (event, event_result) in results.with_events(meet)? {
+        let columns = Column::for_event(event, config.write_country);
+        let _ = writeln!(text, "\n{}", event_title(event, &config));
+        if let Some(sponsor) = event.sponsor.as_ref().filter(|sponsor| !sponsor.is_empty()) {
+            let _ = writeln!(text, "{sponsor}");
+        }
+
+        for class_result in &event_result.classes {
+            if let Some(title) = class_title(event, class_result.class) {
+                let _ = writeln!(text, "\n{title}");
+            }
+            let _ = writeln!(
+                text,
+                "{}",
+                line(&columns, |column| column.header().to_string())
+            );
+            for placed in class_result.placed() {
+                let _ = writeln!(text, "{}", line(&columns, |column| column.cell(&placed)));
+                if let Some(details) = details(placed.result) {
+                    let _ = writeln!(
+                        text,
+                        "{:indent$}{details}",
+                        "",
+                        indent = column_width(Column::Place)
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(footer) = &config.footer {
+        let _ = writeln!(text, "\n{footer}");
+    }
+    if config.write_date_time {
+        let _ = writeln!(text, "Printed {}", printed());
+    }
+
+    Ok(text)
+}
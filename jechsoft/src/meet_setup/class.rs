@@ -1,14 +1,14 @@
 extern crate chrono;
-extern crate serde;
 extern crate gregorian;
+extern crate serde;
 
 use crate::meet_setup::class::chrono::Datelike;
 
-use std::convert::TryFrom;
-use std::convert::TryInto;
 use self::chrono::Local;
 use self::gregorian::Year;
 use self::serde::Deserialize;
+use std::convert::TryFrom;
+use std::convert::TryInto;
 
 use super::handicap::Handicap;
 
@@ -92,3 +92,18 @@ impl<'de> Deserialize<'de> for Class {
         }
     }
 }
+
+#[allow(clippy::recursive_format_impl)]
+impl std::fmt::Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Senior => write!(f, "senior"),
+                Self::Junior(None) => write!(f, "junior"),
+                Self::Junior(Some(year)) => write!(f, "junior {year}"),
+                Self::Handicap(handicap) => write!(f, "{handicap}"),
+            },
+        }
+    }
+}
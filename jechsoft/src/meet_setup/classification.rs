@@ -0,0 +1,212 @@
+//! # Classification
+//!
+//! Para swimmers are classified once per style group. A swimmer might for instance be classified
+//! as `S8`, `SB7` and `SM8`, and which of these classes applies depends on the `Style` of the
+//! event. This module selects the right sport class for an event and applies the handicap rules
+//! configured on the `Meet` and the `Event`.
+use super::{
+    event::Event,
+    handicap::{Error as HandicapError, Handicap, StyleGroup},
+    meet::Meet,
+    style::Style,
+};
+use std::{convert::TryFrom, fmt::Display};
+
+/// Sport classes of a single athlete.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Classification {
+    /// `S` class. Used in freestyle, backstroke and butterfly.
+    pub freestyle_backstroke_butterfly: Option<Handicap>,
+    /// `SB` class. Used in breaststroke.
+    pub breaststroke: Option<Handicap>,
+    /// `SM` class. Used in individual medley.
+    pub medley: Option<Handicap>,
+}
+
+/// Outcome of checking a classified athlete against a single event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eligibility {
+    /// Sport class the athlete competes in.
+    pub sport_class: Handicap,
+    /// If true all handicap classes are merged into one single class. See
+    /// `Meet::hc_single_age_group`.
+    pub single_age_group: bool,
+    /// If true the athlete can enroll without a qualification time. See `Meet::no_qual_hc` and
+    /// `Event::no_qualification_for_handicap`.
+    pub qualification_exempt: bool,
+}
+
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum Error {
+    Handicap(#[from] HandicapError),
+    /// The same style group is listed twice.
+    DuplicateStyleGroup(StyleGroup),
+    /// The athlete is not classified for the style group of the event.
+    NotClassified(StyleGroup),
+    /// The event is a relay.
+    Relay,
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Handicap(error) => write!(f, "{error}"),
+                Self::DuplicateStyleGroup(style_group) => {
+                    write!(f, "{} class is listed more than once", style_group.prefix())
+                }
+                Self::NotClassified(style_group) => {
+                    write!(f, "athlete has no {} class", style_group.prefix())
+                }
+                Self::Relay => write!(f, "classification does not apply to relays"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.align().is_some() {
+            f.pad(&self.to_string())
+        } else {
+            let classes: Vec<String> = [
+                self.freestyle_backstroke_butterfly,
+                self.breaststroke,
+                self.medley,
+            ]
+            .into_iter()
+            .flatten()
+            .map(|handicap| handicap.to_string())
+            .collect();
+            write!(f, "{}", classes.join(" "))
+        }
+    }
+}
+
+/// Parses a list of sport classes separated by white space or ','. Example: "S8 SB7 SM8".
+impl TryFrom<&str> for Classification {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut classification = Self::default();
+
+        for sport_class in value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|sport_class| !sport_class.is_empty())
+        {
+            let handicap = Handicap::try_from(sport_class)?;
+            let slot = classification.slot(handicap.style_group);
+            if slot.is_some() {
+                return Err(Error::DuplicateStyleGroup(handicap.style_group));
+            }
+            *slot = Some(handicap);
+        }
+
+        Ok(classification)
+    }
+}
+
+impl Classification {
+    const fn slot(&mut self, style_group: StyleGroup) -> &mut Option<Handicap> {
+        match style_group {
+            StyleGroup::FreestyleBackstrokeButterfly => &mut self.freestyle_backstroke_butterfly,
+            StyleGroup::BreastStroke => &mut self.breaststroke,
+            StyleGroup::Medley => &mut self.medley,
+        }
+    }
+
+    #[must_use]
+    pub const fn get(&self, style_group: StyleGroup) -> Option<Handicap> {
+        match style_group {
+            StyleGroup::FreestyleBackstrokeButterfly => self.freestyle_backstroke_butterfly,
+            StyleGroup::BreastStroke => self.breaststroke,
+            StyleGroup::Medley => self.medley,
+        }
+    }
+
+    /// Sport class that applies to an event swum in `style`.
+    #[must_use]
+    pub fn sport_class(&self, style: Style) -> Option<Handicap> {
+        self.get(StyleGroup::from(style))
+    }
+
+    /// Check in which sport class the athlete competes in `event` and which handicap rules of
+    /// `meet` apply.
+    ///
+    /// # Errors
+    /// Returns an error if the event is a relay or if the athlete is not classified for the style
+    /// group of the event.
+    pub fn eligibility(&self, meet: &Meet, event: &Event) -> Result<Eligibility, Error> {
+        if event.distance.is_team() {
+            return Err(Error::Relay);
+        }
+        let style_group = StyleGroup::from(event.style);
+        let sport_class = self
+            .get(style_group)
+            .ok_or(Error::NotClassified(style_group))?;
+
+        Ok(Eligibility {
+            sport_class,
+            single_age_group: meet.hc_single_age_group,
+            qualification_exempt: meet.no_qual_hc || event.no_qualification_for_handicap,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meet() -> Meet {
+        Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet")
+    }
+
+    #[test]
+    fn try_from_reads_one_class_per_style_group() {
+        let classification = Classification::try_from("S8, SB7 SM8").expect("valid classes");
+        assert_eq!(classification.to_string(), "S8 SB7 SM8");
+        assert!(matches!(
+            Classification::try_from("S8 S9"),
+            Err(Error::DuplicateStyleGroup(
+                StyleGroup::FreestyleBackstrokeButterfly
+            ))
+        ));
+    }
+
+    #[test]
+    fn eligible_in_the_class_of_the_event_style() {
+        let mut meet = meet();
+        meet.hc_single_age_group = true;
+        meet.no_qual_hc = false;
+        let mut event = meet.events[0].clone();
+        event.no_qualification_for_handicap = true;
+        let classification = Classification::try_from("S8 SB7").expect("valid classes");
+
+        let eligibility = classification
+            .eligibility(&meet, &event)
+            .expect("classified for freestyle");
+
+        assert_eq!(eligibility.sport_class.to_string(), "S8");
+        assert!(eligibility.single_age_group);
+        assert!(eligibility.qualification_exempt);
+    }
+
+    #[test]
+    fn not_eligible_without_a_class_for_the_event_style() {
+        let meet = meet();
+        let classification = Classification::try_from("SB7 SM8").expect("valid classes");
+        assert!(matches!(
+            classification.eligibility(&meet, &meet.events[0]),
+            Err(Error::NotClassified(
+                StyleGroup::FreestyleBackstrokeButterfly
+            ))
+        ));
+        assert!(matches!(
+            classification.eligibility(&meet, &meet.events[1]),
+            Err(Error::Relay)
+        ));
+    }
+}
@@ -1,39 +1,156 @@
 extern crate serde;
 use self::serde::Deserialize;
+use super::{class::Class, handicap::Impairment};
 use std::{
     convert::TryFrom,
     fmt::{Display, Formatter},
 };
 
-/// Competition types
+/// Competition types as numbered by the Norwegian Swimming Federation in `CompetitionTypeId`.
+/// Each type comes with the `Rules` of the meet, see `CompetitionType::rules`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum CompetitionType {
-    /// Meet for mentally disabled athletes. See `Class::(style_group: Handicap{StyleGroup::FreestyleBackstrokeButterfly, dissability_type: 14})`
-    /// Open meets don't have limitations on who can join
-    Open = 1,
-    /// District Championship
-    DistrictChampionship = 2,
-    /// Meet for mentally disabled athletes
-    MentallyDisabledMeet = 3,
-    /// International championship
-    International = 5,
-    /// National meet with athletes from other nations
-    NationalMeetWithAthletesFromForeginNations = 4,
-    /// Unofficial meet
-    Unofficial = 6,
-    /// Norwegian Championship
-    NorwegianChampionship = 8,
-    /// Local or regional meet without qualifications for athletes over 9 years of age.
-    RegionalWithoutQualification = 15,
-    /// Regional Age Class Meet / LÅMØ
-    RegionalAgeGroupMeet = 16,
-    // AgeClassChampionship / ÅM
-    // AgeClassChampionship = todo!(),
-    //
-    /// "Krets/Regionstevne"
-    DistrictRegionalMeet = 18,
-
-    NonNorwegianMeet = 19,
+    /// Open meets don't have limitations on who can join. Id 1.
+    Open,
+    /// District Championship. Id 2.
+    DistrictChampionship,
+    /// Meet for athletes with intellectual impairment, sport class S14. Id 3.
+    MentallyDisabledMeet,
+    /// National meet with athletes from other nations. Id 4.
+    NationalMeetWithAthletesFromForeginNations,
+    /// International championship. Id 5.
+    International,
+    /// Unofficial meet. Results are not reported. Id 6.
+    Unofficial,
+    /// Norwegian Championship. Id 8.
+    NorwegianChampionship,
+    /// Local or regional meet without qualifications for athletes over 9 years of age. Id 15.
+    RegionalWithoutQualification,
+    /// Regional Age Class Meet / LÅMØ. Id 16.
+    RegionalAgeGroupMeet,
+    /// "Krets/Regionstevne". Id 18.
+    DistrictRegionalMeet,
+    /// Meet arranged outside of Norway. Id 19.
+    NonNorwegianMeet,
+    /// Id that is not in the catalogue. Gets the rules of an open meet, and `Meet::validate`
+    /// reports it. These ids are deliberately left unknown:
+    ///
+    /// - 7, 9 to 14 and 17 are gaps in the numbering of the federation, no meet files with them
+    ///   have been seen.
+    /// - The age class championship (ÅM) has an id, but it is not known which one. Guessing
+    ///   would give another type the qualification and junior only rules of ÅM, so ÅM meets are
+    ///   treated as open meets until the id is confirmed.
+    /// - 0 and ids above 19 are not used by the federation.
+    Unknown(u8),
+}
+
+/// Rules that follow from the competition type of a meet.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rules {
+    /// Athletes need a qualification time to enter. In other meets qualification times in the
+    /// meet setup are guidelines.
+    pub qualification: bool,
+    /// Classes that can enter the meet.
+    pub classes: Classes,
+    /// What happens with the results.
+    pub reporting: Reporting,
+}
+
+/// Classes that can enter a meet.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Classes {
+    /// Seniors, juniors and para athletes.
+    All,
+    /// Juniors only.
+    Juniors,
+    /// Para athletes with intellectual impairment only.
+    IntellectualImpairment,
+}
+
+/// What happens with the results of a meet.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Reporting {
+    /// Results are reported to the Norwegian Swimming Federation and count for rankings and
+    /// records.
+    Official,
+    /// Results are not reported.
+    Unofficial,
+    /// Results are reported by the foreign organizer.
+    Foreign,
+}
+
+impl CompetitionType {
+    /// `CompetitionTypeId` of the type.
+    #[must_use]
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::Open => 1,
+            Self::DistrictChampionship => 2,
+            Self::MentallyDisabledMeet => 3,
+            Self::NationalMeetWithAthletesFromForeginNations => 4,
+            Self::International => 5,
+            Self::Unofficial => 6,
+            Self::NorwegianChampionship => 8,
+            Self::RegionalWithoutQualification => 15,
+            Self::RegionalAgeGroupMeet => 16,
+            Self::DistrictRegionalMeet => 18,
+            Self::NonNorwegianMeet => 19,
+            Self::Unknown(id) => id,
+        }
+    }
+
+    #[must_use]
+    pub const fn rules(self) -> Rules {
+        const OPEN: Rules = Rules {
+            qualification: false,
+            classes: Classes::All,
+            reporting: Reporting::Official,
+        };
+        const CHAMPIONSHIP: Rules = Rules {
+            qualification: true,
+            ..OPEN
+        };
+
+        match self {
+            Self::Open
+            | Self::DistrictChampionship
+            | Self::NationalMeetWithAthletesFromForeginNations
+            | Self::RegionalWithoutQualification
+            | Self::DistrictRegionalMeet
+            | Self::Unknown(_) => OPEN,
+            Self::International | Self::NorwegianChampionship => CHAMPIONSHIP,
+            Self::RegionalAgeGroupMeet => Rules {
+                classes: Classes::Juniors,
+                ..CHAMPIONSHIP
+            },
+            Self::MentallyDisabledMeet => Rules {
+                classes: Classes::IntellectualImpairment,
+                ..OPEN
+            },
+            Self::Unofficial => Rules {
+                reporting: Reporting::Unofficial,
+                ..OPEN
+            },
+            Self::NonNorwegianMeet => Rules {
+                reporting: Reporting::Foreign,
+                ..OPEN
+            },
+        }
+    }
+}
+
+impl Classes {
+    /// True if athletes in `class` can enter.
+    #[must_use]
+    pub fn allows(self, class: &Class) -> bool {
+        match (self, class) {
+            (Self::All, _) | (Self::Juniors, Class::Junior(_)) => true,
+            (Self::IntellectualImpairment, Class::Handicap(handicap)) => {
+                handicap.impairment().ok() == Some(Impairment::Intellectual)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[allow(clippy::recursive_format_impl)]
@@ -54,11 +171,39 @@ impl Display for CompetitionType {
                     write!(f, "national meet with athletes from foregin nations")
                 }
                 Self::NonNorwegianMeet => write!(f, "non Norwegian meet"),
-                // Self::AgeClassChempionship => write!(f, "age class championship"),
                 Self::RegionalWithoutQualification => {
                     write!(f, "regional without qualifications")
                 }
                 Self::DistrictRegionalMeet => write!(f, "district / regional meet"),
+                Self::Unknown(id) => write!(f, "unknown competition type {id}"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Classes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::All => write!(f, "all classes"),
+                Self::Juniors => write!(f, "juniors"),
+                Self::IntellectualImpairment => write!(f, "intellectual impairment"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Reporting {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Official => write!(f, "official"),
+                Self::Unofficial => write!(f, "unofficial"),
+                Self::Foreign => write!(f, "foreign"),
             },
         }
     }
@@ -66,14 +211,12 @@ impl Display for CompetitionType {
 
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
-    CompetitionTypeIdDoesNotExists,
     Deseriaize(std::num::ParseIntError),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::CompetitionTypeIdDoesNotExists => write!(f, "competiton type id does not exists"),
             Self::Deseriaize(parse_int_error) => {
                 write!(f, "deseriaization error: {parse_int_error}")
             }
@@ -81,24 +224,21 @@ impl Display for Error {
     }
 }
 
-impl TryFrom<u8> for CompetitionType {
-    type Error = Error;
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<u8> for CompetitionType {
+    fn from(value: u8) -> Self {
         match value {
-            1 => Ok(Self::Open),
-            2 => Ok(Self::DistrictChampionship),
-            3 => Ok(Self::MentallyDisabledMeet),
-            4 => Ok(Self::NationalMeetWithAthletesFromForeginNations),
-            5 => Ok(Self::International),
-            6 => Ok(Self::Unofficial),
-            8 => Ok(Self::NorwegianChampionship),
-            15 => Ok(Self::RegionalWithoutQualification),
-            16 => Ok(Self::RegionalAgeGroupMeet),
-            18 => Ok(Self::DistrictRegionalMeet),
-            19 => Ok(Self::NonNorwegianMeet),
-            // ?? => Ok(Self::AgeClassChampionship),
-            _ => Err(Error::CompetitionTypeIdDoesNotExists),
+            1 => Self::Open,
+            2 => Self::DistrictChampionship,
+            3 => Self::MentallyDisabledMeet,
+            4 => Self::NationalMeetWithAthletesFromForeginNations,
+            5 => Self::International,
+            6 => Self::Unofficial,
+            8 => Self::NorwegianChampionship,
+            15 => Self::RegionalWithoutQualification,
+            16 => Self::RegionalAgeGroupMeet,
+            18 => Self::DistrictRegionalMeet,
+            19 => Self::NonNorwegianMeet,
+            id => Self::Unknown(id),
         }
     }
 }
@@ -107,8 +247,8 @@ impl TryFrom<&str> for CompetitionType {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.parse::<u8>() {
-            Ok(number) => Self::try_from(number),
+        match value.trim().parse::<u8>() {
+            Ok(number) => Ok(Self::from(number)),
             Err(parse_int_error) => Err(Error::Deseriaize(parse_int_error)),
         }
     }
@@ -119,13 +259,39 @@ impl<'de> Deserialize<'de> for CompetitionType {
     where
         D: serde::Deserializer<'de>,
     {
-        const EXPECTED_DESERIALIZER_INPUT: [&str; 11] =
-            ["1", "2", "3", "4", "5", "6", "8", "15", "16", "18", "19"];
+        const EXPECTED: &str = "a competition type id between 0 and 255";
 
         let deserialized_value: String = Deserialize::deserialize(deserializer)?;
-        let parse_error: D::Error =
-            serde::de::Error::unknown_variant(&deserialized_value, &EXPECTED_DESERIALIZER_INPUT);
+        Self::try_from(deserialized_value.as_ref()).map_err(|_| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&deserialized_value),
+                &EXPECTED,
+            )
+        })
+    }
+}
 
-        Self::try_from(deserialized_value.as_ref()).map_err(|_| parse_error)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_ids_round_trip() {
+        for id in [1, 2, 3, 4, 5, 6, 8, 15, 16, 18, 19] {
+            let competition_type = CompetitionType::from(id);
+            assert!(!matches!(competition_type, CompetitionType::Unknown(_)));
+            assert_eq!(competition_type.id(), id);
+        }
+    }
+
+    #[test]
+    fn ids_outside_the_catalogue_are_unknown_with_open_rules() {
+        for id in [0, 7, 9, 10, 11, 12, 13, 14, 17, 20, 255] {
+            assert_eq!(CompetitionType::from(id), CompetitionType::Unknown(id));
+            assert_eq!(
+                CompetitionType::from(id).rules(),
+                CompetitionType::Open.rules()
+            );
+        }
     }
 }
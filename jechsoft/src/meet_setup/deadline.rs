@@ -0,0 +1,49 @@
+extern crate chrono;
+extern crate time;
+use self::chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use self::time::Time;
+use std::fmt::{self, Display};
+
+/// What a `Deadline` is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeadlineKind {
+    /// Last day to enter athletes. See `Meet::last_entry_date`.
+    Entry,
+    /// Last moment to withdraw entries from an event.
+    Withdrawal,
+    /// Last moment to submit relay line-ups for an event.
+    Relay,
+}
+
+/// A date by which something has to be sent to the host of a meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deadline {
+    pub kind: DeadlineKind,
+    pub date: NaiveDate,
+    /// Time of day on `date`. `None` means the deadline lasts the whole day.
+    pub time: Option<Time>,
+    /// Events the deadline applies to. Empty for deadlines that apply to the whole meet.
+    pub events: Vec<u32>,
+}
+
+impl Deadline {
+    /// Moment the deadline passes. Deadlines without a time pass at the end of the day.
+    #[must_use]
+    pub fn date_time(&self) -> NaiveDateTime {
+        let time = self
+            .time
+            .and_then(|time| NaiveTime::from_hms_opt(time.hour().into(), time.minute().into(), 0))
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN));
+        self.date.and_time(time)
+    }
+}
+
+impl Display for DeadlineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Entry => "entry",
+            Self::Withdrawal => "withdrawal",
+            Self::Relay => "relay",
+        })
+    }
+}
@@ -0,0 +1,164 @@
+//! Decode `meetsetup.xml` files to UTF-8 text.
+//!
+//! Victoria writes ISO-8859-1, files saved by other tools are UTF-8 with or without BOM, and the
+//! `encoding` in the XML declaration is not always the encoding of the bytes. The declaration is
+//! therefore ignored: bytes that are valid UTF-8 are read as UTF-8, since single byte text with
+//! 'æøå' is practically never valid UTF-8. Everything else is read as Windows-1252, which is
+//! ISO-8859-1 with printable characters like '–' where ISO-8859-1 has control characters.
+//!
+//! Text files without XML declaration, like CSV exports from spreadsheets, are decoded the same
+//! way.
+extern crate encoding;
+use self::encoding::all::{ISO_8859_1, WINDOWS_1252};
+use self::encoding::{DecoderTrap, Encoding};
+use std::ops::Range;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Text of `bytes` without BOM and with an XML declaration that says UTF-8, so it can be parsed
+/// or written to a file as it is.
+#[must_use]
+pub fn to_utf8(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    let text = std::str::from_utf8(bytes).map_or_else(
+        |_| {
+            WINDOWS_1252
+                .decode(bytes, DecoderTrap::Strict)
+                // the five bytes Windows-1252 leaves undefined are control characters in ISO-8859-1
+                .or_else(|_| ISO_8859_1.decode(bytes, DecoderTrap::Replace))
+                .unwrap_or_else(|_| String::from_utf8_lossy(bytes).into_owned())
+        },
+        str::to_string,
+    );
+    declare_utf8(&text)
+}
+
+/// `text` with the `encoding` of its XML declaration replaced by UTF-8. The XML parser decodes
+/// its input according to the declaration, so text that is already decoded has to say UTF-8.
+#[must_use]
+pub fn declare_utf8(text: &str) -> String {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+    let Some(end) = text
+        .strip_prefix("<?xml")
+        .and_then(|declaration| declaration.find("?>"))
+        .map(|end| end + "<?xml".len())
+    else {
+        return text.to_string();
+    };
+    encoding_value(&text[..end]).map_or_else(
+        || text.to_string(),
+        |range| format!("{}UTF-8{}", &text[..range.start], &text[range.end..]),
+    )
+}
+
+/// Byte range of the value of the `encoding` attribute in an XML declaration.
+fn encoding_value(declaration: &str) -> Option<Range<usize>> {
+    let attribute = declaration.find("encoding")? + "encoding".len();
+    let value = declaration[attribute..]
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start();
+    let quote = value
+        .chars()
+        .next()
+        .filter(|quote| *quote == '"' || *quote == '\'')?;
+    let start = declaration.len() - value.len() + 1;
+    let length = declaration[start..].find(quote)?;
+    Some(start..start + length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_is_read_as_it_is() {
+        let text = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Name>Lørdag – økt 1</Name>";
+        assert_eq!(to_utf8(text.as_bytes()), text);
+    }
+
+    #[test]
+    fn utf8_bom_is_removed() {
+        let bytes =
+            b"\xEF\xBB\xBF<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>L\xC3\xB8rdag</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>Lørdag</Name>"
+        );
+    }
+
+    #[test]
+    fn utf8_declared_latin1_is_read_as_utf8() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Name>L\xC3\xB8rdag</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>Lørdag</Name>"
+        );
+    }
+
+    #[test]
+    fn latin1_declared_latin1_is_decoded() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Name>\xC6\xD8\xC5 \xE6\xF8\xE5</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>ÆØÅ æøå</Name>"
+        );
+    }
+
+    #[test]
+    fn latin1_declared_utf8_is_decoded() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>L\xF8rdag</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>Lørdag</Name>"
+        );
+    }
+
+    #[test]
+    fn latin1_without_declaration_is_decoded() {
+        assert_eq!(to_utf8(b"<Name>L\xF8rdag</Name>"), "<Name>Lørdag</Name>");
+        assert_eq!(
+            to_utf8(b"Navn;Klubb\nP\xE5l;\xC5s IL\n"),
+            "Navn;Klubb\nPål;Ås IL\n"
+        );
+    }
+
+    #[test]
+    fn windows1252_is_decoded() {
+        let bytes =
+            b"<?xml version=\"1.0\" encoding=\"Windows-1252\"?><Name>\x93Bl\xE5\x94 \x96 1</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>“Blå” – 1</Name>"
+        );
+    }
+
+    #[test]
+    fn windows1252_declared_latin1_is_decoded_as_windows1252() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Name>\xD8st \x96 Vest</Name>";
+        assert_eq!(
+            to_utf8(bytes),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Name>Øst – Vest</Name>"
+        );
+    }
+
+    #[test]
+    fn bytes_undefined_in_windows1252_are_read_as_latin1() {
+        assert_eq!(to_utf8(b"\xF8\x81"), "ø\u{81}");
+    }
+
+    #[test]
+    fn declaration_with_single_quotes_is_replaced() {
+        assert_eq!(
+            declare_utf8("<?xml version='1.0' encoding = 'ISO-8859-1'?><MeetSetUp/>"),
+            "<?xml version='1.0' encoding = 'UTF-8'?><MeetSetUp/>"
+        );
+    }
+
+    #[test]
+    fn declaration_without_encoding_is_kept() {
+        let text = "<?xml version=\"1.0\"?><MeetSetUp/>";
+        assert_eq!(declare_utf8(text), text);
+        assert_eq!(declare_utf8("\u{FEFF}<MeetSetUp/>"), "<MeetSetUp/>");
+    }
+}
@@ -10,6 +10,7 @@ use self::time::{format_description::FormatItem, macros::format_description, Tim
 use super::{event::Event, session::Session};
 use serde::de::{self, MapAccess, Visitor};
 use std::collections::HashMap;
+use std::ops::Range;
 use std::time::Duration;
 use url::Url;
 
@@ -123,46 +124,76 @@ pub fn time<'de, D>(deserializer: D) -> Result<Time, D::Error>
 where
     D: serde::de::Deserializer<'de>,
 {
-    const EXPECTED: &str = "a 4 char long string containing numbers representing time of day formatted as 24 hour ('hhmm') with leading zeroes. Min value '0000' max value '2359'.";
-    const FORMAT: &[FormatItem] = format_description!("[hour][minute]");
-
     let deserialized_string: String = Deserialize::deserialize(deserializer)?;
 
     if deserialized_string.is_empty() {
         return Err(serde::de::Error::custom(format!(
-            "received an empty string, expected {EXPECTED}"
+            "received an empty string, expected {TIME_EXPECTED}"
         )));
     }
-    let result = Time::parse(&deserialized_string, &FORMAT).map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
+    parse_time::<D::Error>(&deserialized_string)
+}
 
-    Ok(result)
+/// Same as `time` but returns `None` if the parsed string is empty.
+///
+/// # Errors
+/// returns an error if time cannot be parsed as expected
+pub fn option_time<'de, D>(deserializer: D) -> Result<Option<Time>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let deserialized_string: String = Deserialize::deserialize(deserializer)?;
+
+    match deserialized_string.as_str() {
+        "" => Ok(None),
+        time_maybe => parse_time::<D::Error>(time_maybe).map(Some),
+    }
 }
 
+const TIME_EXPECTED: &str = "a 4 char long string containing numbers representing time of day formatted as 24 hour ('hhmm') with leading zeroes. Min value '0000' max value '2359'.";
+
+fn parse_time<E: serde::de::Error>(value: &str) -> Result<Time, E> {
+    const FORMAT: &[FormatItem] = format_description!("[hour][minute]");
+
+    Time::parse(value, &FORMAT).map_err(|err| {
+        E::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &TIME_EXPECTED)
+    })
+}
+
+/// # Returns
+/// Will return `None` if the parsed string is empty.
+///
 /// # Errors
-/// returns an error if parsing string to number fails.
+/// returns an error if the string is not formatted like 'MM:ss:hh'.
 pub fn option_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
     D: serde::de::Deserializer<'de>,
 {
     const EXPECTED: &str = "8 character long string formatted like 'MM:ss:hh' where 'MM' is minutes, 'ss' is seconds and 'hh' is hundredth part of a second. All values has to be present and separated by ':' with leading zero.";
     let s: String = serde::de::Deserialize::deserialize(deserializer)?;
+    if s.is_empty() {
+        return Ok(None);
+    }
 
-    // Assuming all numbers are inclusive 🤞
-    // and fuck these error checks. Can this be somewhat shortened?
-    let minutes = s[0..2].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
-    let seconds = s[4..5].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
-    let milliseconds = &s[7..8].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
+    match (digits(&s, 0..2), digits(&s, 3..5), digits(&s, 6..8)) {
+        (Some(minutes), Some(seconds), Some(hundredths)) => {
+            let duration = minutes * 60 * 1000 + seconds * 1000 + hundredths * 10;
+            Ok(Some(Duration::from_millis(duration)))
+        }
+        _ => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(&s),
+            &EXPECTED,
+        )),
+    }
+}
 
-    let duration = minutes * 60 * 1000 + seconds * 1000 + milliseconds * 100;
-    Ok(Some(Duration::from_millis(duration)))
+/// Number written in `s[range]`. Returns `None` instead of panicking if `range` is outside of
+/// `s` or splits a character, and if the range contains anything but ASCII digits.
+pub(crate) fn digits(s: &str, range: Range<usize>) -> Option<u64> {
+    s.get(range)
+        .filter(|digits| !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()))?
+        .parse()
+        .ok()
 }
 
 /// # Errors
@@ -1,13 +1,13 @@
 use super::individual_distance::Error as IndividualError;
 use super::team_distance::Error as TeamError;
-use super::{individual_distance::Individual, team_distance::Team};
+use super::{individual_distance::Individual, pool_length::PoolLength, team_distance::Team};
 extern crate serde;
 use self::serde::Deserialize;
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
 
 /// Distance in meters.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Distance {
     Individual(Individual),
     Team(Team),
@@ -25,23 +25,50 @@ impl Display for Distance {
         }
     }
 }
+
+impl Distance {
+    /// Returns true if the distance is a relay distance.
+    #[must_use]
+    pub const fn is_team(&self) -> bool {
+        matches!(self, Self::Team(_))
+    }
+
+    /// Total distance in meters. For relays this is the sum of all legs.
+    #[must_use]
+    pub fn meters(&self) -> u32 {
+        match self {
+            Self::Individual(individual) => u32::from(individual.meters()),
+            Self::Team(team) => u32::from(team.legs()) * u32::from(team.leg_length()),
+        }
+    }
+
+    /// Check that the distance can be swum in a pool of `pool_length`.
+    ///
+    /// # Errors
+    /// Returns an error if the individual distance or the relay leg length is not a multiple of
+    /// `pool_length`.
+    pub fn validate(&self, pool_length: PoolLength) -> Result<(), Error> {
+        match self {
+            Self::Individual(individual) => {
+                individual.validate(pool_length).map_err(Error::Individual)
+            }
+            Self::Team(team) => team.validate(pool_length).map_err(Error::Team),
+        }
+    }
+}
+
+/// Relay distances are written as `legs*leg_length` and individual distances as a plain number,
+/// so the '*' character decides which one is parsed.
 impl TryFrom<&str> for Distance {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        // try to return a team distance
-        let team_err = match Team::try_from(value) {
-            Ok(team) => return Ok(Self::Team(team)),
-            Err(err) => err,
-        };
-
-        // try to return an individual distance
-        let individual_err = match Individual::try_from(value) {
-            Ok(individual) => return Ok(Self::Individual(individual)),
-            Err(err) => err,
-        };
-
-        // could not construct distance so return the reason for failure
-        Err(Error::TryFrom(individual_err, team_err))
+        if value.contains('*') {
+            Team::try_from(value).map(Self::Team).map_err(Error::Team)
+        } else {
+            Individual::try_from(value)
+                .map(Self::Individual)
+                .map_err(Error::Individual)
+        }
     }
 }
 
@@ -51,21 +78,15 @@ impl<'de> Deserialize<'de> for Distance {
         D: serde::Deserializer<'de>,
     {
         let deserialized_value: String = Deserialize::deserialize(deserializer)?;
-        let deserialize_error = serde::de::Error::unknown_variant(
-            &deserialized_value,
-            &[
-                "25", "50", "100", "200", "400", "800", "1500", "4*25", "4*50", "6*50", "4*100",
-                "4*200", "4*400",
-            ],
-        );
-        // Note: This might be a fucked up way to do it because some distances might overlap and give false positives.
-        Self::try_from(deserialized_value.as_str()).map_err(|_| deserialize_error)
+        Self::try_from(deserialized_value.as_str())
+            .map_err(|err| serde::de::Error::custom(format!("'{deserialized_value}': {err}")))
     }
 }
 
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
-    TryFrom(IndividualError, TeamError),
+    Individual(IndividualError),
+    Team(TeamError),
 }
 
 #[allow(clippy::recursive_format_impl)]
@@ -74,7 +95,10 @@ impl Display for Error {
         match f.align() {
             Some(_) => f.pad(&self.to_string()),
             None => match self {
-                Self::TryFrom(individual,team) => write!(f, "could not construct distance out of either individual nor team. Error individual: {individual}. Error team: {team}"),
+                Self::Individual(individual) => {
+                    write!(f, "invalid individual distance: {individual}")
+                }
+                Self::Team(team) => write!(f, "invalid relay distance: {team}"),
             },
         }
     }
@@ -1,9 +1,11 @@
 extern crate chrono;
 extern crate gregorian;
 extern crate serde;
+extern crate time;
 use self::chrono::NaiveDate;
 use self::gregorian::Year;
 use self::serde::Deserialize;
+use self::time::Time;
 use super::{
     award::Award, deserializer, distance::Distance, gender_group::GenderGroup,
     pool_length::PoolLength, round::Round, sorting::Sorting, style::Style,
@@ -181,19 +183,35 @@ pub struct Event {
     /// Undocumented field.
     pub lenex_event_order: Option<u16>,
 
-    /// Undocumented field.
-    #[serde(rename = "DEADLINEDATEWITHDRAWALS")]
-    pub deadline_date_withdrawals: Option<u64>, // date
+    /// Last date for withdrawing entries from this event.
+    #[serde(
+        default,
+        rename = "DEADLINEDATEWITHDRAWALS",
+        deserialize_with = "deserializer::option_date"
+    )]
+    pub deadline_date_withdrawals: Option<NaiveDate>,
 
-    /// Undocumented field.
-    #[serde(rename = "DEADLINETIMEWITHDRAWALS")]
-    pub deadline_time_withdrawals: Option<u16>, // four digits time
+    /// Time of day on `deadline_date_withdrawals` when withdrawals close.
+    #[serde(
+        default,
+        rename = "DEADLINETIMEWITHDRAWALS",
+        deserialize_with = "deserializer::option_time"
+    )]
+    pub deadline_time_withdrawals: Option<Time>,
 
-    /// Undocumented field.
-    #[serde(rename = "DEADLINEDATERELAY")]
-    pub deadline_date_relay: Option<u64>, // date
+    /// Last date for submitting relay team line-ups for this event.
+    #[serde(
+        default,
+        rename = "DEADLINEDATERELAY",
+        deserialize_with = "deserializer::option_date"
+    )]
+    pub deadline_date_relay: Option<NaiveDate>,
 
-    /// Undocumented field.
-    #[serde(rename = "DEADLINETIMERELAY")]
-    pub deadline_time_relay: Option<u16>, // four digits 24h format
+    /// Time of day on `deadline_date_relay` when relay line-ups close.
+    #[serde(
+        default,
+        rename = "DEADLINETIMERELAY",
+        deserialize_with = "deserializer::option_time"
+    )]
+    pub deadline_time_relay: Option<Time>,
 }
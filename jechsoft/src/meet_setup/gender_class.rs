@@ -6,7 +6,7 @@ use self::serde::Deserialize;
 use super::{class::Class, gender_group::GenderGroup};
 use std::{convert::TryFrom, fmt::Display, num::ParseIntError};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy)]
 pub struct GenderClass {
     pub gender_group: GenderGroup,
     pub class: Class,
@@ -35,6 +35,27 @@ impl Display for Error {
     }
 }
 
+impl GenderClass {
+    /// Three character code as used in `uni_p.txt`. Gender is written as 'M', 'K' or 'X' followed
+    /// by "SR", "JR" or the last two digits of the birth year. Handicap classes are written as
+    /// seniors.
+    #[must_use]
+    pub fn code(&self) -> String {
+        let gender = match self.gender_group {
+            GenderGroup::Male => 'M',
+            GenderGroup::Female => 'K',
+            GenderGroup::Mixed => 'X',
+        };
+        match self.class {
+            Class::Senior | Class::Handicap(_) => format!("{gender}SR"),
+            Class::Junior(None) => format!("{gender}JR"),
+            Class::Junior(Some(year)) => {
+                format!("{gender}{:02}", year.to_number().rem_euclid(100))
+            }
+        }
+    }
+}
+
 impl TryFrom<&str> for GenderClass {
     type Error = Error;
 
@@ -52,7 +73,7 @@ impl TryFrom<&str> for GenderClass {
                     gender_group: GenderGroup::Male,
                     class: Class::Senior,
                 }),
-                "MJS" => Ok(Self {
+                "MJR" | "MJS" => Ok(Self {
                     gender_group: GenderGroup::Male,
                     class: Class::Junior(None),
                 }),
@@ -75,7 +96,7 @@ impl TryFrom<&str> for GenderClass {
                 input => {
                     // the only valid input is one letter gender character and two letter birth year
 
-                    let class_year: String = input.chars().take(2).collect();
+                    let class_year: String = input.chars().skip(1).collect();
                     let class_year = class_year
                         .parse::<i16>()
                         .map_err(Error::InvalidClassYearStr)?;
@@ -1,8 +1,8 @@
 extern crate serde;
 use self::serde::Deserialize;
-use std::fmt;
+use std::{convert::TryFrom, fmt};
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum GenderGroup {
     /// restrict athletes to male participants only
@@ -14,6 +14,11 @@ pub enum GenderGroup {
     Mixed,
 }
 
+#[derive(Debug, thiserror::Error, Clone, Copy)]
+pub enum Error {
+    GenderGroupDoesNotExists,
+}
+
 #[allow(clippy::recursive_format_impl)]
 impl fmt::Display for GenderGroup {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -27,3 +32,28 @@ impl fmt::Display for GenderGroup {
         }
     }
 }
+
+#[allow(clippy::recursive_format_impl)]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::GenderGroupDoesNotExists => write!(f, "gender group does not exists"),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for GenderGroup {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "MALE" | "male" => Ok(Self::Male),
+            "FEMALE" | "female" => Ok(Self::Female),
+            "MIXED" | "mixed" => Ok(Self::Mixed),
+            _ => Err(Error::GenderGroupDoesNotExists),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 extern crate serde;
 use self::serde::Deserialize;
-use std::{convert::TryFrom, fmt::Display, num::ParseIntError};
+use super::style::{Stroke, Style};
+use std::{convert::TryFrom, fmt::Display, num::ParseIntError, ops::RangeInclusive};
 
-#[derive(Debug, Clone, Copy)]
+/// Para swimming sport class. Written as `S`, `SB` or `SM` followed by the class number, for
+/// example `S8`, `SB7` or `SM8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Handicap {
     /// Range of styles this dissability affects.
     pub style_group: StyleGroup,
@@ -16,7 +19,7 @@ pub struct Handicap {
     pub disability_type: u8,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Hash)]
 pub enum StyleGroup {
     /// Freestyle, Backstroke and Butterfly
     FreestyleBackstrokeButterfly,
@@ -26,10 +29,27 @@ pub enum StyleGroup {
     Medley,
 }
 
+/// Impairment category of a sport class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Impairment {
+    /// Sport classes 1 - 10.
+    Physical,
+    /// Sport classes 11 - 13.
+    Visual,
+    /// Sport class 14.
+    Intellectual,
+    /// Sport class 15.
+    Hearing,
+}
+
+/// Valid sport class numbers.
+pub const DISABILITY_TYPES: RangeInclusive<u8> = 1..=15;
+
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
     ParseIntError(#[from] ParseIntError),
     InvalidHandicapStyleGroup,
+    DisabilityTypeOutOfRange(u8),
 }
 
 #[allow(clippy::recursive_format_impl)]
@@ -38,13 +58,38 @@ impl Display for Handicap {
         if f.align().is_some() {
             f.pad(&self.to_string())
         } else {
-            let result = match self.style_group {
-                StyleGroup::FreestyleBackstrokeButterfly => "S",
-                StyleGroup::BreastStroke => "SB",
-                StyleGroup::Medley => "SM",
-            };
+            write!(f, "{}{}", self.style_group.prefix(), self.disability_type)
+        }
+    }
+}
 
-            write!(f, "{},{}", result, self.disability_type)
+#[allow(clippy::recursive_format_impl)]
+impl Display for StyleGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::FreestyleBackstrokeButterfly => {
+                    write!(f, "freestyle, backstroke and butterfly")
+                }
+                Self::BreastStroke => write!(f, "breaststroke"),
+                Self::Medley => write!(f, "medley"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Impairment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Physical => write!(f, "movement and mobility"),
+                Self::Visual => write!(f, "reduced eye sight or blind"),
+                Self::Intellectual => write!(f, "mental disability"),
+                Self::Hearing => write!(f, "deaf"),
+            },
         }
     }
 }
@@ -57,27 +102,73 @@ impl Display for Error {
             None => match self {
                 Self::ParseIntError(error) => write!(f, "{error}"),
                 Self::InvalidHandicapStyleGroup => write!(f, "invalid style group"),
+                Self::DisabilityTypeOutOfRange(disability_type) => write!(
+                    f,
+                    "sport class {disability_type} is out of range. Expected a number between {} and {}",
+                    DISABILITY_TYPES.start(),
+                    DISABILITY_TYPES.end()
+                ),
             },
         }
     }
 }
 
-impl Handicap {
+impl StyleGroup {
+    /// Para swimming sport class prefix.
     #[must_use]
-    pub fn explain(self) -> String {
-        let disability_type = match self.disability_type {
-            1..=10 => "movement and mobility",
-            11..=13 => "reduced eye sight or blind",
-            14 => "mental disability",
-            15 => "deaf",
-            0 | 16..=u8::MAX => unimplemented!(),
-        };
-        let styles = match self.style_group {
-            StyleGroup::FreestyleBackstrokeButterfly => "freestyle, backstroke and butterfly",
-            StyleGroup::BreastStroke => "breaststroke",
-            StyleGroup::Medley => "medley",
-        };
-        format!("{disability_type} in {styles}")
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::FreestyleBackstrokeButterfly => "S",
+            Self::BreastStroke => "SB",
+            Self::Medley => "SM",
+        }
+    }
+}
+
+/// Sport class that applies to an event with this `Style`. Freestyle, backstroke and butterfly
+/// use the `S` class, breaststroke the `SB` class and individual medley the `SM` class.
+impl From<Style> for StyleGroup {
+    fn from(style: Style) -> Self {
+        match style {
+            Style::Single(Stroke::BreastStroke) => Self::BreastStroke,
+            Style::Single(Stroke::FreeStyle | Stroke::BackStroke | Stroke::Butterfly) => {
+                Self::FreestyleBackstrokeButterfly
+            }
+            Style::Medley(_) | Style::MedleyHC(_) => Self::Medley,
+        }
+    }
+}
+
+impl Handicap {
+    /// # Errors
+    /// Returns an error if `disability_type` is outside of `DISABILITY_TYPES`.
+    pub fn new(style_group: StyleGroup, disability_type: u8) -> Result<Self, Error> {
+        if !DISABILITY_TYPES.contains(&disability_type) {
+            return Err(Error::DisabilityTypeOutOfRange(disability_type));
+        }
+        Ok(Self {
+            style_group,
+            disability_type,
+        })
+    }
+
+    /// # Errors
+    /// Returns an error if `disability_type` is outside of `DISABILITY_TYPES`. This can only
+    /// happen if the struct is constructed without `Handicap::new` or `Handicap::try_from`.
+    pub const fn impairment(self) -> Result<Impairment, Error> {
+        match self.disability_type {
+            1..=10 => Ok(Impairment::Physical),
+            11..=13 => Ok(Impairment::Visual),
+            14 => Ok(Impairment::Intellectual),
+            15 => Ok(Impairment::Hearing),
+            disability_type => Err(Error::DisabilityTypeOutOfRange(disability_type)),
+        }
+    }
+
+    /// # Errors
+    /// See `Handicap::impairment`.
+    pub fn explain(self) -> Result<String, Error> {
+        Ok(format!("{} in {}", self.impairment()?, self.style_group))
     }
 }
 
@@ -85,23 +176,18 @@ impl TryFrom<&str> for Handicap {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let style_group = match value {
-            x if x.starts_with("SB") => StyleGroup::BreastStroke,
-            x if x.starts_with("SM") => StyleGroup::Medley,
-            x if x.starts_with('S') => StyleGroup::FreestyleBackstrokeButterfly,
-            _ => Err(Error::InvalidHandicapStyleGroup)?,
+        let value = value.trim();
+        let (style_group, number) = if let Some(number) = value.strip_prefix("SB") {
+            (StyleGroup::BreastStroke, number)
+        } else if let Some(number) = value.strip_prefix("SM") {
+            (StyleGroup::Medley, number)
+        } else if let Some(number) = value.strip_prefix('S') {
+            (StyleGroup::FreestyleBackstrokeButterfly, number)
+        } else {
+            return Err(Error::InvalidHandicapStyleGroup);
         };
-        let number: String = value
-            .chars()
-            .filter(|character| character.is_numeric())
-            .collect();
-
-        let disability_type = number.parse::<u8>()?;
 
-        Ok(Self {
-            style_group,
-            disability_type,
-        })
+        Self::new(style_group, number.trim().parse::<u8>()?)
     }
 }
 
@@ -118,3 +204,93 @@ impl<'de> Deserialize<'de> for Handicap {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_accepts_sport_classes_1_to_15() {
+        for disability_type in [1, 15] {
+            let handicap = Handicap::new(StyleGroup::Medley, disability_type).expect("in range");
+            assert_eq!(handicap.disability_type, disability_type);
+        }
+        for disability_type in [0, 16] {
+            assert!(matches!(
+                Handicap::new(StyleGroup::Medley, disability_type),
+                Err(Error::DisabilityTypeOutOfRange(number)) if number == disability_type
+            ));
+        }
+    }
+
+    #[test]
+    fn impairment_of_each_class_range() {
+        let impairment = |disability_type| {
+            Handicap::new(StyleGroup::FreestyleBackstrokeButterfly, disability_type)
+                .expect("in range")
+                .impairment()
+                .expect("impairment")
+        };
+        assert_eq!(impairment(1), Impairment::Physical);
+        assert_eq!(impairment(10), Impairment::Physical);
+        assert_eq!(impairment(11), Impairment::Visual);
+        assert_eq!(impairment(13), Impairment::Visual);
+        assert_eq!(impairment(14), Impairment::Intellectual);
+        assert_eq!(impairment(15), Impairment::Hearing);
+    }
+
+    #[test]
+    fn impairment_of_a_class_built_without_new_is_an_error() {
+        let handicap = Handicap {
+            style_group: StyleGroup::BreastStroke,
+            disability_type: 16,
+        };
+        assert!(matches!(
+            handicap.impairment(),
+            Err(Error::DisabilityTypeOutOfRange(16))
+        ));
+        assert!(handicap.explain().is_err());
+    }
+
+    #[test]
+    fn style_group_of_each_style() {
+        let style_group = |style| StyleGroup::from(Style::try_from(style).expect("valid style"));
+        assert_eq!(
+            style_group("FREESTYLE"),
+            StyleGroup::FreestyleBackstrokeButterfly
+        );
+        assert_eq!(
+            style_group("BACKSTROKE"),
+            StyleGroup::FreestyleBackstrokeButterfly
+        );
+        assert_eq!(
+            style_group("BUTTERFLY"),
+            StyleGroup::FreestyleBackstrokeButterfly
+        );
+        assert_eq!(style_group("BREASTSTROKE"), StyleGroup::BreastStroke);
+        assert_eq!(style_group("INDIVIDUALMEDLEY"), StyleGroup::Medley);
+        assert_eq!(style_group("HCMEDLEY"), StyleGroup::Medley);
+    }
+
+    #[test]
+    fn try_from_reads_the_prefix() {
+        let handicap = |value| Handicap::try_from(value).expect("valid sport class");
+        assert_eq!(
+            handicap("S8"),
+            Handicap::new(StyleGroup::FreestyleBackstrokeButterfly, 8).expect("in range")
+        );
+        assert_eq!(
+            handicap("SB7"),
+            Handicap::new(StyleGroup::BreastStroke, 7).expect("in range")
+        );
+        assert_eq!(handicap(" SM14 ").to_string(), "SM14");
+        assert!(matches!(
+            Handicap::try_from("B8"),
+            Err(Error::InvalidHandicapStyleGroup)
+        ));
+        assert!(matches!(
+            Handicap::try_from("S16"),
+            Err(Error::DisabilityTypeOutOfRange(16))
+        ));
+    }
+}
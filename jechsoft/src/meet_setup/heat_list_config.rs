@@ -0,0 +1,46 @@
+/// Presentation settings for heat lists (start lists). Created with `Meet::heat_list_config`.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HeatListConfig {
+    /// Lane number of the first lane. Usually 1, but some 10 lane pools start with 0.
+    pub first_lane: u8,
+
+    /// Number of lanes used in each heat.
+    pub lanes: u8,
+
+    /// Print records for the event above the heats.
+    pub records_in_heat_list: bool,
+
+    /// Print page numbers.
+    pub page_number: bool,
+
+    /// Write "Heat 1 of 3" instead of just the heat number.
+    pub show_heat_text: bool,
+
+    /// Print the scheduled start time of the session.
+    pub show_time_schedule: bool,
+
+    /// Print the scheduled start time only for the first heat of each event.
+    pub show_time_only_heat_one: bool,
+
+    /// Print the event description instead of distance, style and gender group.
+    pub use_group_text: bool,
+
+    /// Add a column with the nation of the athlete.
+    pub write_country: bool,
+
+    /// Add a column for the first lap split.
+    pub write_first_lap: bool,
+
+    /// Print the name of the athlete swimming the first leg of relays.
+    pub write_first_stage: bool,
+
+    /// Print the time the heat list was created.
+    pub write_date_time: bool,
+
+    /// Custom text on top of every page.
+    pub header: Option<String>,
+
+    /// Custom text at the bottom of every page.
+    pub footer: Option<String>,
+}
@@ -2,45 +2,48 @@ extern crate core;
 use self::core::fmt;
 extern crate serde;
 use self::serde::Deserialize;
+use super::pool_length::PoolLength;
 use std::{convert::TryFrom, fmt::Display};
 
-/// Individual distances
-/// TODO: add support for adjustable distance. Distance has to be a multiple of `PoolLength`.
-#[derive(Deserialize, Debug, Copy, Clone)]
-pub enum Individual {
-    #[serde(rename = "25")]
-    Distance25 = 25,
-    #[serde(rename = "50")]
-    Distance50 = 50,
-    #[serde(rename = "100")]
-    Distance100 = 100,
-    #[serde(rename = "150")]
-    Distance150 = 150,
-    #[serde(rename = "200")]
-    Distance200 = 200,
-    #[serde(rename = "400")]
-    Distance400 = 400,
-    #[serde(rename = "800")]
-    Distance800 = 800,
-    #[serde(rename = "1500")]
-    Distance1500 = 1500,
+/// Shortest distance that can be swum. Every distance has to be a multiple of it.
+pub const MIN_DISTANCE: u16 = 25;
+
+/// Individual distance in meters. Any multiple of `MIN_DISTANCE` is a valid distance, the
+/// distance has to be a multiple of the `PoolLength` of the event as well. See
+/// `Individual::validate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Individual(u16);
+
+impl Individual {
+    /// # Errors
+    /// Returns an error if `meters` is zero or not a multiple of `MIN_DISTANCE`.
+    pub const fn new(meters: u16) -> Result<Self, Error> {
+        if meters == 0 || !meters.is_multiple_of(MIN_DISTANCE) {
+            return Err(Error::InvalidDistance(meters));
+        }
+        Ok(Self(meters))
+    }
+
+    #[must_use]
+    pub const fn meters(self) -> u16 {
+        self.0
+    }
+
+    /// # Errors
+    /// Returns an error if the distance cannot be swum in a pool of `pool_length`.
+    pub const fn validate(self, pool_length: PoolLength) -> Result<(), Error> {
+        if !self.0.is_multiple_of(pool_length.meters()) {
+            return Err(Error::NotMultipleOfPoolLength(self.0, pool_length));
+        }
+        Ok(())
+    }
 }
 
-impl TryFrom<isize> for Individual {
+impl TryFrom<u16> for Individual {
     type Error = Error;
 
-    fn try_from(value: isize) -> Result<Self, Self::Error> {
-        match value {
-            25 => Ok(Self::Distance25),
-            50 => Ok(Self::Distance50),
-            100 => Ok(Self::Distance100),
-            150 => Ok(Self::Distance150),
-            200 => Ok(Self::Distance200),
-            400 => Ok(Self::Distance400),
-            800 => Ok(Self::Distance800),
-            1500 => Ok(Self::Distance1500),
-            _ => Err(Error::InvalidDistance),
-        }
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::new(value)
     }
 }
 
@@ -48,27 +51,28 @@ impl TryFrom<&str> for Individual {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parsed_number = value.parse::<isize>().map_err(Error::Convert)?;
+        let parsed_number = value.trim().parse::<u16>().map_err(Error::Convert)?;
 
         Self::try_from(parsed_number)
     }
 }
 
+impl<'de> Deserialize<'de> for Individual {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let deserialized_value: String = Deserialize::deserialize(deserializer)?;
+        Self::try_from(deserialized_value.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
 #[allow(clippy::recursive_format_impl)]
 impl fmt::Display for Individual {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match f.align() {
             Some(_) => f.pad(&self.to_string()),
-            None => match self {
-                Self::Distance25 => write!(f, "25m"),
-                Self::Distance50 => write!(f, "50m"),
-                Self::Distance100 => write!(f, "100m"),
-                Self::Distance150 => write!(f, "150m"),
-                Self::Distance200 => write!(f, "200m"),
-                Self::Distance400 => write!(f, "400m"),
-                Self::Distance800 => write!(f, "800m"),
-                Self::Distance1500 => write!(f, "1500m"),
-            },
+            None => write!(f, "{}m", self.0),
         }
     }
 }
@@ -76,7 +80,8 @@ impl fmt::Display for Individual {
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
     Convert(std::num::ParseIntError),
-    InvalidDistance,
+    InvalidDistance(u16),
+    NotMultipleOfPoolLength(u16, PoolLength),
 }
 
 #[allow(clippy::recursive_format_impl)]
@@ -86,7 +91,14 @@ impl Display for Error {
             Some(_) => f.pad(&self.to_string()),
             None => match self {
                 Self::Convert(parse_error) => write!(f, "conversion error: {parse_error}"),
-                Self::InvalidDistance => write!(f, "distance does not exists"),
+                Self::InvalidDistance(meters) => write!(
+                    f,
+                    "distance {meters}m does not exists. Distance has to be a multiple of {MIN_DISTANCE}m"
+                ),
+                Self::NotMultipleOfPoolLength(meters, pool_length) => write!(
+                    f,
+                    "distance {meters}m is not a multiple of pool length {pool_length}"
+                ),
             },
         }
     }
@@ -12,14 +12,25 @@ use self::reqwest::Url;
 use self::serde::Deserialize;
 use self::serde_email::Email;
 use super::{
-    age_group::DefinedAgeGroups, australian_rank::AustralianRank,
-    australian_world_record::AustralianWorldRecord, award::Award,
-    competition_type::CompetitionType, deserializer, distance::Distance, event::Event,
-    person::Person, pool_category::PoolCategory, qualification_set::QualificationSet,
-    session::Session, touch_pad_set::TouchPadSet,
+    age_group::DefinedAgeGroups,
+    australian_rank::AustralianRank,
+    australian_world_record::AustralianWorldRecord,
+    award::Award,
+    competition_type::{Classes, CompetitionType},
+    deadline::{Deadline, DeadlineKind},
+    decode, deserializer,
+    distance::Distance,
+    event::Event,
+    heat_list_config::HeatListConfig,
+    person::Person,
+    pool_category::PoolCategory,
+    qualification_set::QualificationSet,
+    session::Session,
+    touch_pad_set::TouchPadSet,
+    validation::Issue,
 };
 use std::error::Error;
-use std::io::BufReader;
+use std::io::Read;
 use std::{fs::File, path::Path};
 
 /// `MeetConfig` is a rust structure that represents `meetsetup.xml` file used by Jechsoft Victoria.
@@ -30,9 +41,9 @@ use std::{fs::File, path::Path};
 /// TODOs:
 /// - [ ] Refactor pub products: Vec<(String, Price)>. Look at #[serde(flatten)]
 /// - [ ] Use money package for deserializing currency like [rusty money](https://docs.rs/rusty-money/latest/rusty_money/)
-/// - [ ] Group together configuration for heat list generation
+/// - [x] Group together configuration for heat list generation. See `Meet::heat_list_config`
 /// - [ ] Group together configuration for scheduling
-/// - [ ] Add methods for non data type validation that will pass type validation
+/// - [x] Add methods for non data type validation that will pass type validation. See `Meet::validate`
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename = "MeetSetUp", rename_all = "PascalCase")]
 #[allow(clippy::struct_excessive_bools)]
@@ -202,11 +213,15 @@ pub struct Meet {
     pub competition_type: String,
 
     /// Url to where results can be found.
-    #[serde(default, rename = "ResultWebaddress", deserialize_with="deserializer::url")]
+    #[serde(
+        default,
+        rename = "ResultWebaddress",
+        deserialize_with = "deserializer::url"
+    )]
     pub result_web_address: Option<Url>,
 
     /// homepage
-    #[serde(default, rename = "Homepage", deserialize_with="deserializer::url")]
+    #[serde(default, rename = "Homepage", deserialize_with = "deserializer::url")]
     pub home_page: Option<Url>,
 
     /// enrollment email address
@@ -261,16 +276,15 @@ pub struct Meet {
     pub write_country: bool,
 
     /// A configuration that controls whether records for the event should be printed in the heat
-    /// lists.
-    // TODO: group this setting into heat list congiguration
+    /// lists. See `Meet::heat_list_config`.
     #[serde(rename = "RecordsInHeatlist", deserialize_with = "deserializer::bool")]
     pub records_in_heat_list: bool,
 
     #[serde(default, deserialize_with = "deserializer::option_bool")]
     pub write_first_lap: Option<bool>,
 
-    /// A configuration that controls whether page number should be printed in heat lists.
-    // TODO: group this setting into heat list congiguration
+    /// A configuration that controls whether page number should be printed in heat lists. See
+    /// `Meet::heat_list_config`.
     #[serde(
         rename = "PageNumberInHeatlist",
         deserialize_with = "deserializer::option_bool"
@@ -310,7 +324,6 @@ pub struct Meet {
     // Custom products that are being provided on the meet
     // #[serde(default, deserialize_with = "deserializer::product")]
     // pub products: HashMap<String, u16>,
-
     #[serde(default)]
     pub other_payment1: Option<String>,
 
@@ -402,22 +415,235 @@ pub struct Meet {
 }
 
 impl Meet {
+    /// Read a meet setup file in any of the encodings described in `decode`.
+    ///
     ///#  Errors
     /// returns Error if:
     /// - `local_xml_file` cannot be opened.
     /// - deserialization fails
     pub fn try_from(local_xml_file: &Path) -> Result<Self, Box<dyn Error>> {
-        let file = File::open(local_xml_file)?;
-        let reader = BufReader::new(file);
-        let meet: Self = serde_xml_rs::de::from_reader(reader)?;
+        Self::from_reader(File::open(local_xml_file)?)
+    }
+
+    /// Read a meet setup file from `reader`. The encoding is detected, see `decode`.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails or if the content is not a valid meet setup file.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, Box<dyn Error>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_str(&decode::to_utf8(&bytes))
+    }
+
+    /// Parse a meet setup file that is already decoded. The encoding in the XML declaration is
+    /// ignored.
+    ///
+    /// # Errors
+    /// Returns an error if `xml` is not a valid meet setup file.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(xml: &str) -> Result<Self, Box<dyn Error>> {
+        let mut meet: Self = serde_xml_rs::de::from_str(&decode::declare_utf8(xml))?;
+        meet.resolve_handicap_medleys();
 
         Ok(meet)
     }
 
+    /// Check the meet for inconsistencies that pass type validation, like events in sessions that
+    /// do not exist or entry deadlines after the meet has started. An empty list means that no
+    /// issues were found.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let rules = self.competition_type_id.rules();
+
+        if self.events.is_empty() {
+            issues.push(Issue::NoEvents);
+        }
+        if let (Some(start), Some(end)) = (self.date_start, self.date_end) {
+            if start > end {
+                issues.push(Issue::MeetDates { start, end });
+            }
+        }
+        if self.first_entry_date > self.last_entry_date {
+            issues.push(Issue::EntryDates {
+                first: self.first_entry_date,
+                last: self.last_entry_date,
+            });
+        }
+        if let Some(start) = self.date_start {
+            if self.last_entry_date > start {
+                issues.push(Issue::EntryAfterStart {
+                    last: self.last_entry_date,
+                    start,
+                });
+            }
+        }
+
+        for (index, session) in self.sessions.iter().enumerate() {
+            if self.sessions[..index]
+                .iter()
+                .any(|other| other.id == session.id)
+            {
+                issues.push(Issue::DuplicateSession(session.id));
+            }
+        }
+
+        for (index, event) in self.events.iter().enumerate() {
+            if self.events[..index]
+                .iter()
+                .any(|other| other.id == event.id)
+            {
+                issues.push(Issue::DuplicateEvent(event.id));
+            }
+            if let (Some(start), Some(end)) = (self.date_start, self.date_end) {
+                if event.date < start || event.date > end {
+                    issues.push(Issue::EventOutsideMeet {
+                        event: event.id,
+                        date: event.date,
+                    });
+                }
+            }
+            if let Some(session_id) = event.ses_id {
+                match self
+                    .sessions
+                    .iter()
+                    .find(|session| session.id == session_id)
+                {
+                    None => issues.push(Issue::UnknownSession {
+                        event: event.id,
+                        session: session_id,
+                    }),
+                    Some(session) if session.date != event.date => {
+                        issues.push(Issue::SessionDate {
+                            event: event.id,
+                            session: session_id,
+                        });
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let (Some(youngest), Some(oldest)) = (event.youngest, event.oldest) {
+                if oldest.to_number() > youngest.to_number() {
+                    issues.push(Issue::AgeRange(event.id));
+                }
+            }
+            if event.distance.validate(event.pool_length).is_err() {
+                issues.push(Issue::PoolLength {
+                    event: event.id,
+                    distance: event.distance,
+                    pool_length: event.pool_length,
+                });
+            }
+            if rules.classes == Classes::Juniors && event.senior {
+                issues.push(Issue::SeniorsNotAllowed(event.id));
+            }
+        }
+
+        if let CompetitionType::Unknown(id) = self.competition_type_id {
+            issues.push(Issue::UnknownCompetitionType(id));
+        }
+        let has_qualification_times = self.qualification_set.is_some()
+            || self.events.iter().any(|event| {
+                event.qualification_time_long_course.is_some()
+                    || event.qualification_time_short_course.is_some()
+            });
+        if rules.qualification && !has_qualification_times {
+            issues.push(Issue::NoQualificationTimes(self.competition_type_id));
+        }
+
+        issues
+    }
+
+    /// Individual medley and handicap medley share the same value in `meetsetup.xml`. Replace
+    /// individual medleys that are handicap medleys with `Style::MedleyHC`.
+    fn resolve_handicap_medleys(&mut self) {
+        for event in &mut self.events {
+            let style = event.style;
+            event.style = style.resolve_handicap_medley(event.distance, &event.description, None);
+        }
+
+        if let Some(qualification_set) = &mut self.qualification_set {
+            for qualification in &mut qualification_set.qualifications {
+                qualification.style = qualification.style.resolve_handicap_medley(
+                    qualification.distance,
+                    "",
+                    Some(&qualification.athlete_class),
+                );
+            }
+        }
+    }
+
+    /// Heat list settings grouped together. The settings stay flat on `Meet` as well because
+    /// `#[serde(flatten)]` does not work together with `#[serde(deny_unknown_fields)]`.
+    #[must_use]
+    pub fn heat_list_config(&self) -> HeatListConfig {
+        HeatListConfig {
+            first_lane: self.start_with_lane.unwrap_or(1),
+            lanes: self.lanes,
+            records_in_heat_list: self.records_in_heat_list,
+            page_number: self.page_number_in_heat_list.unwrap_or(false),
+            show_heat_text: self.show_heat_text,
+            show_time_schedule: self.show_time_schedule,
+            show_time_only_heat_one: self.show_time_only_heat_one,
+            use_group_text: self.use_group_text,
+            write_country: self.write_country,
+            write_first_lap: self.write_first_lap.unwrap_or(false),
+            write_first_stage: self.write_first_stage.unwrap_or(false),
+            write_date_time: self.write_date_time,
+            header: self.header.clone().filter(|header| !header.is_empty()),
+            footer: self.footer.clone().filter(|footer| !footer.is_empty()),
+        }
+    }
+
+    /// Entry deadline of the meet together with withdrawal and relay deadlines of all events,
+    /// sorted by date and time. Events sharing the same deadline are merged into one `Deadline`.
+    #[must_use]
+    pub fn deadlines(&self) -> Vec<Deadline> {
+        let mut deadlines = vec![Deadline {
+            kind: DeadlineKind::Entry,
+            date: self.last_entry_date,
+            time: None,
+            events: Vec::new(),
+        }];
+
+        for event in &self.events {
+            let event_deadlines = [
+                (
+                    DeadlineKind::Withdrawal,
+                    event.deadline_date_withdrawals,
+                    event.deadline_time_withdrawals,
+                ),
+                (
+                    DeadlineKind::Relay,
+                    event.deadline_date_relay,
+                    event.deadline_time_relay,
+                ),
+            ];
+            for (kind, date, time) in event_deadlines {
+                let Some(date) = date else {
+                    continue;
+                };
+                match deadlines.iter_mut().find(|deadline| {
+                    deadline.kind == kind && deadline.date == date && deadline.time == time
+                }) {
+                    Some(deadline) => deadline.events.push(event.id),
+                    None => deadlines.push(Deadline {
+                        kind,
+                        date,
+                        time,
+                        events: vec![event.id],
+                    }),
+                }
+            }
+        }
+
+        deadlines.sort_by_key(|deadline| (deadline.date_time(), deadline.kind));
+        deadlines
+    }
+
     // Here we assume that we get the exactly the same name as `MeetInfo::get_filename(&self)`
     #[must_use]
     pub fn get_filename(&self) -> Option<String> {
         self.nsf_meet_id.map(|id| format!("{id:0<11}"))
-
     }
 }
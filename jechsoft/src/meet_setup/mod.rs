@@ -4,24 +4,30 @@ mod australian_rank;
 mod australian_world_record;
 mod award;
 pub mod class;
-mod competition_type;
+pub mod classification;
+pub mod competition_type;
+pub mod deadline;
+pub mod decode;
 pub mod deserializer;
 pub mod distance;
 pub mod event;
 pub mod gender_class;
 pub mod gender_group;
-mod handicap;
-mod individual_distance;
+pub mod handicap;
+pub mod heat_list_config;
+pub mod individual_distance;
 mod junior;
 pub mod meet;
-mod person;
+pub mod person;
 mod pool_category;
 pub mod pool_length;
-mod qualification;
-mod qualification_set;
+pub mod qualification;
+pub mod qualification_set;
 mod round;
-mod session;
+pub mod scrub;
+pub mod session;
 mod sorting;
 pub mod style;
-mod team_distance;
-mod touch_pad_set;
+pub mod team_distance;
+pub mod touch_pad_set;
+pub mod validation;
@@ -3,7 +3,7 @@ use self::serde::Deserialize;
 use std::{convert::TryFrom, fmt::Display};
 
 /// Length of the pool
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PoolLength {
     /// 25 meters pool. Often called "short course".
     #[serde(rename = "25")]
@@ -19,6 +19,16 @@ pub enum Error {
     TryFrom(std::num::ParseIntError),
 }
 
+impl PoolLength {
+    #[must_use]
+    pub const fn meters(self) -> u16 {
+        match self {
+            Self::PoolLength25 => 25,
+            Self::PoolLength50 => 50,
+        }
+    }
+}
+
 impl TryFrom<u8> for PoolLength {
     type Error = Error;
 
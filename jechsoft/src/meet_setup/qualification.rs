@@ -1,6 +1,6 @@
 use super::{
-    class::Class, distance::Distance, gender_group::GenderGroup, pool_length::PoolLength,
-    style::Style,
+    class::Class, deserializer::digits, distance::Distance, gender_group::GenderGroup,
+    pool_length::PoolLength, style::Style,
 };
 extern crate serde;
 use self::serde::Deserialize;
@@ -38,6 +38,9 @@ pub struct Qualification {
 
 /// Deserializer for Qualification structure. We need to use a custom deserializer because
 /// `Duration` is not defined in this crate.
+///
+/// # Errors
+/// Returns an error if the minutes, seconds or hundredths cannot be parsed.
 pub fn duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: serde::de::Deserializer<'de>,
@@ -45,18 +48,13 @@ where
     const EXPECTED: &str = "6 character long string formatted like 'MMsshh' where 'MM' is minutes, 'ss' is seconds and 'hh' is hundredth part of a second. All values has leading zero.";
     let s: String = serde::de::Deserialize::deserialize(deserializer)?;
 
-    // Assuming all numbers are inclusive 🤞
-    // and fuck these error checks. Can this be somewhat shortened?
-    let minutes = s[0..2].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
-    let seconds = s[3..4].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
-    let milliseconds = &s[5..6].parse::<u64>().map_err(|err| -> D::Error {
-        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&err.to_string()), &EXPECTED)
-    })?;
-
-    let duration = minutes * 60 * 1000 + seconds * 1000 + milliseconds * 100;
-    Ok(Duration::from_millis(duration))
+    match (digits(&s, 0..2), digits(&s, 2..4), digits(&s, 4..6)) {
+        (Some(minutes), Some(seconds), Some(hundredths)) => Ok(Duration::from_millis(
+            minutes * 60 * 1000 + seconds * 1000 + hundredths * 10,
+        )),
+        _ => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(&s),
+            &EXPECTED,
+        )),
+    }
 }
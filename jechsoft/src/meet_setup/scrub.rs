@@ -0,0 +1,59 @@
+//! Remove personal data from meet setup files before they are shared, for example as test
+//! fixtures in `tests/corpus`.
+
+/// Elements with personal data and the text they are replaced with.
+const PERSONAL: &[(&str, &str)] = &[
+    ("FirstName", "Ola"),
+    ("LastName", "Nordmann"),
+    ("EntryEmail", "entries@example.com"),
+    ("EntryMail", "entries@example.com"),
+    ("MailPameldinger", "entries@example.com"),
+    ("PayAccount", "1234.56.78903"),
+    ("Email", "contact@example.com"),
+    ("Phone", "12345678"),
+    ("Mobile", "12345678"),
+    ("Info", "Informasjon"),
+    ("Header", "Topptekst"),
+    ("Footer", "Bunntekst"),
+];
+
+/// Replace the text of elements with personal data in `xml` with placeholders.
+///
+/// The file is processed as bytes and only the replaced text changes, so the encoding, whitespace
+/// and every other quirk of the file survives. Empty elements stay empty. Names of clubs, places
+/// and the meet are not personal data and are kept.
+#[must_use]
+pub fn scrub(xml: &[u8]) -> Vec<u8> {
+    let mut scrubbed = Vec::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = rest.iter().position(|&byte| byte == b'<') {
+        scrubbed.extend_from_slice(&rest[..start]);
+        rest = &rest[start..];
+
+        let replaced = PERSONAL.iter().find_map(|(tag, replacement)| {
+            let open = format!("<{tag}>");
+            let close = format!("</{tag}>");
+            let text = rest.strip_prefix(open.as_bytes())?;
+            let end = text
+                .windows(close.len())
+                .position(|window| window == close.as_bytes())?;
+            Some((open, end, close, replacement))
+        });
+        if let Some((open, end, close, replacement)) = replaced {
+            scrubbed.extend_from_slice(open.as_bytes());
+            if !rest[open.len()..open.len() + end]
+                .iter()
+                .all(u8::is_ascii_whitespace)
+            {
+                scrubbed.extend_from_slice(replacement.as_bytes());
+            }
+            scrubbed.extend_from_slice(close.as_bytes());
+            rest = &rest[open.len() + end + close.len()..];
+        } else {
+            scrubbed.push(b'<');
+            rest = &rest[1..];
+        }
+    }
+    scrubbed.extend_from_slice(rest);
+    scrubbed
+}
@@ -1,5 +1,6 @@
 extern crate serde;
 use self::serde::Deserialize;
+use super::{class::Class, distance::Distance, handicap::StyleGroup};
 use std::{
     convert::TryFrom,
     fmt::{self, Display},
@@ -7,15 +8,16 @@ use std::{
 
 /// `Style` of the `Event`. A wrapper for `Stroke` to account for `Medley`
 /// which is a list of `Stroke`s.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Style {
     /// Single stroke.
     Single(Stroke),
     /// Four styles combined in one event. See `INDIVIDUAL_MEDLEY` and `TEAM_MEDLEY`.
     Medley([Stroke; 4]),
-    // Cannot deserialize following structure because it is indistinguishable from individual medley.
-    // Three styles combined in one event. Only valid for some HC groups. `INDIVIDUAL_MEDLEY_HC`.
-    // MedleyHC([Stroke; 3]),
+    /// Three styles combined in one event. Only valid for some HC groups. `INDIVIDUAL_MEDLEY_HC`.
+    /// This style is indistinguishable from individual medley in `meetsetup.xml` and has to be
+    /// inferred with `Style::resolve_handicap_medley`.
+    MedleyHC([Stroke; 3]),
 }
 
 impl<'de> Deserialize<'de> for Style {
@@ -35,6 +37,7 @@ impl<'de> Deserialize<'de> for Style {
                     "BREASTSTROKE",
                     "INDIVIDUALMEDLEY",
                     "MEDLEYRELAY",
+                    "HCMEDLEY",
                 ],
             )
         })
@@ -52,13 +55,115 @@ impl TryFrom<&str> for Style {
             "BREASTSTROKE" | "BR" => Ok(Self::Single(Stroke::BreastStroke)),
             "INDIVIDUALMEDLEY" | "IM" => Ok(Self::Medley(INDIVIDUAL_MEDLEY)), // this string is present for handicapped individual medley relays as well as regular medley relays
             "MEDLEYRELAY" | "LM" => Ok(Self::Medley(TEAM_MEDLEY)),
+            "HCMEDLEY" => Ok(Self::MedleyHC(INDIVIDUAL_MEDLEY_HC)),
             _ => Err(Error::StyleDoesNotExists),
         }
     }
 }
 
-// pub const INDIVIDUAL_MEDLEY_HC: [Stroke; 3] =
-//     [Stroke::BackStroke, Stroke::BreastStroke, Stroke::FreeStyle];
+impl Style {
+    /// Style as written in `meetsetup.xml` and in the roster, base time and record files of this
+    /// crate. `meetsetup.xml` has no code for the handicap medley, so it is written as "HCMEDLEY"
+    /// which only this crate reads. Files for other tools keep it out: `uni_p.txt` writes it as
+    /// an individual medley. Lenex has no stroke for it either and is only imported, see
+    /// `Records::from_lenex`, where medleys are resolved with `Style::resolve_handicap_medley`.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Single(Stroke::FreeStyle) => "FREESTYLE",
+            Self::Single(Stroke::Butterfly) => "BUTTERFLY",
+            Self::Single(Stroke::BackStroke) => "BACKSTROKE",
+            Self::Single(Stroke::BreastStroke) => "BREASTSTROKE",
+            Self::Medley(TEAM_MEDLEY) => "MEDLEYRELAY",
+            Self::Medley(_) => "INDIVIDUALMEDLEY",
+            Self::MedleyHC(_) => "HCMEDLEY",
+        }
+    }
+
+    /// `meetsetup.xml` uses `INDIVIDUALMEDLEY` for both the regular individual medley and the
+    /// three stroke handicap medley swum by SM1 - SM4. This returns `Style::MedleyHC` if an
+    /// individual medley is a handicap medley and `self` otherwise.
+    ///
+    /// An individual medley is a handicap medley if:
+    /// - the distance is 75m or 150m which cannot be split into four equal legs, or
+    /// - `class` is one of SM1 - SM4, or
+    /// - `description` mentions one of SM1 - SM4 and no other medley class.
+    #[must_use]
+    pub fn resolve_handicap_medley(
+        self,
+        distance: Distance,
+        description: &str,
+        class: Option<&Class>,
+    ) -> Self {
+        if self != Self::Medley(INDIVIDUAL_MEDLEY) {
+            return self;
+        }
+
+        let three_legged_distance = matches!(
+            distance,
+            Distance::Individual(individual) if THREE_LEGGED_DISTANCES.contains(&individual.meters())
+        );
+        let handicap_class = match class {
+            Some(Class::Handicap(handicap)) => {
+                handicap.style_group == StyleGroup::Medley
+                    && SM_HANDICAP_MEDLEY.contains(&handicap.disability_type)
+            }
+            _ => false,
+        };
+        let medley_classes = medley_classes(description);
+        let handicap_description = !medley_classes.is_empty()
+            && medley_classes
+                .iter()
+                .all(|class| SM_HANDICAP_MEDLEY.contains(class));
+
+        if three_legged_distance || handicap_class || handicap_description {
+            Self::MedleyHC(INDIVIDUAL_MEDLEY_HC)
+        } else {
+            self
+        }
+    }
+}
+
+/// Individual medley distances that can only be swum as a three stroke medley.
+const THREE_LEGGED_DISTANCES: [u16; 2] = [75, 150];
+
+/// `SM` classes that swim `INDIVIDUAL_MEDLEY_HC` instead of `INDIVIDUAL_MEDLEY`.
+pub const SM_HANDICAP_MEDLEY: std::ops::RangeInclusive<u8> = 1..=4;
+
+/// Extract the numbers of all `SM` classes mentioned in `description`. "SM1-SM4" and "SM1-4" are
+/// both read as a range.
+fn medley_classes(description: &str) -> Vec<u8> {
+    let mut classes = Vec::new();
+    let mut rest = description;
+
+    while let Some(index) = rest.find("SM") {
+        rest = &rest[index + 2..];
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        let Ok(from) = digits.parse::<u8>() else {
+            continue;
+        };
+        rest = &rest[digits.len()..];
+
+        let range = rest
+            .strip_prefix('-')
+            .map(|range| range.strip_prefix("SM").unwrap_or(range));
+        let upper = range.and_then(|range| {
+            let digits: String = range.chars().take_while(char::is_ascii_digit).collect();
+            let to = digits.parse::<u8>().ok()?;
+            Some((to, &range[digits.len()..]))
+        });
+
+        match upper {
+            Some((to, after)) if to >= from => {
+                classes.extend(from..=to);
+                rest = after;
+            }
+            _ => classes.push(from),
+        }
+    }
+
+    classes
+}
 
 #[allow(clippy::recursive_format_impl)]
 impl fmt::Display for Style {
@@ -70,16 +175,16 @@ impl fmt::Display for Style {
                     INDIVIDUAL_MEDLEY => write!(f, "individual medley"),
                     _ => Err(std::fmt::Error),
                 },
+                Self::MedleyHC(medley_hc) => match *medley_hc {
+                    INDIVIDUAL_MEDLEY_HC => write!(f, "handicap medley"),
+                    _ => Err(std::fmt::Error),
+                },
                 Self::Single(a) => match *a {
                     Stroke::BackStroke => write!(f, "backstroke"),
                     Stroke::FreeStyle => write!(f, "freestyle"),
                     Stroke::Butterfly => write!(f, "butterfly"),
                     Stroke::BreastStroke => write!(f, "breaststroke"),
                 },
-                // Style::MedleyHC(medley_hc) => match *medley_hc {
-                //     INDIVIDUAL_MEDLEY_HC => write!(f, "handicap medley"),
-                //     _ => Err(fmt::Error::custom("Not a valid handicap medley order")),
-                // },
             },
             Some(_) => f.pad(&self.to_string()),
         }
@@ -89,7 +194,7 @@ impl fmt::Display for Style {
 /// Stroke
 #[allow(clippy::enum_variant_names)]
 #[allow(clippy::module_name_repetitions)]
-#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Stroke {
     /// Backstroke.
@@ -110,6 +215,10 @@ pub const INDIVIDUAL_MEDLEY: [Stroke; 4] = [
     Stroke::FreeStyle,
 ];
 
+/// Individual medley for handicap classes SM1 - SM4. Butterfly is left out.
+pub const INDIVIDUAL_MEDLEY_HC: [Stroke; 3] =
+    [Stroke::BackStroke, Stroke::BreastStroke, Stroke::FreeStyle];
+
 /// Team medley
 pub const TEAM_MEDLEY: [Stroke; 4] = [
     Stroke::BackStroke,
@@ -134,3 +243,100 @@ impl Display for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meet_setup::handicap::Handicap;
+
+    const MEDLEY: Style = Style::Medley(INDIVIDUAL_MEDLEY);
+    const HANDICAP_MEDLEY: Style = Style::MedleyHC(INDIVIDUAL_MEDLEY_HC);
+
+    fn distance(meters: &str) -> Distance {
+        Distance::try_from(meters).expect("valid distance")
+    }
+
+    fn class(sport_class: &str) -> Class {
+        Class::Handicap(Handicap::try_from(sport_class).expect("valid sport class"))
+    }
+
+    #[test]
+    fn three_legged_distances_are_handicap_medleys() {
+        assert_eq!(
+            MEDLEY.resolve_handicap_medley(distance("75"), "", None),
+            HANDICAP_MEDLEY
+        );
+        assert_eq!(
+            MEDLEY.resolve_handicap_medley(distance("150"), "", None),
+            HANDICAP_MEDLEY
+        );
+        assert_eq!(
+            MEDLEY.resolve_handicap_medley(distance("200"), "", None),
+            MEDLEY
+        );
+    }
+
+    #[test]
+    fn sm1_to_sm4_swim_the_handicap_medley() {
+        let resolve = |sport_class| {
+            MEDLEY.resolve_handicap_medley(distance("200"), "", Some(&class(sport_class)))
+        };
+        assert_eq!(resolve("SM1"), HANDICAP_MEDLEY);
+        assert_eq!(resolve("SM4"), HANDICAP_MEDLEY);
+        assert_eq!(resolve("SM5"), MEDLEY);
+        assert_eq!(resolve("S3"), MEDLEY);
+        assert_eq!(
+            MEDLEY.resolve_handicap_medley(distance("200"), "", Some(&Class::Senior)),
+            MEDLEY
+        );
+    }
+
+    #[test]
+    fn description_with_only_sm1_to_sm4_is_a_handicap_medley() {
+        let resolve =
+            |description| MEDLEY.resolve_handicap_medley(distance("200"), description, None);
+        assert_eq!(resolve("200m medley SM1-SM4"), HANDICAP_MEDLEY);
+        assert_eq!(resolve("200m medley SM1-4"), HANDICAP_MEDLEY);
+        assert_eq!(resolve("200m medley SM3"), HANDICAP_MEDLEY);
+        assert_eq!(resolve("200m medley SM3, SM8"), MEDLEY);
+        assert_eq!(resolve("200m medley SM1-SM10"), MEDLEY);
+        assert_eq!(resolve("200m medley"), MEDLEY);
+    }
+
+    #[test]
+    fn other_styles_are_left_alone() {
+        for style in [
+            Style::Single(Stroke::FreeStyle),
+            Style::Medley(TEAM_MEDLEY),
+            HANDICAP_MEDLEY,
+        ] {
+            assert_eq!(
+                style.resolve_handicap_medley(distance("150"), "SM3", Some(&class("SM3"))),
+                style
+            );
+        }
+    }
+
+    #[test]
+    fn medley_classes_reads_single_classes_and_ranges() {
+        assert_eq!(medley_classes("SM1-SM4"), [1, 2, 3, 4]);
+        assert_eq!(medley_classes("SM2-3 og SM9"), [2, 3, 9]);
+        assert_eq!(medley_classes("SM5-SM3"), [5, 3]);
+        assert!(medley_classes("SMx S4").is_empty());
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for style in [
+            Style::Single(Stroke::FreeStyle),
+            Style::Single(Stroke::Butterfly),
+            Style::Single(Stroke::BackStroke),
+            Style::Single(Stroke::BreastStroke),
+            MEDLEY,
+            Style::Medley(TEAM_MEDLEY),
+            HANDICAP_MEDLEY,
+        ] {
+            assert_eq!(Style::try_from(style.code()).expect("valid code"), style);
+        }
+    }
+}
@@ -1,17 +1,54 @@
 extern crate serde;
 use self::serde::Deserialize;
+use super::{individual_distance::MIN_DISTANCE, pool_length::PoolLength};
 use std::{
-    convert::{TryFrom, TryInto},
+    convert::TryFrom,
     fmt::{self, Display},
 };
 
 /// Team distances
-/// constructed from number of laps as the first argument and distance in the second argument.
-/// Total distance is number of laps multiplied by distance per lap
-#[derive(Debug, Clone, Copy)]
-pub enum Team {
-    /// Distance(laps,Distance)
-    Distance(u8, u16),
+/// constructed from number of legs as the first argument and distance per leg in the second
+/// argument. Total distance is number of legs multiplied by distance per leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Team {
+    legs: u8,
+    leg_length: u16,
+}
+
+impl Team {
+    /// # Errors
+    /// Returns an error if there are less than two legs or if `leg_length` is zero or not a
+    /// multiple of `MIN_DISTANCE`.
+    pub const fn new(legs: u8, leg_length: u16) -> Result<Self, Error> {
+        if legs < 2 {
+            return Err(Error::InvalidLegCount(legs));
+        }
+        if leg_length == 0 || !leg_length.is_multiple_of(MIN_DISTANCE) {
+            return Err(Error::InvalidLegLength(leg_length));
+        }
+        Ok(Self { legs, leg_length })
+    }
+
+    /// Number of athletes in the relay.
+    #[must_use]
+    pub const fn legs(self) -> u8 {
+        self.legs
+    }
+
+    /// Distance swum by each athlete.
+    #[must_use]
+    pub const fn leg_length(self) -> u16 {
+        self.leg_length
+    }
+
+    /// # Errors
+    /// Returns an error if a leg cannot be swum in a pool of `pool_length`.
+    pub const fn validate(self, pool_length: PoolLength) -> Result<(), Error> {
+        if !self.leg_length.is_multiple_of(pool_length.meters()) {
+            return Err(Error::NotMultipleOfPoolLength(self.leg_length, pool_length));
+        }
+        Ok(())
+    }
 }
 
 impl<'de> Deserialize<'de> for Team {
@@ -19,67 +56,31 @@ impl<'de> Deserialize<'de> for Team {
     where
         D: serde::Deserializer<'de>,
     {
-        let expected = "num_laps*total_distance. two numbers separated by a '*' character. example: '2*50', '20*50', '4'100'";
         let value: String = serde::de::Deserialize::deserialize(deserializer)?;
-        let unexpected = serde::de::Unexpected::Str(value.as_ref());
-        let parse_error: D::Error = serde::de::Error::invalid_value(unexpected, &expected);
-
-        // extract two numbers separated by '*' character
-        let parsed_numbers = value
-            .split('*')
-            .filter_map(|maybe_number| maybe_number.parse::<u16>().ok())
-            .array_chunks::<2>()
-            .next();
-
-        match parsed_numbers {
-            Some([num_laps, total_distance]) if num_laps % 2 == 0 && total_distance % 25 == 0 => {
-                Ok(Self::Distance(
-                    num_laps.try_into().map_err(|_| parse_error)?,
-                    total_distance,
-                ))
-            },
-            Some([_,_]) => Err(serde::de::Error::invalid_value(unexpected, &"number of laps has to be a multiple of 2 and distance per lap has to be a multiple of 25")),
-            _ => Err(parse_error),
-        }
+        Self::try_from(value.as_str()).map_err(serde::de::Error::custom)
     }
 }
 
-impl TryFrom<isize> for Team {
-    type Error = Error;
-
-    fn try_from(_value: isize) -> Result<Self, Self::Error> {
-        Err(Error::InvalidDistance)
-    }
-}
+/// Parses `legs*leg_length`. Example: "4*50", "10*100".
 impl TryFrom<&str> for Team {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parsed_numbers = value
-            .split('*')
-            .filter_map(|maybe_number| maybe_number.parse::<u16>().ok())
-            .array_chunks::<2>()
-            .next();
+        let (legs, leg_length) = value.split_once('*').ok_or(Error::MissingSeparator)?;
+        let legs = legs.trim().parse::<u8>().map_err(Error::Convert)?;
+        let leg_length = leg_length.trim().parse::<u16>().map_err(Error::Convert)?;
 
-        match parsed_numbers {
-            Some([num_laps, total_distance]) if num_laps % 2 == 0 && total_distance % 25 == 0 => {
-                Ok(Self::Distance(
-                    num_laps.try_into().unwrap(),
-                    // .try_into()
-                    // .map_err(|convert_error| Error::Convert(convert_error))?,
-                    total_distance,
-                ))
-            }
-            Some([_, _]) | None => Err(Error::InvalidDistance),
-        }
+        Self::new(legs, leg_length)
     }
 }
 
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
-    IndistinguishableDistance,
+    MissingSeparator,
     Convert(std::num::ParseIntError),
-    InvalidDistance,
+    InvalidLegCount(u8),
+    InvalidLegLength(u16),
+    NotMultipleOfPoolLength(u16, PoolLength),
 }
 
 #[allow(clippy::recursive_format_impl)]
@@ -87,9 +88,7 @@ impl fmt::Display for Team {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match f.align() {
             Some(_) => f.pad(&self.to_string()),
-            None => match self {
-                Self::Distance(laps, distance) => write!(f, "{laps}x{distance}m"),
-            },
+            None => write!(f, "{}x{}m", self.legs, self.leg_length),
         }
     }
 }
@@ -100,11 +99,22 @@ impl Display for Error {
         match f.align() {
             Some(_) => f.pad(&self.to_string()),
             None => match self {
-                Self::IndistinguishableDistance => {
-                    write!(f, "cannot uniquely identify the distance")
-                }
+                Self::MissingSeparator => write!(
+                    f,
+                    "expected number of legs and distance per leg separated by a '*' character. example: '4*50'"
+                ),
                 Self::Convert(parse_error) => write!(f, "conversion error: {parse_error}"),
-                Self::InvalidDistance => write!(f, "distance does not exists"),
+                Self::InvalidLegCount(legs) => {
+                    write!(f, "a relay needs at least 2 legs, got {legs}")
+                }
+                Self::InvalidLegLength(leg_length) => write!(
+                    f,
+                    "leg length {leg_length}m does not exists. Leg length has to be a multiple of {MIN_DISTANCE}m"
+                ),
+                Self::NotMultipleOfPoolLength(leg_length, pool_length) => write!(
+                    f,
+                    "leg length {leg_length}m is not a multiple of pool length {pool_length}"
+                ),
             },
         }
     }
@@ -2,10 +2,14 @@ use std::{convert::TryFrom, fmt::Display};
 extern crate serde;
 use self::serde::Deserialize;
 
-#[derive(Debug, Clone, Copy)]
+/// Touch pads installed in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TouchPadSet {
+    /// One pad per lane.
     OneSet,
+    /// Two redundant pads per lane connected to separate console inputs.
     TwoSet,
+    /// No touch pads. Times are taken with backup buttons or stopwatches.
     None,
 }
 
@@ -0,0 +1,101 @@
+extern crate chrono;
+use self::chrono::NaiveDate;
+use super::{competition_type::CompetitionType, distance::Distance, pool_length::PoolLength};
+use std::fmt::{self, Display};
+
+/// Inconsistency in a `Meet` that passes type validation. See `Meet::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The meet has no events.
+    NoEvents,
+    /// `Meet::date_start` is after `Meet::date_end`.
+    MeetDates { start: NaiveDate, end: NaiveDate },
+    /// `Meet::first_entry_date` is after `Meet::last_entry_date`.
+    EntryDates { first: NaiveDate, last: NaiveDate },
+    /// `Meet::last_entry_date` is after the first day of the meet.
+    EntryAfterStart { last: NaiveDate, start: NaiveDate },
+    /// More than one event with the same event number.
+    DuplicateEvent(u32),
+    /// More than one session with the same session id.
+    DuplicateSession(u8),
+    /// Event number and session id of an event in a session that does not exist.
+    UnknownSession { event: u32, session: u8 },
+    /// Event number and date of an event outside of the dates of the meet.
+    EventOutsideMeet { event: u32, date: NaiveDate },
+    /// Event number and session id of an event that is not on the date of its session.
+    SessionDate { event: u32, session: u8 },
+    /// Event number of an event where the oldest birth year is after the youngest birth year.
+    AgeRange(u32),
+    /// Event number of an event with a distance, or relay leg length, that is not a multiple of
+    /// its pool length.
+    PoolLength {
+        event: u32,
+        distance: Distance,
+        pool_length: PoolLength,
+    },
+    /// Event number of an event open to seniors in a meet for juniors. See `CompetitionType::rules`.
+    SeniorsNotAllowed(u32),
+    /// Competition type that requires qualification in a meet without qualification times.
+    NoQualificationTimes(CompetitionType),
+    /// `CompetitionTypeId` that is not in the catalogue.
+    UnknownCompetitionType(u8),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoEvents => write!(f, "meet has no events"),
+            Self::MeetDates { start, end } => {
+                write!(f, "meet starts {start} after it ends {end}")
+            }
+            Self::EntryDates { first, last } => write!(
+                f,
+                "first entry date {first} is after last entry date {last}"
+            ),
+            Self::EntryAfterStart { last, start } => {
+                write!(f, "last entry date {last} is after the meet starts {start}")
+            }
+            Self::DuplicateEvent(event) => write!(f, "event {event} is defined more than once"),
+            Self::DuplicateSession(session) => {
+                write!(f, "session {session} is defined more than once")
+            }
+            Self::UnknownSession { event, session } => {
+                write!(
+                    f,
+                    "event {event} is in session {session} which does not exist"
+                )
+            }
+            Self::EventOutsideMeet { event, date } => {
+                write!(f, "event {event} on {date} is outside of the meet dates")
+            }
+            Self::SessionDate { event, session } => {
+                write!(f, "event {event} is not on the date of session {session}")
+            }
+            Self::AgeRange(event) => write!(
+                f,
+                "event {event} has an oldest birth year after the youngest birth year"
+            ),
+            Self::PoolLength {
+                event,
+                distance,
+                pool_length,
+            } => write!(
+                f,
+                "event {event} over {distance} can not be swum in a {pool_length} pool"
+            ),
+            Self::SeniorsNotAllowed(event) => {
+                write!(f, "event {event} is open to seniors in a meet for juniors")
+            }
+            Self::NoQualificationTimes(competition_type) => write!(
+                f,
+                "{competition_type} requires qualification but the meet has no qualification times"
+            ),
+            Self::UnknownCompetitionType(id) => {
+                write!(
+                    f,
+                    "competition type {id} is unknown, using the rules of an open meet"
+                )
+            }
+        }
+    }
+}
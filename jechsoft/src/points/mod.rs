@@ -0,0 +1,287 @@
+//! World Aquatics (former FINA) points.
+//!
+//! Points are calculated with the cubic formula `P = 1000 * (B / T)^3` where `B` is the base time
+//! for the event and `T` is the swim time. Base times are updated yearly by World Aquatics and are
+//! therefore loaded from a file instead of being compiled in.
+//!
+//! Base time files are plain text files with one base time per line:
+//!
+//! ```text
+//! # gender group, pool length, distance, style, base time
+//! FEMALE,25,50,FREESTYLE,23.61
+//! MALE,50,4*100,MEDLEYRELAY,3:26.78
+//! ```
+//!
+//! Empty lines and lines starting with `#` are ignored. The three stroke handicap medley is
+//! written as `HCMEDLEY`, see `Style::code`.
+use crate::meet_setup::{
+    distance::Distance, gender_group::GenderGroup, pool_length::PoolLength, style::Style,
+};
+use crate::swim_time;
+use std::{collections::HashMap, convert::TryFrom, fmt::Display, fs, path::Path, time::Duration};
+
+/// Identifies a single base time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub gender_group: GenderGroup,
+    pub pool_length: PoolLength,
+    pub distance: Distance,
+    pub style: Style,
+}
+
+/// Set of base times, usually one per year.
+#[derive(Debug, Clone, Default)]
+pub struct BaseTimes {
+    times: HashMap<Key, Duration>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    GenderGroup,
+    PoolLength,
+    Distance,
+    Style,
+    BaseTime,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    /// Line number and field that is missing.
+    Missing(usize, Field),
+    /// Line number and field that could not be parsed.
+    Invalid(usize, Field),
+    /// Line number of a base time that has already been defined.
+    Duplicate(usize),
+    /// There is no base time for the requested event.
+    MissingBaseTime(Key),
+    /// A swim time of zero cannot be scored.
+    ZeroTime,
+    /// The swim time is so fast that the points does not fit.
+    PointsOutOfRange,
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::GenderGroup => write!(f, "gender group"),
+                Self::PoolLength => write!(f, "pool length"),
+                Self::Distance => write!(f, "distance"),
+                Self::Style => write!(f, "style"),
+                Self::BaseTime => write!(f, "base time"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Missing(line, field) => write!(f, "line {line}: missing field: {field}"),
+                Self::Invalid(line, field) => write!(f, "line {line}: invalid field: {field}"),
+                Self::Duplicate(line) => write!(f, "line {line}: base time is already defined"),
+                Self::MissingBaseTime(key) => write!(
+                    f,
+                    "no base time for {} {} {} in {} pool",
+                    key.gender_group, key.distance, key.style, key.pool_length
+                ),
+                Self::ZeroTime => write!(f, "cannot calculate points for a time of zero"),
+                Self::PointsOutOfRange => write!(f, "points are out of range"),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for BaseTimes {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut times = HashMap::new();
+
+        for (index, line) in value.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split(',').map(str::trim);
+            let mut next = |field| fields.next().ok_or(Error::Missing(line_number, field));
+
+            let key = Key {
+                gender_group: GenderGroup::try_from(next(Field::GenderGroup)?)
+                    .map_err(invalid(line_number, Field::GenderGroup))?,
+                pool_length: PoolLength::try_from(next(Field::PoolLength)?)
+                    .map_err(invalid(line_number, Field::PoolLength))?,
+                distance: Distance::try_from(next(Field::Distance)?)
+                    .map_err(invalid(line_number, Field::Distance))?,
+                style: Style::try_from(next(Field::Style)?)
+                    .map_err(invalid(line_number, Field::Style))?,
+            };
+            let key = Key {
+                style: key.style.resolve_handicap_medley(key.distance, "", None),
+                ..key
+            };
+            let base_time = swim_time::parse(next(Field::BaseTime)?)
+                .map_err(invalid(line_number, Field::BaseTime))?;
+
+            if times.insert(key, base_time).is_some() {
+                return Err(Error::Duplicate(line_number));
+            }
+        }
+
+        Ok(Self { times })
+    }
+}
+
+fn invalid<E>(line_number: usize, field: Field) -> impl FnOnce(E) -> Error {
+    move |_| Error::Invalid(line_number, field)
+}
+
+impl BaseTimes {
+    /// # Errors
+    /// Returns an error if the file cannot be read or if any of the lines are invalid.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        Self::try_from(content.as_str())
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &Key) -> Option<Duration> {
+        self.times.get(key).copied()
+    }
+
+    /// Calculate points for `time` swum in the event described by the remaining arguments.
+    ///
+    /// # Errors
+    /// Returns an error if there is no base time for the event or if the points cannot be
+    /// calculated for `time`.
+    pub fn points(
+        &self,
+        time: Duration,
+        distance: Distance,
+        style: Style,
+        gender_group: GenderGroup,
+        pool_length: PoolLength,
+    ) -> Result<u16, Error> {
+        let key = Key {
+            gender_group,
+            pool_length,
+            distance,
+            style,
+        };
+        let base_time = self.get(&key).ok_or(Error::MissingBaseTime(key))?;
+        points(base_time, time)
+    }
+}
+
+/// Calculate points for `time` given a `base_time`. Points are truncated, not rounded, and times
+/// are compared in hundredths of a second.
+///
+/// # Errors
+/// Returns an error if `time` is zero or if the points does not fit in `u16`.
+pub fn points(base_time: Duration, time: Duration) -> Result<u16, Error> {
+    let base_time = base_time.as_millis() / 10;
+    let time = time.as_millis() / 10;
+    if time == 0 {
+        return Err(Error::ZeroTime);
+    }
+
+    let points = 1000 * base_time.pow(3) / time.pow(3);
+    u16::try_from(points).map_err(|_| Error::PointsOutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_TIMES: &str = "# gender group, pool length, distance, style, base time
+FEMALE,50,100,FREESTYLE,50.00
+MALE,25,4*50,MEDLEYRELAY,1:30.00
+";
+
+    fn seconds(hundredths: u64) -> Duration {
+        Duration::from_millis(hundredths * 10)
+    }
+
+    #[test]
+    fn base_time_gives_1000_points() {
+        assert_eq!(points(seconds(2361), seconds(2361)).ok(), Some(1000));
+    }
+
+    #[test]
+    fn points_follow_the_cubic_formula() {
+        // 1000 * (50.00 / 55.00)^3 = 751.31
+        assert_eq!(points(seconds(5000), seconds(5500)).ok(), Some(751));
+    }
+
+    #[test]
+    fn points_are_truncated() {
+        // 1000 * (50.00 / 53.00)^3 = 839.62
+        assert_eq!(points(seconds(5000), seconds(5300)).ok(), Some(839));
+    }
+
+    #[test]
+    fn thousandths_are_ignored() {
+        let time = seconds(5300) + Duration::from_millis(9);
+        assert_eq!(points(seconds(5000), time).ok(), Some(839));
+    }
+
+    #[test]
+    fn zero_time_is_an_error() {
+        assert!(matches!(
+            points(seconds(5000), Duration::ZERO),
+            Err(Error::ZeroTime)
+        ));
+    }
+
+    #[test]
+    fn points_that_do_not_fit_are_an_error() {
+        assert!(matches!(
+            points(seconds(6000), seconds(100)),
+            Err(Error::PointsOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn points_use_the_base_time_of_the_event() {
+        let base_times = BaseTimes::try_from(BASE_TIMES).expect("valid base times");
+        let points = base_times.points(
+            seconds(5300),
+            Distance::try_from("100").expect("distance"),
+            Style::try_from("FREESTYLE").expect("style"),
+            GenderGroup::Female,
+            PoolLength::PoolLength50,
+        );
+        assert_eq!(points.ok(), Some(839));
+    }
+
+    #[test]
+    fn missing_base_time_is_an_error() {
+        let base_times = BaseTimes::try_from(BASE_TIMES).expect("valid base times");
+        let points = base_times.points(
+            seconds(5300),
+            Distance::try_from("100").expect("distance"),
+            Style::try_from("FREESTYLE").expect("style"),
+            GenderGroup::Mixed,
+            PoolLength::PoolLength50,
+        );
+        assert!(matches!(points, Err(Error::MissingBaseTime(_))));
+    }
+
+    #[test]
+    fn duplicate_base_times_are_an_error() {
+        let duplicate = format!("{BASE_TIMES}FEMALE,50,100,FREESTYLE,51.00\n");
+        assert!(matches!(
+            BaseTimes::try_from(duplicate.as_str()),
+            Err(Error::Duplicate(4))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! Building blocks shared by printable documents like heat lists and result lists.
+extern crate chrono;
+extern crate printpdf;
+use self::chrono::Local;
+use self::printpdf::{BuiltinFont, Mm, PdfDocument};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+pub const MARGIN: f32 = 15.0;
+/// Space reserved at the bottom of every page for the footer and page number.
+const FOOTER_HEIGHT: f32 = 10.0;
+pub const LINE_HEIGHT: f32 = 5.0;
+pub const TITLE_SIZE: f32 = 16.0;
+pub const HEADING_SIZE: f32 = 12.0;
+pub const TEXT_SIZE: f32 = 9.0;
+
+/// Settings printed on every page of a document.
+pub struct PageSettings<'a> {
+    pub title: &'a str,
+    pub header: Option<&'a str>,
+    pub footer: Option<&'a str>,
+    pub write_date_time: bool,
+    pub page_number: bool,
+}
+
+/// Text placed on a page. Coordinates are in millimeters from the bottom left corner.
+struct Text {
+    x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    content: String,
+}
+
+/// Lays out A4 pages top to bottom and starts new pages when a block does not fit.
+pub struct Layout {
+    pages: Vec<Vec<Text>>,
+    y: f32,
+}
+
+impl Layout {
+    pub fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Start a new page unless `lines` lines fit on the current page.
+    pub fn reserve(&mut self, lines: usize) {
+        let height = LINE_HEIGHT * f32::from(u16::try_from(lines).unwrap_or(u16::MAX));
+        if self.y - height < MARGIN + FOOTER_HEIGHT
+            && self.pages.last().is_some_and(|page| !page.is_empty())
+        {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    /// Add a line of text. `texts` are pairs of x coordinate and text.
+    pub fn line(&mut self, texts: Vec<(f32, String)>, size: f32, bold: bool) {
+        self.reserve(1);
+        self.y -= LINE_HEIGHT.max(size * 0.45);
+        let y = self.y;
+        if let Some(page) = self.pages.last_mut() {
+            page.extend(texts.into_iter().map(|(x, content)| Text {
+                x,
+                y,
+                size,
+                bold,
+                content,
+            }));
+        }
+    }
+
+    /// Add a line with a single text starting at the left margin.
+    pub fn text(&mut self, content: String, size: f32, bold: bool) {
+        self.line(vec![(MARGIN, content)], size, bold);
+    }
+
+    pub fn space(&mut self) {
+        self.y -= LINE_HEIGHT / 2.0;
+    }
+
+    /// Create the PDF document.
+    ///
+    /// # Errors
+    /// Returns an error if the fonts cannot be added to the document.
+    pub fn render(self, page_settings: &PageSettings) -> Result<Vec<u8>, printpdf::Error> {
+        let (document, first_page, first_layer) = PdfDocument::new(
+            page_settings.title,
+            Mm(PAGE_WIDTH),
+            Mm(PAGE_HEIGHT),
+            "content",
+        );
+        let font = document.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = document.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let printed = printed();
+        let page_count = self.pages.len();
+
+        for (index, texts) in self.pages.into_iter().enumerate() {
+            let (page, layer) = if index == 0 {
+                (first_page, first_layer)
+            } else {
+                document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content")
+            };
+            let layer = document.get_page(page).get_layer(layer);
+
+            if let Some(header) = page_settings.header {
+                layer.use_text(
+                    header,
+                    TEXT_SIZE,
+                    Mm(MARGIN),
+                    Mm(PAGE_HEIGHT - MARGIN / 2.0),
+                    &font,
+                );
+            }
+            for text in texts {
+                let font = if text.bold { &bold } else { &font };
+                layer.use_text(text.content, text.size, Mm(text.x), Mm(text.y), font);
+            }
+
+            let footer_y = Mm(MARGIN);
+            if let Some(footer) = page_settings.footer {
+                layer.use_text(footer, TEXT_SIZE, Mm(MARGIN), footer_y, &font);
+            }
+            if page_settings.write_date_time {
+                layer.use_text(
+                    format!("Printed {printed}"),
+                    TEXT_SIZE,
+                    Mm(PAGE_WIDTH / 2.0 - 15.0),
+                    footer_y,
+                    &font,
+                );
+            }
+            if page_settings.page_number {
+                layer.use_text(
+                    format!("Page {} of {page_count}", index + 1),
+                    TEXT_SIZE,
+                    Mm(PAGE_WIDTH - MARGIN - 25.0),
+                    footer_y,
+                    &font,
+                );
+            }
+        }
+
+        document.save_to_bytes()
+    }
+}
+
+/// Place cells next to each other starting at the left margin. `cells` are pairs of column width
+/// in millimeters and text.
+pub fn row(cells: impl IntoIterator<Item = (f32, String)>) -> Vec<(f32, String)> {
+    let mut x = MARGIN;
+    cells
+        .into_iter()
+        .map(|(width, content)| {
+            let text = (x, content);
+            x += width;
+            text
+        })
+        .collect()
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Time the document was created as printed when `write_date_time` is set.
+pub fn printed() -> String {
+    Local::now().format("%Y-%m-%d %H:%M").to_string()
+}
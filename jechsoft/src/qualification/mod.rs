@@ -0,0 +1,438 @@
+//! # Qualification
+//!
+//! Checks an athlete's best times against the qualification requirements of a `Meet`. A meet can
+//! require qualification in two ways:
+//! - a `QualificationSet` with limits per class, gender, pool length, distance and style,
+//! - qualification times on each `Event` for long course and short course.
+//!
+//! An event is qualified if any of the limits that apply to the athlete is met. Para athletes are
+//! exempt when the meet or the event says so, see `Eligibility::qualification_exempt`.
+extern crate chrono;
+extern crate gregorian;
+use self::chrono::Datelike;
+use self::gregorian::Year;
+use crate::meet_setup::{
+    class::Class, classification::Classification, distance::Distance, event::Event,
+    gender_group::GenderGroup, meet::Meet, pool_length::PoolLength, style::Style,
+};
+use std::{collections::HashMap, time::Duration};
+
+/// Age from which athletes are seniors when the meet does not set senior birth years.
+pub(crate) const SENIOR_AGE: i16 = 20;
+
+/// Best times of an athlete by distance, style and pool length.
+pub type BestTimes = HashMap<(Distance, Style, PoolLength), Duration>;
+
+/// What the checker needs to know about an athlete.
+#[derive(Debug, Clone, Copy)]
+pub struct Profile<'a> {
+    pub birth_year: Year,
+    /// Has to be either `GenderGroup::Male` or `GenderGroup::Female`.
+    pub gender: GenderGroup,
+    pub classification: Option<&'a Classification>,
+    pub best_times: &'a BestTimes,
+}
+
+/// A time limit and the best time it was compared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit {
+    pub pool_length: PoolLength,
+    pub limit: Duration,
+    /// Best time in `pool_length`. `None` if the athlete has not swum the event in that pool.
+    pub best_time: Option<Duration>,
+}
+
+/// Qualification status of an athlete in one event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The athlete is qualified by the limit.
+    Qualified(Limit),
+    /// The athlete is not qualified. Holds the limit the athlete is closest to.
+    NotQualified(Limit),
+    /// The event has no limits for the athlete.
+    Open,
+    /// Para athlete that does not need a qualification time.
+    Exempt,
+    /// The athlete cannot swim the event because of gender or age.
+    NotEligible,
+}
+
+/// Qualification status for a single event.
+#[derive(Debug, Clone, Copy)]
+pub struct EventQualification<'a> {
+    pub event: &'a Event,
+    pub status: Status,
+}
+
+impl Limit {
+    /// Time the athlete is faster than the limit, or `None` if the athlete is slower or has no
+    /// best time.
+    #[must_use]
+    pub fn margin(&self) -> Option<Duration> {
+        self.best_time
+            .and_then(|best_time| self.limit.checked_sub(best_time))
+    }
+
+    /// Time the athlete has to improve to reach the limit. Zero if the limit is reached and
+    /// `None` if the athlete has no best time.
+    #[must_use]
+    pub fn missing(&self) -> Option<Duration> {
+        self.best_time
+            .map(|best_time| best_time.saturating_sub(self.limit))
+    }
+
+    fn is_met(&self) -> bool {
+        self.best_time
+            .is_some_and(|best_time| best_time <= self.limit)
+    }
+}
+
+impl Status {
+    #[must_use]
+    pub const fn is_qualified(&self) -> bool {
+        matches!(self, Self::Qualified(_) | Self::Open | Self::Exempt)
+    }
+}
+
+/// Check every individual event of `meet` for `profile`, in event order.
+#[must_use]
+pub fn check<'a>(meet: &'a Meet, profile: &Profile) -> Vec<EventQualification<'a>> {
+    meet.events
+        .iter()
+        .filter(|event| !event.distance.is_team())
+        .map(|event| EventQualification {
+            event,
+            status: check_event(meet, event, profile),
+        })
+        .collect()
+}
+
+/// Check a single event for `profile`.
+#[must_use]
+pub fn check_event(meet: &Meet, event: &Event, profile: &Profile) -> Status {
+    if !is_eligible(event, profile) {
+        return Status::NotEligible;
+    }
+    let exempt = profile
+        .classification
+        .and_then(|classification| classification.eligibility(meet, event).ok())
+        .is_some_and(|eligibility| eligibility.qualification_exempt);
+    if exempt {
+        return Status::Exempt;
+    }
+
+    let mut limits: Vec<Limit> = event_limits(event)
+        .chain(set_limits(meet, event, profile))
+        .map(|(pool_length, limit)| Limit {
+            pool_length,
+            limit,
+            best_time: profile
+                .best_times
+                .get(&(event.distance, event.style, pool_length))
+                .copied(),
+        })
+        .collect();
+    if limits.is_empty() {
+        return Status::Open;
+    }
+
+    if let Some(limit) = limits
+        .iter()
+        .filter(|limit| limit.is_met())
+        .max_by_key(|limit| limit.margin())
+    {
+        return Status::Qualified(*limit);
+    }
+    limits.sort_by_key(|limit| (limit.missing().is_none(), limit.missing()));
+    Status::NotQualified(limits[0])
+}
+
+fn is_eligible(event: &Event, profile: &Profile) -> bool {
+    let gender = match event.gender_group {
+        GenderGroup::Mixed => profile.gender != GenderGroup::Mixed,
+        gender_group => profile.gender == gender_group,
+    };
+    let youngest = event
+        .youngest
+        .is_none_or(|youngest| profile.birth_year <= youngest);
+    let oldest = event
+        .oldest
+        .is_none_or(|oldest| profile.birth_year >= oldest);
+
+    gender && youngest && oldest
+}
+
+/// Qualification times set on the event itself.
+fn event_limits(event: &Event) -> impl Iterator<Item = (PoolLength, Duration)> {
+    [
+        (
+            PoolLength::PoolLength50,
+            event.qualification_time_long_course,
+        ),
+        (
+            PoolLength::PoolLength25,
+            event.qualification_time_short_course,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(pool_length, limit)| limit.map(|limit| (pool_length, limit)))
+    .filter(|(_, limit)| !limit.is_zero())
+}
+
+/// Qualification times from the qualification set of the meet that apply to the athlete.
+fn set_limits<'a>(
+    meet: &'a Meet,
+    event: &'a Event,
+    profile: &'a Profile,
+) -> impl Iterator<Item = (PoolLength, Duration)> + 'a {
+    let gender = profile.gender;
+    meet.qualification_set
+        .iter()
+        .flat_map(|qualification_set| &qualification_set.qualifications)
+        .filter(move |qualification| {
+            qualification.distance == event.distance
+                && qualification.style == event.style
+                && qualification.athlete_gender == gender
+                && in_class(meet, event, profile, qualification.athlete_class)
+        })
+        .map(|qualification| (qualification.pool_length, qualification.time))
+}
+
+/// Check whether the athlete competes in `class` in `event`.
+fn in_class(meet: &Meet, event: &Event, profile: &Profile, class: Class) -> bool {
+    match class {
+        Class::Senior => is_senior(meet, event, profile),
+        Class::Junior(None) => !is_senior(meet, event, profile),
+        Class::Junior(Some(year)) => profile.birth_year == year,
+        Class::Handicap(handicap) => profile
+            .classification
+            .and_then(|classification| classification.sport_class(event.style))
+            .is_some_and(|sport_class| sport_class == handicap),
+    }
+}
+
+/// Check whether the athlete competes as a senior in `event`. Uses the senior birth years of the
+/// meet and falls back to athletes turning `SENIOR_AGE` in the year of the event.
+#[must_use]
+pub fn is_senior(meet: &Meet, event: &Event, profile: &Profile) -> bool {
+    let senior_year = match profile.gender {
+        GenderGroup::Female => meet.women_senior,
+        _ => meet.men_senior,
+    };
+    senior_year.map_or_else(
+        || {
+            i16::try_from(event.date.year())
+                .is_ok_and(|year| year - profile.birth_year.to_number() >= SENIOR_AGE)
+        },
+        |senior_year| profile.birth_year <= senior_year,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swim_time;
+
+    fn meet(xml: &str) -> Meet {
+        Meet::from_str(xml).expect("valid meet")
+    }
+
+    fn basic() -> Meet {
+        meet(include_str!("../../tests/corpus/basic.xml"))
+    }
+
+    fn time(time: &str) -> Duration {
+        swim_time::parse(time).expect("valid time")
+    }
+
+    /// Best times in 100m freestyle, the first event of the corpus meets.
+    fn best_times(meet: &Meet, times: &[(PoolLength, &str)]) -> BestTimes {
+        let event = &meet.events[0];
+        times
+            .iter()
+            .map(|&(pool_length, best_time)| {
+                ((event.distance, event.style, pool_length), time(best_time))
+            })
+            .collect()
+    }
+
+    fn profile(birth_year: i16, gender: GenderGroup, best_times: &BestTimes) -> Profile<'_> {
+        Profile {
+            birth_year: Year::new(birth_year),
+            gender,
+            classification: None,
+            best_times,
+        }
+    }
+
+    #[test]
+    fn seniors_turn_senior_age_in_the_year_of_the_event() {
+        let meet = basic();
+        let event = &meet.events[0];
+        let best_times = BestTimes::new();
+        let senior = |birth_year| {
+            is_senior(
+                &meet,
+                event,
+                &profile(birth_year, GenderGroup::Female, &best_times),
+            )
+        };
+        assert_eq!(event.date.year(), 2026);
+        assert!(senior(2006));
+        assert!(!senior(2007));
+    }
+
+    #[test]
+    fn senior_birth_years_of_the_meet_are_used_per_gender() {
+        let mut meet = basic();
+        meet.women_senior = Some(Year::new(2008));
+        meet.men_senior = Some(Year::new(2006));
+        let event = &meet.events[0];
+        let best_times = BestTimes::new();
+        let senior =
+            |birth_year, gender| is_senior(&meet, event, &profile(birth_year, gender, &best_times));
+        assert!(senior(2008, GenderGroup::Female));
+        assert!(!senior(2009, GenderGroup::Female));
+        assert!(senior(2006, GenderGroup::Male));
+        assert!(!senior(2007, GenderGroup::Male));
+    }
+
+    #[test]
+    fn event_without_qualification_times_is_open() {
+        let mut meet = basic();
+        meet.events[0].qualification_time_long_course = None;
+        meet.events[0].qualification_time_short_course = Some(Duration::ZERO);
+        let best_times = BestTimes::new();
+        let status = check_event(
+            &meet,
+            &meet.events[0],
+            &profile(2000, GenderGroup::Female, &best_times),
+        );
+        assert_eq!(status, Status::Open);
+        assert!(status.is_qualified());
+    }
+
+    #[test]
+    fn athlete_without_best_time_is_not_qualified() {
+        let meet = basic();
+        let best_times = BestTimes::new();
+        let status = check_event(
+            &meet,
+            &meet.events[0],
+            &profile(2000, GenderGroup::Female, &best_times),
+        );
+        let Status::NotQualified(limit) = status else {
+            panic!("expected not qualified, got {status:?}");
+        };
+        assert_eq!(limit.pool_length, PoolLength::PoolLength50);
+        assert_eq!(limit.limit, time("1:10.00"));
+        assert_eq!(limit.best_time, None);
+        assert_eq!(limit.missing(), None);
+    }
+
+    #[test]
+    fn either_pool_length_qualifies() {
+        let mut meet = basic();
+        meet.events[0].qualification_time_short_course = Some(time("1:07.00"));
+        let event = &meet.events[0];
+
+        let long_course = best_times(
+            &meet,
+            &[
+                (PoolLength::PoolLength50, "1:09.50"),
+                (PoolLength::PoolLength25, "1:08.00"),
+            ],
+        );
+        let Status::Qualified(limit) = check_event(
+            &meet,
+            event,
+            &profile(2000, GenderGroup::Female, &long_course),
+        ) else {
+            panic!("expected qualified in long course");
+        };
+        assert_eq!(limit.pool_length, PoolLength::PoolLength50);
+        assert_eq!(limit.margin(), Some(time("0.50")));
+
+        let short_course = best_times(&meet, &[(PoolLength::PoolLength25, "1:06.90")]);
+        let Status::Qualified(limit) = check_event(
+            &meet,
+            event,
+            &profile(2000, GenderGroup::Female, &short_course),
+        ) else {
+            panic!("expected qualified in short course");
+        };
+        assert_eq!(limit.pool_length, PoolLength::PoolLength25);
+    }
+
+    #[test]
+    fn not_qualified_reports_the_closest_limit() {
+        let mut meet = basic();
+        meet.events[0].qualification_time_short_course = Some(time("1:07.00"));
+        let best_times = best_times(
+            &meet,
+            &[
+                (PoolLength::PoolLength50, "1:12.00"),
+                (PoolLength::PoolLength25, "1:07.50"),
+            ],
+        );
+        let status = check_event(
+            &meet,
+            &meet.events[0],
+            &profile(2000, GenderGroup::Female, &best_times),
+        );
+        let Status::NotQualified(limit) = status else {
+            panic!("expected not qualified, got {status:?}");
+        };
+        assert_eq!(limit.pool_length, PoolLength::PoolLength25);
+        assert_eq!(limit.missing(), Some(time("0.50")));
+    }
+
+    #[test]
+    fn qualification_set_limits_apply_to_their_class() {
+        let mut meet = meet(include_str!("../../tests/corpus/qualification_set.xml"));
+        meet.events[0].qualification_time_long_course = None;
+        let best_times = best_times(&meet, &[(PoolLength::PoolLength50, "1:12.00")]);
+
+        let senior = check_event(
+            &meet,
+            &meet.events[0],
+            &profile(2000, GenderGroup::Female, &best_times),
+        );
+        assert!(matches!(senior, Status::NotQualified(limit) if limit.limit == time("1:10.00")));
+
+        let junior = check_event(
+            &meet,
+            &meet.events[0],
+            &profile(2012, GenderGroup::Female, &best_times),
+        );
+        assert_eq!(junior, Status::Open);
+    }
+
+    #[test]
+    fn gender_and_classification_decide_before_limits() {
+        let meet = basic();
+        let best_times = BestTimes::new();
+        let male = profile(2000, GenderGroup::Male, &best_times);
+        assert_eq!(
+            check_event(&meet, &meet.events[0], &male),
+            Status::NotEligible
+        );
+
+        let classification = Classification::try_from("S8").expect("valid class");
+        let para = Profile {
+            classification: Some(&classification),
+            ..profile(2000, GenderGroup::Female, &best_times)
+        };
+        assert_eq!(check_event(&meet, &meet.events[0], &para), Status::Exempt);
+    }
+
+    #[test]
+    fn check_skips_relays() {
+        let meet = basic();
+        let best_times = BestTimes::new();
+        let qualifications = check(&meet, &profile(2000, GenderGroup::Male, &best_times));
+        assert_eq!(qualifications.len(), 1);
+        assert_eq!(qualifications[0].event.id, 1);
+        assert_eq!(qualifications[0].status, Status::NotEligible);
+    }
+}
@@ -0,0 +1,351 @@
+//! Record lists in Lenex, the XML format most meet managers use to exchange meets, results and
+//! records. Only the parts of `RECORDLISTS` that fit a `Key` are read.
+extern crate chrono;
+extern crate serde;
+use self::chrono::NaiveDate;
+use self::serde::Deserialize;
+use super::{AgeClass, Error, Field, Holder, Key, Record, Records, Scope, DATE_FORMAT};
+use crate::meet_setup::{
+    distance::Distance, gender_group::GenderGroup, pool_length::PoolLength, style::Style,
+};
+use crate::qualification::SENIOR_AGE;
+use crate::swim_time;
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+#[derive(Deserialize)]
+struct Lenex {
+    #[serde(rename = "RECORDLISTS", default)]
+    record_lists: RecordLists,
+}
+
+#[derive(Deserialize, Default)]
+struct RecordLists {
+    #[serde(rename = "RECORDLIST", default)]
+    lists: Vec<RecordList>,
+}
+
+#[derive(Deserialize)]
+struct RecordList {
+    course: String,
+    gender: String,
+    #[serde(rename = "AGEGROUP")]
+    age_group: Option<AgeGroup>,
+    #[serde(rename = "RECORDS", default)]
+    records: RecordElements,
+}
+
+#[derive(Deserialize)]
+struct AgeGroup {
+    agemin: i16,
+    agemax: i16,
+}
+
+#[derive(Deserialize, Default)]
+struct RecordElements {
+    #[serde(rename = "RECORD", default)]
+    records: Vec<RecordElement>,
+}
+
+#[derive(Deserialize)]
+struct RecordElement {
+    swimtime: String,
+    #[serde(rename = "SWIMSTYLE")]
+    swim_style: SwimStyle,
+    #[serde(rename = "MEETINFO")]
+    meet_info: Option<MeetInfo>,
+    #[serde(rename = "ATHLETE")]
+    athlete: Option<Athlete>,
+    #[serde(rename = "RELAY")]
+    relay: Option<Relay>,
+}
+
+#[derive(Deserialize)]
+struct SwimStyle {
+    /// Distance of a single swimmer, also in relays.
+    distance: u16,
+    #[serde(default = "one")]
+    relaycount: u8,
+    stroke: String,
+}
+
+#[derive(Deserialize)]
+struct MeetInfo {
+    date: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Athlete {
+    firstname: String,
+    lastname: String,
+    #[serde(rename = "CLUB")]
+    club: Option<Club>,
+}
+
+#[derive(Deserialize)]
+struct Relay {
+    name: Option<String>,
+    #[serde(rename = "CLUB")]
+    club: Option<Club>,
+}
+
+#[derive(Deserialize)]
+struct Club {
+    name: String,
+}
+
+/// Age of the youngest junior class, see `Junior`.
+const YOUNGEST_JUNIOR_AGE: i16 = 9;
+
+const fn one() -> u8 {
+    1
+}
+
+/// Records of all record lists in `xml`. See `Records::from_lenex`.
+pub(super) fn parse(xml: &str, scope: Scope) -> Result<Records, Error> {
+    let lenex: Lenex = serde_xml_rs::de::from_str(xml)?;
+    let mut records = HashMap::new();
+    let mut number = 0;
+
+    for list in lenex.record_lists.lists {
+        let pool_length = match list.course.as_str() {
+            "LCM" => PoolLength::PoolLength50,
+            "SCM" => PoolLength::PoolLength25,
+            course => {
+                log::debug!("skipping record list in {course}");
+                continue;
+            }
+        };
+        let gender_group = match list.gender.as_str() {
+            "M" => GenderGroup::Male,
+            "F" => GenderGroup::Female,
+            "X" => GenderGroup::Mixed,
+            gender => {
+                log::debug!("skipping record list for gender {gender}");
+                continue;
+            }
+        };
+        let Some(age_class) = age_class(list.age_group.as_ref()) else {
+            log::debug!("skipping record list for an age group that is not an age class");
+            continue;
+        };
+
+        for element in list.records.records {
+            number += 1;
+            let Some((distance, style)) = event(&element.swim_style) else {
+                log::debug!("skipping record {number} in {}", element.swim_style.stroke);
+                continue;
+            };
+            let key = Key {
+                scope,
+                gender_group,
+                pool_length,
+                distance,
+                style,
+                age_class,
+            };
+            let time =
+                lenex_time(&element.swimtime).ok_or(Error::InvalidLenex(number, Field::Time))?;
+            let (name, club) = match (element.athlete, element.relay) {
+                (Some(athlete), _) => (
+                    format!("{} {}", athlete.firstname, athlete.lastname),
+                    athlete.club,
+                ),
+                (None, Some(relay)) => {
+                    let club = relay.club;
+                    let name = relay
+                        .name
+                        .or_else(|| club.as_ref().map(|club| club.name.clone()))
+                        .ok_or(Error::InvalidLenex(number, Field::Name))?;
+                    (name, club)
+                }
+                (None, None) => return Err(Error::InvalidLenex(number, Field::Name)),
+            };
+            let meet_info = element.meet_info;
+            let date = meet_info
+                .as_ref()
+                .and_then(|meet_info| meet_info.date.as_deref())
+                .map(|date| NaiveDate::parse_from_str(date, DATE_FORMAT))
+                .transpose()
+                .map_err(|_| Error::InvalidLenex(number, Field::Date))?;
+
+            let club = club.map(|club| club.name).unwrap_or_default();
+            let record = Record {
+                time,
+                name,
+                club,
+                date,
+                meet: meet_info.and_then(|meet_info| meet_info.name),
+            };
+            if records
+                .insert(Holder::new(key, &record.club), record)
+                .is_some()
+            {
+                return Err(Error::DuplicateLenex(number));
+            }
+        }
+    }
+
+    Ok(Records { records })
+}
+
+/// Lenex writes ages as -1 for no limit. Lists for a single age are age records, lists without
+/// limits open records and lists for all juniors, from the youngest junior class or younger up
+/// to the year before `SENIOR_AGE`, junior records. Other groups, like 13-14 or 15-16 years, have
+/// no age class.
+fn age_class(age_group: Option<&AgeGroup>) -> Option<AgeClass> {
+    let Some(AgeGroup { agemin, agemax }) = age_group else {
+        return Some(AgeClass::Open);
+    };
+    match (*agemin, *agemax) {
+        (-1, -1) => Some(AgeClass::Open),
+        (min, max) if min == max => u8::try_from(min).ok().map(AgeClass::Age),
+        (min, max) if min <= YOUNGEST_JUNIOR_AGE && max == SENIOR_AGE - 1 => Some(AgeClass::Junior),
+        _ => None,
+    }
+}
+
+fn event(swim_style: &SwimStyle) -> Option<(Distance, Style)> {
+    let style = match (swim_style.stroke.as_str(), swim_style.relaycount) {
+        ("FREE", _) => "FREESTYLE",
+        ("BACK", _) => "BACKSTROKE",
+        ("BREAST", _) => "BREASTSTROKE",
+        ("FLY", _) => "BUTTERFLY",
+        ("MEDLEY", 1) => "INDIVIDUALMEDLEY",
+        ("MEDLEY", _) => "MEDLEYRELAY",
+        _ => return None,
+    };
+    let distance = match swim_style.relaycount {
+        1 => swim_style.distance.to_string(),
+        legs => format!("{legs}*{}", swim_style.distance),
+    };
+    let distance = Distance::try_from(distance.as_str()).ok()?;
+    let style = Style::try_from(style).ok()?;
+    Some((distance, style.resolve_handicap_medley(distance, "", None)))
+}
+
+/// Lenex swim times are formatted as `hh:mm:ss.hh`.
+fn lenex_time(value: &str) -> Option<Duration> {
+    let (hours, rest) = value.split_once(':')?;
+    let hours = hours.parse::<u64>().ok()?;
+    let time = swim_time::parse(rest).ok()?;
+    time.checked_add(Duration::from_secs(hours.checked_mul(3600)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LENEX: &str = r#"<LENEX version="3.0">
+  <RECORDLISTS>
+    <RECORDLIST course="LCM" gender="F" name="Norske rekorder">
+      <RECORDS>
+        <RECORD swimtime="00:00:53.52">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Kari" lastname="Nordmann"><CLUB name="Bergen Svømmeklubb" /></ATHLETE>
+        </RECORD>
+      </RECORDS>
+    </RECORDLIST>
+    <RECORDLIST course="LCM" gender="F" name="Juniorrekorder">
+      <AGEGROUP agemin="-1" agemax="19" />
+      <RECORDS>
+        <RECORD swimtime="00:00:54.10">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Lise" lastname="Nordmann"><CLUB name="Fana IL" /></ATHLETE>
+        </RECORD>
+      </RECORDS>
+    </RECORDLIST>
+    <RECORDLIST course="LCM" gender="F" name="Rekorder 13-14 år">
+      <AGEGROUP agemin="13" agemax="14" />
+      <RECORDS>
+        <RECORD swimtime="00:00:57.00">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Anne" lastname="Nordmann"><CLUB name="Fana IL" /></ATHLETE>
+        </RECORD>
+      </RECORDS>
+    </RECORDLIST>
+    <RECORDLIST course="LCM" gender="F" name="Rekorder 15-16 år">
+      <AGEGROUP agemin="15" agemax="16" />
+      <RECORDS>
+        <RECORD swimtime="00:00:55.00">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Ida" lastname="Nordmann"><CLUB name="Fana IL" /></ATHLETE>
+        </RECORD>
+      </RECORDS>
+    </RECORDLIST>
+    <RECORDLIST course="LCM" gender="F" name="Rekorder 14 år">
+      <AGEGROUP agemin="14" agemax="14" />
+      <RECORDS>
+        <RECORD swimtime="00:00:56.20">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Anne" lastname="Nordmann"><CLUB name="Fana IL" /></ATHLETE>
+        </RECORD>
+      </RECORDS>
+    </RECORDLIST>
+  </RECORDLISTS>
+</LENEX>"#;
+
+    fn age_group(agemin: i16, agemax: i16) -> Option<AgeClass> {
+        age_class(Some(&AgeGroup { agemin, agemax }))
+    }
+
+    #[test]
+    fn age_groups_map_to_age_classes() {
+        assert_eq!(age_class(None), Some(AgeClass::Open));
+        assert_eq!(age_group(-1, -1), Some(AgeClass::Open));
+        assert_eq!(age_group(14, 14), Some(AgeClass::Age(14)));
+        assert_eq!(age_group(-1, 19), Some(AgeClass::Junior));
+        assert_eq!(age_group(0, 19), Some(AgeClass::Junior));
+        assert_eq!(age_group(9, 19), Some(AgeClass::Junior));
+    }
+
+    #[test]
+    fn age_groups_that_are_not_all_juniors_are_skipped() {
+        assert_eq!(age_group(13, 14), None);
+        assert_eq!(age_group(15, 16), None);
+        assert_eq!(age_group(-1, 12), None);
+        assert_eq!(age_group(15, 19), None);
+        assert_eq!(age_group(20, -1), None);
+    }
+
+    #[test]
+    fn multi_age_lists_do_not_replace_junior_records() {
+        let records = parse(LENEX, Scope::National).expect("valid Lenex");
+        let sorted = records.sorted();
+        let by_class: Vec<_> = sorted
+            .iter()
+            .map(|(key, record)| (key.age_class, record.name.as_str()))
+            .collect();
+        assert_eq!(
+            by_class,
+            [
+                (AgeClass::Open, "Kari Nordmann"),
+                (AgeClass::Junior, "Lise Nordmann"),
+                (AgeClass::Age(14), "Anne Nordmann"),
+            ]
+        );
+        assert_eq!(
+            sorted[1].0.flag(),
+            Some(crate::meet_result::RecordFlag::NationalJunior)
+        );
+    }
+
+    #[test]
+    fn duplicate_record_is_an_error() {
+        let record = r#"<RECORD swimtime="00:00:53.52">
+          <SWIMSTYLE distance="100" relaycount="1" stroke="FREE" />
+          <ATHLETE firstname="Kari" lastname="Nordmann" />
+        </RECORD>"#;
+        let xml = format!(
+            r#"<LENEX version="3.0"><RECORDLISTS>
+    <RECORDLIST course="LCM" gender="F"><RECORDS>{record}</RECORDS></RECORDLIST>
+    <RECORDLIST course="SCM" gender="F"><RECORDS>{record}</RECORDS></RECORDLIST>
+    <RECORDLIST course="LCM" gender="F"><RECORDS>{record}</RECORDS></RECORDLIST>
+  </RECORDLISTS></LENEX>"#
+        );
+        assert!(matches!(
+            parse(&xml, Scope::National),
+            Err(Error::DuplicateLenex(3))
+        ));
+    }
+}
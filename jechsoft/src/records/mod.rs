@@ -0,0 +1,884 @@
+//! # Records
+//!
+//! Club, district, national and meet records, and detection of records broken in meet results.
+//!
+//! Records are read from plain text files with one record per line:
+//!
+//! ```text
+//! # scope, gender group, pool length, distance, style, age class, time, name, club, date, meet
+//! NATIONAL,FEMALE,50,100,FREESTYLE,SR,53.52,Kari Nordmann,Bergen Svømmeklubb,2021-07-25,NM Langbane
+//! CLUB,FEMALE,25,200,INDIVIDUALMEDLEY,14,2:31.90,Kari Nordmann,Bergen Svømmeklubb,2022-03-12,
+//! CLUB,MALE,25,4*50,MEDLEYRELAY,JR,1:52.10,Bergen Svømmeklubb 1,Bergen Svømmeklubb,,
+//! ```
+//!
+//! Scope is `CLUB`, `DISTRICT`, `NATIONAL` or `MEET`. Age class is "SR" for open records, "JR"
+//! for junior records or the age of the athlete in the year of the swim. Date and meet are
+//! optional. Empty lines and lines starting with `#` are ignored. Club records are kept per club,
+//! so one file can hold the club records of several clubs. The three stroke handicap medley is
+//! written as `HCMEDLEY`, see `Style::code`. Names, clubs and meets containing ',' are quoted like
+//! in CSV.
+//!
+//! Record lists in Lenex format are imported with `Records::from_lenex`.
+extern crate chrono;
+use self::chrono::{Datelike, NaiveDate};
+use crate::meet_result::{self, MeetResult, RecordFlag, Status, SwimResult};
+use crate::meet_setup::{
+    class::Class, distance::Distance, event::Event, gender_group::GenderGroup, meet::Meet,
+    pool_length::PoolLength, style::Style,
+};
+use crate::qualification::SENIOR_AGE;
+use crate::roster::{distance_code, gender_code};
+use crate::swim_time;
+use crate::trygg_i_vann::{quote, split};
+use std::{
+    collections::HashMap, convert::TryFrom, fmt::Display, fmt::Write, fs, path::Path,
+    time::Duration,
+};
+
+mod lenex;
+
+/// Format of the dates in record files.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Who can hold a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Scope {
+    /// Athletes of the club holding the record.
+    Club,
+    /// Athletes of the clubs in a district. Every swim is compared, so district records should
+    /// only be checked against meets within the district.
+    District,
+    /// Norwegian athletes.
+    National,
+    /// Athletes swimming the meet.
+    Meet,
+}
+
+/// Age class of a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AgeClass {
+    /// Records of any age.
+    Open,
+    /// Records of juniors.
+    Junior,
+    /// Records of athletes with this age in the year of the swim.
+    Age(u8),
+}
+
+/// Identifies a single record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub scope: Scope,
+    pub gender_group: GenderGroup,
+    pub pool_length: PoolLength,
+    pub distance: Distance,
+    pub style: Style,
+    pub age_class: AgeClass,
+}
+
+/// Swim holding a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub time: Duration,
+    /// Athlete name or relay team name.
+    pub name: String,
+    pub club: String,
+    pub date: Option<NaiveDate>,
+    /// Name of the meet the record was set in.
+    pub meet: Option<String>,
+}
+
+/// Set of records, usually the records of one or more clubs and their district together with the
+/// national records and the records of a meet.
+#[derive(Debug, Clone, Default)]
+pub struct Records {
+    records: HashMap<Holder, Record>,
+}
+
+/// Key of a record together with the club holding it for club records, so the club records of
+/// different clubs don't replace each other. The club is empty for other scopes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Holder {
+    key: Key,
+    club: String,
+}
+
+impl Holder {
+    fn new(key: Key, club: &str) -> Self {
+        let club = match key.scope {
+            Scope::Club => club.to_string(),
+            Scope::District | Scope::National | Scope::Meet => String::new(),
+        };
+        Self { key, club }
+    }
+}
+
+/// Record broken in a meet. See `Records::update`.
+#[derive(Debug, Clone)]
+pub struct NewRecord {
+    pub key: Key,
+    /// Event number. See `Event::id`.
+    pub event: u32,
+    pub previous: Record,
+    pub record: Record,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Scope,
+    GenderGroup,
+    PoolLength,
+    Distance,
+    Style,
+    AgeClass,
+    Time,
+    Name,
+    Club,
+    Date,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    Lenex(#[from] serde_xml_rs::Error),
+    /// Line number and field that is missing.
+    Missing(usize, Field),
+    /// Line number and field that could not be parsed.
+    Invalid(usize, Field),
+    /// Number of the record in a Lenex file and field that could not be parsed.
+    InvalidLenex(usize, Field),
+    /// Line number of a record that has already been defined.
+    Duplicate(usize),
+    /// Number of a record in a Lenex file that has already been defined.
+    DuplicateLenex(usize),
+    Results(#[from] meet_result::Error),
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Club => write!(f, "club"),
+                Self::District => write!(f, "district"),
+                Self::National => write!(f, "national"),
+                Self::Meet => write!(f, "meet"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for AgeClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Open => write!(f, "open"),
+                Self::Junior => write!(f, "junior"),
+                Self::Age(age) => write!(f, "{age} years"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Scope => write!(f, "scope"),
+                Self::GenderGroup => write!(f, "gender group"),
+                Self::PoolLength => write!(f, "pool length"),
+                Self::Distance => write!(f, "distance"),
+                Self::Style => write!(f, "style"),
+                Self::AgeClass => write!(f, "age class"),
+                Self::Time => write!(f, "time"),
+                Self::Name => write!(f, "name"),
+                Self::Club => write!(f, "club"),
+                Self::Date => write!(f, "date"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Lenex(err) => write!(f, "invalid Lenex file: {err}"),
+                Self::Missing(line, field) => write!(f, "line {line}: missing field: {field}"),
+                Self::Invalid(line, field) => write!(f, "line {line}: invalid field: {field}"),
+                Self::InvalidLenex(record, field) => {
+                    write!(f, "record {record}: invalid field: {field}")
+                }
+                Self::Duplicate(line) => write!(f, "line {line}: record is already defined"),
+                Self::DuplicateLenex(record) => {
+                    write!(f, "record {record}: record is already defined")
+                }
+                Self::Results(err) => write!(f, "{err}"),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for Scope {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "CLUB" => Ok(Self::Club),
+            "DISTRICT" => Ok(Self::District),
+            "NATIONAL" => Ok(Self::National),
+            "MEET" => Ok(Self::Meet),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Scope {
+    /// Scope as written in record files.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Club => "CLUB",
+            Self::District => "DISTRICT",
+            Self::National => "NATIONAL",
+            Self::Meet => "MEET",
+        }
+    }
+}
+
+impl TryFrom<&str> for AgeClass {
+    type Error = std::num::ParseIntError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "SR" => Ok(Self::Open),
+            "JR" => Ok(Self::Junior),
+            age => Ok(Self::Age(age.parse()?)),
+        }
+    }
+}
+
+impl AgeClass {
+    /// Age class as written in record files.
+    #[must_use]
+    pub fn code(self) -> String {
+        match self {
+            Self::Open => "SR".to_string(),
+            Self::Junior => "JR".to_string(),
+            Self::Age(age) => age.to_string(),
+        }
+    }
+}
+
+impl Key {
+    /// Flag added to results that break the record. National records of single ages have no
+    /// flag.
+    #[must_use]
+    pub const fn flag(&self) -> Option<RecordFlag> {
+        match (self.scope, self.age_class) {
+            (Scope::Club, _) => Some(RecordFlag::Club),
+            (Scope::District, _) => Some(RecordFlag::District),
+            (Scope::Meet, _) => Some(RecordFlag::Meet),
+            (Scope::National, AgeClass::Open) => Some(RecordFlag::National),
+            (Scope::National, AgeClass::Junior) => Some(RecordFlag::NationalJunior),
+            (Scope::National, AgeClass::Age(_)) => None,
+        }
+    }
+
+    /// Short name of the record as printed in heat lists, like "NR" or "CR 14 years".
+    #[must_use]
+    pub fn label(&self) -> String {
+        let scope = match self.scope {
+            Scope::Club => "CR",
+            Scope::District => "DR",
+            Scope::National => "NR",
+            Scope::Meet => "MR",
+        };
+        match self.age_class {
+            AgeClass::Open => scope.to_string(),
+            age_class => format!("{scope} {age_class}"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Records {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut records = HashMap::new();
+
+        for (index, line) in value.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = split(line, ',');
+            let mut fields = fields.iter().map(|field| field.trim());
+            let mut next = |field| fields.next().ok_or(Error::Missing(line_number, field));
+
+            let key = Key {
+                scope: Scope::try_from(next(Field::Scope)?)
+                    .map_err(invalid(line_number, Field::Scope))?,
+                gender_group: GenderGroup::try_from(next(Field::GenderGroup)?)
+                    .map_err(invalid(line_number, Field::GenderGroup))?,
+                pool_length: PoolLength::try_from(next(Field::PoolLength)?)
+                    .map_err(invalid(line_number, Field::PoolLength))?,
+                distance: Distance::try_from(next(Field::Distance)?)
+                    .map_err(invalid(line_number, Field::Distance))?,
+                style: Style::try_from(next(Field::Style)?)
+                    .map_err(invalid(line_number, Field::Style))?,
+                age_class: AgeClass::try_from(next(Field::AgeClass)?)
+                    .map_err(invalid(line_number, Field::AgeClass))?,
+            };
+            let key = Key {
+                style: key.style.resolve_handicap_medley(key.distance, "", None),
+                ..key
+            };
+            let time =
+                swim_time::parse(next(Field::Time)?).map_err(invalid(line_number, Field::Time))?;
+            let name = next(Field::Name)?;
+            if name.is_empty() {
+                return Err(Error::Missing(line_number, Field::Name));
+            }
+            let club = next(Field::Club)?;
+            let date = match next(Field::Date).unwrap_or_default() {
+                "" => None,
+                date => Some(
+                    NaiveDate::parse_from_str(date, DATE_FORMAT)
+                        .map_err(invalid(line_number, Field::Date))?,
+                ),
+            };
+            let meet = fields
+                .next()
+                .filter(|meet| !meet.is_empty())
+                .map(ToString::to_string);
+
+            let record = Record {
+                time,
+                name: name.to_string(),
+                club: club.to_string(),
+                date,
+                meet,
+            };
+            if records.insert(Holder::new(key, club), record).is_some() {
+                return Err(Error::Duplicate(line_number));
+            }
+        }
+
+        Ok(Self { records })
+    }
+}
+
+fn invalid<E>(line_number: usize, field: Field) -> impl FnOnce(E) -> Error {
+    move |_| Error::Invalid(line_number, field)
+}
+
+impl Records {
+    /// # Errors
+    /// Returns an error if the file cannot be read or if any of the lines are invalid.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        Self::try_from(content.as_str())
+    }
+
+    /// Read the record lists of an uncompressed Lenex file (`.lef`) as records of `scope`. Lenex
+    /// does not say whose records a list holds, so the scope has to be given.
+    ///
+    /// Records in yards, in strokes other than the five regular ones and in age groups that are
+    /// neither open, all juniors nor a single age, like 13-14 years, are left out.
+    ///
+    /// # Errors
+    /// Returns an error if `xml` is not a Lenex file or if any of the records are invalid or
+    /// defined twice.
+    pub fn from_lenex(xml: &str, scope: Scope) -> Result<Self, Error> {
+        lenex::parse(xml, scope)
+    }
+
+    /// Write the records with one record per line. See `Records::from_file`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    /// Plain text records with one record per line.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "# scope, gender group, pool length, distance, style, age class, time, name, club, date, meet\n",
+        );
+        for (key, record) in self.sorted() {
+            let _ = writeln!(
+                text,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                key.scope.code(),
+                gender_code(key.gender_group),
+                key.pool_length.meters(),
+                distance_code(key.distance),
+                key.style.code(),
+                key.age_class.code(),
+                swim_time::format(record.time),
+                quote(&record.name, ','),
+                quote(&record.club, ','),
+                record
+                    .date
+                    .map(|date| date.format(DATE_FORMAT).to_string())
+                    .unwrap_or_default(),
+                quote(record.meet.as_deref().unwrap_or_default(), ','),
+            );
+        }
+        text
+    }
+
+    /// Record of `key`. `club` is the club holding the record and only used for club records.
+    #[must_use]
+    pub fn get(&self, key: &Key, club: &str) -> Option<&Record> {
+        self.records.get(&Holder::new(*key, club))
+    }
+
+    /// Store `record`. Returns the record it replaces, for club records only a record of the same
+    /// club.
+    pub fn insert(&mut self, key: Key, record: Record) -> Option<Record> {
+        self.records.insert(Holder::new(key, &record.club), record)
+    }
+
+    /// Store all records of `other`, replacing the records with the same key and, for club
+    /// records, the same club. Returns the number of records stored.
+    pub fn merge(&mut self, other: Self) -> usize {
+        let count = other.records.len();
+        self.records.extend(other.records);
+        count
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Records ordered by scope, gender group, pool length, style, distance, age class and club.
+    #[must_use]
+    pub fn sorted(&self) -> Vec<(&Key, &Record)> {
+        let mut records: Vec<_> = self.records.iter().collect();
+        records.sort_by_key(|(Holder { key, club }, _)| {
+            (
+                key.scope,
+                gender_code(key.gender_group),
+                key.pool_length.meters(),
+                key.style.code(),
+                key.distance.meters(),
+                key.age_class,
+                club,
+            )
+        });
+        records
+            .into_iter()
+            .map(|(holder, record)| (&holder.key, record))
+            .collect()
+    }
+
+    /// Records of the distance, style, gender group and pool length of `event`, ordered by scope
+    /// and age class.
+    #[must_use]
+    pub fn for_event(&self, event: &Event) -> Vec<(&Key, &Record)> {
+        self.sorted()
+            .into_iter()
+            .filter(|(key, _)| {
+                key.distance == event.distance
+                    && key.style == event.style
+                    && key.gender_group == event.gender_group
+                    && key.pool_length == event.pool_length
+            })
+            .collect()
+    }
+
+    /// Compare the finished swims in `results` with the records, store the records that are
+    /// broken and flag the swims that broke them. Returns the broken records in result order.
+    ///
+    /// A swim breaks a record when it is faster than the record, so equalling a record does not
+    /// count. Events without a record are left alone, since a missing record usually means an
+    /// incomplete record list rather than a free record. Club records are compared with the
+    /// records of the club of the athlete, national records only with athletes without a nation
+    /// or from Norway. Juniors are athletes in a junior class or younger than 20 years.
+    ///
+    /// # Errors
+    /// Returns an error if the results contain an event that is not in `meet`.
+    pub fn update(
+        &mut self,
+        meet: &Meet,
+        results: &mut MeetResult,
+    ) -> Result<Vec<NewRecord>, Error> {
+        let mut new_records = Vec::new();
+
+        for event_result in &mut results.events {
+            let event = meet
+                .events
+                .iter()
+                .find(|event| event.id == event_result.event)
+                .ok_or(meet_result::Error::UnknownEvent(event_result.event))?;
+
+            for class_result in &mut event_result.classes {
+                let class = class_result.class;
+                for result in &mut class_result.results {
+                    if result.status != Status::Finished {
+                        continue;
+                    }
+                    let Some(time) = result.time else {
+                        continue;
+                    };
+                    for key in keys(event, class, result) {
+                        let Some(previous) = self
+                            .get(&key, &result.club)
+                            .filter(|previous| time < previous.time)
+                            .cloned()
+                        else {
+                            continue;
+                        };
+                        let record = Record {
+                            time,
+                            name: result.name.clone(),
+                            club: result.club.clone(),
+                            date: Some(event.date),
+                            meet: Some(meet.name.clone()),
+                        };
+                        self.insert(key, record.clone());
+                        if let Some(flag) = key.flag().filter(|flag| !result.records.contains(flag))
+                        {
+                            result.records.push(flag);
+                        }
+                        new_records.push(NewRecord {
+                            key,
+                            event: event.id,
+                            previous,
+                            record,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(new_records)
+    }
+}
+
+/// Keys of the records a swim in `event` and `class` can break.
+fn keys(event: &Event, class: Class, result: &SwimResult) -> Vec<Key> {
+    let age = result
+        .birth_year
+        .filter(|_| !event.distance.is_team())
+        .and_then(|birth_year| {
+            i16::try_from(event.date.year())
+                .ok()
+                .and_then(|year| u8::try_from(year - birth_year.to_number()).ok())
+        });
+    let junior =
+        matches!(class, Class::Junior(_)) || age.is_some_and(|age| i16::from(age) < SENIOR_AGE);
+    let norwegian = result
+        .nation
+        .as_deref()
+        .is_none_or(|nation| nation.is_empty() || nation == "NOR");
+
+    let mut age_classes = vec![AgeClass::Open];
+    if junior {
+        age_classes.push(AgeClass::Junior);
+    }
+    age_classes.extend(age.map(AgeClass::Age));
+
+    [Scope::Club, Scope::District, Scope::National, Scope::Meet]
+        .into_iter()
+        .filter(|scope| *scope != Scope::National || norwegian)
+        .flat_map(|scope| {
+            age_classes.iter().map(move |&age_class| Key {
+                scope,
+                gender_group: event.gender_group,
+                pool_length: event.pool_length,
+                distance: event.distance,
+                style: event.style,
+                age_class,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORDS: &str = "\
+CLUB,FEMALE,50,100,FREESTYLE,SR,1:05.00,Kari Nordmann,Bergen Svømmeklubb,,
+CLUB,FEMALE,50,100,FREESTYLE,SR,1:04.00,Ola Nordmann,Corpus SK,,
+NATIONAL,FEMALE,50,100,FREESTYLE,SR,53.52,Kari Nordmann,Bergen Svømmeklubb,2021-07-25,NM Langbane
+";
+
+    fn meet() -> Meet {
+        Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet")
+    }
+
+    fn records() -> Records {
+        Records::try_from(RECORDS).expect("valid records")
+    }
+
+    fn club_record() -> Key {
+        Key {
+            scope: Scope::Club,
+            gender_group: GenderGroup::Female,
+            pool_length: PoolLength::PoolLength50,
+            distance: Distance::try_from("100").expect("valid distance"),
+            style: Style::try_from("FREESTYLE").expect("valid style"),
+            age_class: AgeClass::Open,
+        }
+    }
+
+    /// Results of event 1 of the corpus meet, 100m freestyle women on 2026-11-01.
+    fn results(results: &str) -> MeetResult {
+        MeetResult::try_from(
+            format!(
+                r#"{{ "events": [{{ "event": 1, "classes": [{{ "class": "SR", "results": [{results}] }}] }}] }}"#
+            )
+            .as_str(),
+        )
+        .expect("valid results")
+    }
+
+    /// Scope and age class of the keys of the first swim in `results` in event `event`.
+    fn key_classes(event: u32, class: Class, result: &str) -> Vec<(Scope, AgeClass)> {
+        let meet = meet();
+        let event = meet
+            .events
+            .iter()
+            .find(|candidate| candidate.id == event)
+            .expect("event");
+        let results = results(result);
+        keys(event, class, &results.events[0].classes[0].results[0])
+            .into_iter()
+            .map(|key| (key.scope, key.age_class))
+            .collect()
+    }
+
+    /// Keys of every scope for each of `age_classes`.
+    fn all_scopes(age_classes: &[AgeClass]) -> Vec<(Scope, AgeClass)> {
+        [Scope::Club, Scope::District, Scope::National, Scope::Meet]
+            .into_iter()
+            .flat_map(|scope| age_classes.iter().map(move |&age_class| (scope, age_class)))
+            .collect()
+    }
+
+    #[test]
+    fn club_records_of_different_clubs_are_kept_apart() {
+        let records = records();
+        assert_eq!(records.len(), 3);
+        let key = club_record();
+        assert_eq!(
+            records
+                .get(&key, "Bergen Svømmeklubb")
+                .expect("valid record")
+                .name,
+            "Kari Nordmann"
+        );
+        assert_eq!(
+            records.get(&key, "Corpus SK").expect("valid record").name,
+            "Ola Nordmann"
+        );
+        assert!(records.get(&key, "Åsane Svømmeklubb").is_none());
+    }
+
+    #[test]
+    fn merge_keeps_the_club_records_of_other_clubs() {
+        let mut records = records();
+        let other =
+            Records::try_from("CLUB,FEMALE,50,100,FREESTYLE,SR,1:03.00,Per Nordmann,Corpus SK,,")
+                .expect("valid records");
+        assert_eq!(records.merge(other), 1);
+        assert_eq!(records.len(), 3);
+        let key = club_record();
+        assert_eq!(
+            records.get(&key, "Corpus SK").expect("valid record").name,
+            "Per Nordmann"
+        );
+        assert_eq!(
+            records
+                .get(&key, "Bergen Svømmeklubb")
+                .expect("valid record")
+                .name,
+            "Kari Nordmann"
+        );
+    }
+
+    #[test]
+    fn duplicate_club_record_of_the_same_club_is_an_error() {
+        let text = "\
+CLUB,FEMALE,50,100,FREESTYLE,SR,1:05.00,Kari Nordmann,Corpus SK,,
+CLUB,FEMALE,50,100,FREESTYLE,SR,1:04.00,Ola Nordmann,Corpus SK,,
+";
+        assert!(matches!(Records::try_from(text), Err(Error::Duplicate(2))));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut records = records();
+        records.insert(
+            Key {
+                age_class: AgeClass::Age(14),
+                ..club_record()
+            },
+            Record {
+                time: swim_time::parse("1:08.12").expect("valid time"),
+                name: "Nordmann, Lise".to_string(),
+                club: "Bergen SK, avd. Fana".to_string(),
+                date: NaiveDate::from_ymd_opt(2022, 3, 12),
+                meet: Some("Bergen Open, del 2".to_string()),
+            },
+        );
+        let text = records.to_text();
+        assert!(text.contains(
+            "CLUB,FEMALE,50,100,FREESTYLE,14,1:08.12,\"Nordmann, Lise\",\"Bergen SK, avd. Fana\",2022-03-12,\"Bergen Open, del 2\"\n"
+        ));
+
+        let read = Records::try_from(text.as_str()).expect("valid records");
+        assert_eq!(read.sorted(), records.sorted());
+        assert_eq!(read.to_text(), text);
+    }
+
+    #[test]
+    fn keys_of_a_junior_by_age() {
+        assert_eq!(
+            key_classes(
+                1,
+                Class::Senior,
+                r#"{ "name": "Kari Nordmann", "club": "Bergen Svømmeklubb", "birth_year": 2010, "time": "1:00.00" }"#
+            ),
+            all_scopes(&[AgeClass::Open, AgeClass::Junior, AgeClass::Age(16)])
+        );
+    }
+
+    #[test]
+    fn keys_of_a_junior_by_class() {
+        assert_eq!(
+            key_classes(
+                1,
+                Class::Junior(None),
+                r#"{ "name": "Kari Nordmann", "club": "Bergen Svømmeklubb", "time": "1:00.00" }"#
+            ),
+            all_scopes(&[AgeClass::Open, AgeClass::Junior])
+        );
+    }
+
+    #[test]
+    fn keys_of_a_senior_include_the_single_age() {
+        assert_eq!(
+            key_classes(
+                1,
+                Class::Senior,
+                r#"{ "name": "Kari Nordmann", "club": "Bergen Svømmeklubb", "birth_year": 2006, "time": "1:00.00" }"#
+            ),
+            all_scopes(&[AgeClass::Open, AgeClass::Age(20)])
+        );
+    }
+
+    #[test]
+    fn keys_of_relays_have_no_single_age() {
+        assert_eq!(
+            key_classes(
+                2,
+                Class::Senior,
+                r#"{ "name": "Bergen Svømmeklubb 1", "club": "Bergen Svømmeklubb", "birth_year": 2010, "time": "1:50.00" }"#
+            ),
+            all_scopes(&[AgeClass::Open])
+        );
+    }
+
+    #[test]
+    fn national_records_only_for_norwegian_athletes() {
+        for nation in [r#""NOR""#, r#""""#, "null"] {
+            let classes = key_classes(
+                1,
+                Class::Senior,
+                &format!(
+                    r#"{{ "name": "Kari Nordmann", "club": "Bergen Svømmeklubb", "nation": {nation}, "time": "1:00.00" }}"#
+                ),
+            );
+            assert!(classes.contains(&(Scope::National, AgeClass::Open)));
+        }
+        assert_eq!(
+            key_classes(
+                1,
+                Class::Senior,
+                r#"{ "name": "Karin Svensson", "club": "Göteborg Sim", "nation": "SWE", "time": "1:00.00" }"#
+            ),
+            [
+                (Scope::Club, AgeClass::Open),
+                (Scope::District, AgeClass::Open),
+                (Scope::Meet, AgeClass::Open),
+            ]
+        );
+    }
+
+    #[test]
+    fn swims_break_the_club_record_of_their_own_club() {
+        let meet = meet();
+        let mut records = records();
+        let mut results = results(
+            r#"{ "name": "Lise Nordmann", "club": "Bergen Svømmeklubb", "time": "1:04.50" },
+               { "name": "Anne Nordmann", "club": "Åsane Svømmeklubb", "time": "1:00.00" }"#,
+        );
+
+        let new_records = records.update(&meet, &mut results).expect("valid results");
+
+        assert_eq!(new_records.len(), 1);
+        assert_eq!(new_records[0].record.name, "Lise Nordmann");
+        assert_eq!(new_records[0].previous.name, "Kari Nordmann");
+        let key = club_record();
+        assert_eq!(
+            records
+                .get(&key, "Bergen Svømmeklubb")
+                .expect("valid record")
+                .name,
+            "Lise Nordmann"
+        );
+        assert_eq!(
+            records.get(&key, "Corpus SK").expect("valid record").name,
+            "Ola Nordmann"
+        );
+        assert_eq!(
+            results.events[0].classes[0].results[0].records,
+            [RecordFlag::Club]
+        );
+        assert!(results.events[0].classes[0].results[1].records.is_empty());
+    }
+
+    #[test]
+    fn equal_time_does_not_break_the_record() {
+        let meet = meet();
+        let mut records = records();
+        let mut results = results(
+            r#"{ "name": "Lise Nordmann", "club": "Bergen Svømmeklubb", "time": "1:05.00" }"#,
+        );
+
+        let new_records = records.update(&meet, &mut results).expect("valid results");
+
+        assert!(new_records.is_empty());
+        assert_eq!(
+            records
+                .get(&club_record(), "Bergen Svømmeklubb")
+                .expect("valid record")
+                .name,
+            "Kari Nordmann"
+        );
+        assert!(results.events[0].classes[0].results[0].records.is_empty());
+    }
+}
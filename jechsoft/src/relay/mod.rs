@@ -0,0 +1,546 @@
+//! # Relay
+//!
+//! Finds the fastest legal relay line-ups for a relay `Event` out of a pool of swimmers.
+//!
+//! A line-up is legal when:
+//! - every leg is swum by a different swimmer,
+//! - all swimmers are born between `Event::oldest` and `Event::youngest`,
+//! - the genders match the `GenderGroup` of the event. Mixed relays have as many female as male
+//!   swimmers,
+//! - the sum of sport classes does not exceed `Constraints::max_class_points` when it is set.
+//!   Events take the limit from their description, either written out as "34 poeng" or implied
+//!   by the classes of a para relay like "S1-S10".
+//!
+//! Free, back, breast and butterfly relays use the same stroke on every leg. Team medley relays
+//! are swum in the order of `TEAM_MEDLEY` and strokes are assigned to the swimmers that give the
+//! fastest total time.
+extern crate gregorian;
+use self::gregorian::Year;
+use crate::meet_setup::{
+    classification::Classification,
+    distance::Distance,
+    event::Event,
+    gender_group::GenderGroup,
+    handicap::StyleGroup,
+    individual_distance::Individual,
+    style::{Stroke, Style, TEAM_MEDLEY},
+};
+use std::{collections::HashMap, fmt::Display, time::Duration};
+
+/// Swimmer available for relays.
+#[derive(Debug, Clone)]
+pub struct Swimmer {
+    pub name: String,
+    /// Has to be either `GenderGroup::Male` or `GenderGroup::Female`.
+    pub gender: GenderGroup,
+    pub birth_year: Year,
+    /// Sport classes of para swimmers.
+    pub classification: Option<Classification>,
+    /// Best times by distance and stroke. The optimizer only looks at the leg length of the relay.
+    pub best_times: HashMap<(Individual, Stroke), Duration>,
+}
+
+/// Rules a line-up has to follow. Usually created from an `Event` with `Constraints::from`.
+#[derive(Debug, Clone, Copy)]
+pub struct Constraints {
+    pub gender_group: GenderGroup,
+    /// Youngest allowed birth year.
+    pub youngest: Option<Year>,
+    /// Oldest allowed birth year.
+    pub oldest: Option<Year>,
+    /// Max sum of sport classes in a para relay, for example 34 for S1 - S10 relays. Swimmers
+    /// without a sport class for the stroke they swim are not eligible when this is set.
+    pub max_class_points: Option<u16>,
+}
+
+/// One swimmer swimming one leg.
+#[derive(Debug, Clone)]
+pub struct Leg {
+    /// Index of the swimmer in the slice passed to `optimize`.
+    pub swimmer: usize,
+    pub stroke: Stroke,
+    pub time: Duration,
+}
+
+/// A complete relay team.
+#[derive(Debug, Clone)]
+pub struct LineUp {
+    /// Legs in swimming order.
+    pub legs: Vec<Leg>,
+    /// Sum of the best times of all legs.
+    pub total: Duration,
+}
+
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum Error {
+    /// The event is not a relay event.
+    NotARelay,
+    /// Medley relays are swum with four legs.
+    MedleyLegs(u8),
+    /// Mixed relays need an even number of legs.
+    MixedLegs(u8),
+    /// There are not enough eligible swimmers for a single team.
+    NoLegalLineUp,
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::NotARelay => write!(f, "event is not a relay"),
+                Self::MedleyLegs(legs) => {
+                    write!(f, "medley relays have 4 legs, this relay has {legs}")
+                }
+                Self::MixedLegs(legs) => write!(
+                    f,
+                    "mixed relays need an even number of legs, this relay has {legs}"
+                ),
+                Self::NoLegalLineUp => write!(f, "not enough eligible swimmers for a team"),
+            },
+        }
+    }
+}
+
+impl From<&Event> for Constraints {
+    fn from(event: &Event) -> Self {
+        Self {
+            gender_group: event.gender_group,
+            youngest: event.youngest,
+            oldest: event.oldest,
+            max_class_points: max_class_points(&event.description),
+        }
+    }
+}
+
+/// Class point limits of the para relays of World Para Swimming by the classes in the event
+/// description.
+const PARA_RELAY_POINTS: [(&str, u16); 4] = [
+    ("S1-S10", 34),
+    ("S1-10", 34),
+    ("S11-S13", 49),
+    ("S11-13", 49),
+];
+
+/// Class point limit in an event description like "4x50m fri 34 poeng" or "4x100m fri S1-S10".
+fn max_class_points(description: &str) -> Option<u16> {
+    let words: Vec<&str> = description.split_whitespace().collect();
+    for (index, word) in words.iter().enumerate() {
+        let word = word.trim_start_matches('(');
+        let digits = word.len() - word.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            continue;
+        }
+        let unit = match &word[digits..] {
+            "" => words.get(index + 1).copied().unwrap_or_default(),
+            unit => unit,
+        };
+        let unit = unit.trim_end_matches(['.', ',', ')']).to_lowercase();
+        if ["p", "poeng", "points", "pts"].contains(&unit.as_str()) {
+            return word[..digits].parse().ok();
+        }
+    }
+
+    let description: String = description
+        .split_whitespace()
+        .collect::<String>()
+        .to_uppercase();
+    PARA_RELAY_POINTS
+        .iter()
+        .find(|(classes, _)| description.contains(classes))
+        .map(|(_, points)| *points)
+}
+
+impl Constraints {
+    fn allows(&self, swimmer: &Swimmer) -> bool {
+        let gender = match self.gender_group {
+            GenderGroup::Mixed => swimmer.gender != GenderGroup::Mixed,
+            gender_group => swimmer.gender == gender_group,
+        };
+        let youngest = self
+            .youngest
+            .is_none_or(|youngest| swimmer.birth_year <= youngest);
+        let oldest = self
+            .oldest
+            .is_none_or(|oldest| swimmer.birth_year >= oldest);
+
+        gender && youngest && oldest
+    }
+}
+
+/// Find the `teams` fastest line-ups for `event`. Each swimmer is used in at most one line-up,
+/// so the first line-up is the A team, the second the B team and so on.
+///
+/// # Errors
+/// Returns an error if the event is not a relay, if the relay cannot be swum with its style and
+/// gender group, or if not even a single legal line-up exists.
+pub fn optimize(
+    event: &Event,
+    constraints: &Constraints,
+    swimmers: &[Swimmer],
+    teams: usize,
+) -> Result<Vec<LineUp>, Error> {
+    let Distance::Team(team) = event.distance else {
+        return Err(Error::NotARelay);
+    };
+    let legs = team.legs();
+    let leg_length = Individual::new(team.leg_length()).map_err(|_| Error::NotARelay)?;
+
+    let strokes: Vec<Stroke> = match event.style {
+        Style::Single(stroke) => vec![stroke; usize::from(legs)],
+        Style::Medley(_) | Style::MedleyHC(_) if legs == 4 => TEAM_MEDLEY.to_vec(),
+        Style::Medley(_) | Style::MedleyHC(_) => return Err(Error::MedleyLegs(legs)),
+    };
+    if constraints.gender_group == GenderGroup::Mixed && !legs.is_multiple_of(2) {
+        return Err(Error::MixedLegs(legs));
+    }
+
+    let mut available: Vec<bool> = swimmers
+        .iter()
+        .map(|swimmer| constraints.allows(swimmer))
+        .collect();
+    let mut line_ups = Vec::new();
+
+    while line_ups.len() < teams {
+        let Some(fastest_remaining) = fastest_remaining(swimmers, &available, &strokes, leg_length)
+        else {
+            break;
+        };
+        let mut search = Search {
+            swimmers,
+            strokes: &strokes,
+            leg_length,
+            constraints,
+            available: &available,
+            same_stroke: matches!(event.style, Style::Single(_)),
+            fastest_remaining,
+            current: Vec::new(),
+            best: None,
+        };
+        search.run(0, Duration::ZERO);
+
+        let Some(line_up) = search.best else {
+            break;
+        };
+        for leg in &line_up.legs {
+            available[leg.swimmer] = false;
+        }
+        line_ups.push(line_up);
+    }
+
+    if line_ups.is_empty() {
+        return Err(Error::NoLegalLineUp);
+    }
+    Ok(line_ups)
+}
+
+/// Sum of the fastest time of any available swimmer on every leg from each leg to the last, a
+/// lower bound of the time the remaining legs can be swum in. `None` if a leg has no swimmer.
+fn fastest_remaining(
+    swimmers: &[Swimmer],
+    available: &[bool],
+    strokes: &[Stroke],
+    leg_length: Individual,
+) -> Option<Vec<Duration>> {
+    let mut remaining = vec![Duration::ZERO; strokes.len() + 1];
+    for (leg, stroke) in strokes.iter().enumerate().rev() {
+        let fastest = swimmers
+            .iter()
+            .zip(available)
+            .filter(|(_, available)| **available)
+            .filter_map(|(swimmer, _)| swimmer.best_times.get(&(leg_length, *stroke)))
+            .min()?;
+        remaining[leg] = remaining[leg + 1] + *fastest;
+    }
+    Some(remaining)
+}
+
+/// Depth first search over legs. Branches are pruned when the total so far plus the fastest
+/// possible remaining legs cannot beat the best total found so far.
+struct Search<'a> {
+    swimmers: &'a [Swimmer],
+    strokes: &'a [Stroke],
+    leg_length: Individual,
+    constraints: &'a Constraints,
+    available: &'a [bool],
+    /// Leg order does not matter when all legs use the same stroke, so swimmers are only combined
+    /// in increasing index order.
+    same_stroke: bool,
+    /// See `fastest_remaining`.
+    fastest_remaining: Vec<Duration>,
+    current: Vec<Leg>,
+    best: Option<LineUp>,
+}
+
+impl Search<'_> {
+    fn time(&self, swimmer: usize, stroke: Stroke) -> Option<Duration> {
+        self.swimmers[swimmer]
+            .best_times
+            .get(&(self.leg_length, stroke))
+            .copied()
+    }
+
+    fn class_points(&self, swimmer: usize, stroke: Stroke) -> Option<u16> {
+        self.swimmers[swimmer]
+            .classification?
+            .get(StyleGroup::from(Style::Single(stroke)))
+            .map(|handicap| u16::from(handicap.disability_type))
+    }
+
+    /// Check whether `swimmer` can swim `stroke` on the next leg without breaking the gender or
+    /// class point rules.
+    fn fits(&self, swimmer: usize, stroke: Stroke) -> bool {
+        if self.constraints.gender_group == GenderGroup::Mixed {
+            let gender = self.swimmers[swimmer].gender;
+            let same_gender = self
+                .current
+                .iter()
+                .filter(|leg| self.swimmers[leg.swimmer].gender == gender)
+                .count();
+            if (same_gender + 1) * 2 > self.strokes.len() {
+                return false;
+            }
+        }
+
+        self.constraints.max_class_points.is_none_or(|max| {
+            let points: Option<u16> = self
+                .current
+                .iter()
+                .map(|leg| self.class_points(leg.swimmer, leg.stroke))
+                .chain([self.class_points(swimmer, stroke)])
+                .sum();
+            points.is_some_and(|points| points <= max)
+        })
+    }
+
+    fn run(&mut self, leg: usize, total: Duration) {
+        let bound = total + self.fastest_remaining[leg];
+        if self.best.as_ref().is_some_and(|best| bound >= best.total) {
+            return;
+        }
+        let Some(&stroke) = self.strokes.get(leg) else {
+            self.best = Some(LineUp {
+                legs: self.current.clone(),
+                total,
+            });
+            return;
+        };
+
+        let first = if self.same_stroke {
+            self.current.last().map_or(0, |leg| leg.swimmer + 1)
+        } else {
+            0
+        };
+
+        for swimmer in first..self.swimmers.len() {
+            if !self.available[swimmer]
+                || self.current.iter().any(|leg| leg.swimmer == swimmer)
+                || !self.fits(swimmer, stroke)
+            {
+                continue;
+            }
+            let Some(time) = self.time(swimmer, stroke) else {
+                continue;
+            };
+
+            self.current.push(Leg {
+                swimmer,
+                stroke,
+                time,
+            });
+            self.run(leg + 1, total + time);
+            self.current.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meet_setup::meet::Meet;
+
+    /// The 4x50m free relay for men of the basic corpus meet.
+    fn relay() -> Event {
+        let meet =
+            Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet");
+        meet.events
+            .into_iter()
+            .find(|event| event.distance.is_team())
+            .expect("relay event")
+    }
+
+    fn medley_relay() -> Event {
+        Event {
+            style: Style::try_from("MEDLEYRELAY").expect("style"),
+            ..relay()
+        }
+    }
+
+    /// Swimmer with 50m best times in the order of `TEAM_MEDLEY`, in seconds.
+    fn swimmer(name: &str, times: [u64; 4]) -> Swimmer {
+        let leg_length = Individual::new(50).expect("distance");
+        Swimmer {
+            name: name.to_string(),
+            gender: GenderGroup::Male,
+            birth_year: Year::new(2000),
+            classification: None,
+            best_times: TEAM_MEDLEY
+                .iter()
+                .zip(times)
+                .map(|(stroke, time)| ((leg_length, *stroke), Duration::from_secs(time)))
+                .collect(),
+        }
+    }
+
+    fn names(line_up: &LineUp, swimmers: &[Swimmer]) -> Vec<String> {
+        line_up
+            .legs
+            .iter()
+            .map(|leg| swimmers[leg.swimmer].name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn medley_strokes_give_the_fastest_total() {
+        // Swimming the fastest swimmer on the first leg gives 122 seconds, on the last leg 121.
+        let swimmers = [
+            swimmer("A", [30, 35, 28, 25]),
+            swimmer("B", [31, 40, 40, 40]),
+            swimmer("C", [40, 36, 40, 40]),
+            swimmer("D", [40, 40, 29, 40]),
+            swimmer("E", [40, 40, 40, 27]),
+        ];
+        let event = medley_relay();
+        let line_ups = optimize(&event, &Constraints::from(&event), &swimmers, 1).expect("team");
+
+        assert_eq!(line_ups[0].total, Duration::from_secs(121));
+        assert_eq!(names(&line_ups[0], &swimmers), ["B", "C", "D", "A"]);
+    }
+
+    #[test]
+    fn swimmers_swim_at_most_one_leg() {
+        let swimmers = [
+            swimmer("A", [20, 20, 20, 20]),
+            swimmer("B", [30, 30, 30, 30]),
+            swimmer("C", [31, 31, 31, 31]),
+            swimmer("D", [32, 32, 32, 32]),
+            swimmer("E", [33, 33, 33, 33]),
+            swimmer("F", [34, 34, 34, 34]),
+            swimmer("G", [35, 35, 35, 35]),
+            swimmer("H", [36, 36, 36, 36]),
+        ];
+        let event = medley_relay();
+        let line_ups = optimize(&event, &Constraints::from(&event), &swimmers, 3).expect("teams");
+
+        assert_eq!(line_ups.len(), 2);
+        let mut used: Vec<usize> = line_ups
+            .iter()
+            .flat_map(|line_up| line_up.legs.iter().map(|leg| leg.swimmer))
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        assert_eq!(used.len(), 8);
+        assert_eq!(line_ups[0].total, Duration::from_secs(20 + 30 + 31 + 32));
+    }
+
+    #[test]
+    fn free_relay_takes_the_fastest_swimmers() {
+        let swimmers = [
+            swimmer("A", [40, 40, 40, 26]),
+            swimmer("B", [40, 40, 40, 29]),
+            swimmer("C", [40, 40, 40, 25]),
+            swimmer("D", [40, 40, 40, 28]),
+            swimmer("E", [40, 40, 40, 27]),
+        ];
+        let event = relay();
+        let line_ups = optimize(&event, &Constraints::from(&event), &swimmers, 1).expect("team");
+
+        assert_eq!(line_ups[0].total, Duration::from_secs(106));
+        assert!(!names(&line_ups[0], &swimmers).contains(&"B".to_string()));
+    }
+
+    #[test]
+    fn too_few_swimmers_is_an_error() {
+        let swimmers = [
+            swimmer("A", [30, 30, 30, 30]),
+            swimmer("B", [30, 30, 30, 30]),
+            swimmer("C", [30, 30, 30, 30]),
+        ];
+        let event = relay();
+        assert!(matches!(
+            optimize(&event, &Constraints::from(&event), &swimmers, 1),
+            Err(Error::NoLegalLineUp)
+        ));
+    }
+
+    #[test]
+    fn swimmers_without_a_time_for_a_leg_are_skipped() {
+        let mut swimmers = [
+            swimmer("A", [30, 30, 30, 30]),
+            swimmer("B", [30, 30, 30, 30]),
+            swimmer("C", [30, 30, 30, 30]),
+            swimmer("D", [30, 30, 30, 30]),
+        ];
+        swimmers[3].best_times.clear();
+        let event = medley_relay();
+        assert!(matches!(
+            optimize(&event, &Constraints::from(&event), &swimmers, 1),
+            Err(Error::NoLegalLineUp)
+        ));
+    }
+
+    #[test]
+    fn individual_events_are_not_relays() {
+        let event = Event {
+            distance: Distance::try_from("100").expect("distance"),
+            ..relay()
+        };
+        assert!(matches!(
+            optimize(&event, &Constraints::from(&event), &[], 1),
+            Err(Error::NotARelay)
+        ));
+    }
+
+    #[test]
+    fn class_points_limit_the_line_up() {
+        let mut swimmers = [
+            swimmer("S10", [40, 40, 40, 25]),
+            swimmer("S9", [40, 40, 40, 26]),
+            swimmer("S8", [40, 40, 40, 27]),
+            swimmer("S7", [40, 40, 40, 28]),
+            swimmer("S4", [40, 40, 40, 35]),
+        ];
+        for swimmer in &mut swimmers {
+            swimmer.classification =
+                Some(Classification::try_from(swimmer.name.as_str()).expect("class"));
+        }
+        let event = Event {
+            description: "4x50m fri S1-S10".to_string(),
+            ..relay()
+        };
+        let constraints = Constraints::from(&event);
+        assert_eq!(constraints.max_class_points, Some(34));
+
+        let line_ups = optimize(&event, &constraints, &swimmers, 1).expect("team");
+        // 10 + 9 + 8 + 7 is 34 points, the fastest team within the limit.
+        assert_eq!(names(&line_ups[0], &swimmers), ["S10", "S9", "S8", "S7"]);
+
+        let constraints = Constraints {
+            max_class_points: Some(30),
+            ..constraints
+        };
+        let line_ups = optimize(&event, &constraints, &swimmers, 1).expect("team");
+        // S8 does not fit with S4, 10 + 9 + 7 + 4 is 30 points.
+        assert_eq!(names(&line_ups[0], &swimmers), ["S10", "S9", "S7", "S4"]);
+    }
+
+    #[test]
+    fn class_points_are_read_from_the_description() {
+        assert_eq!(max_class_points("4x50m fri 34 poeng"), Some(34));
+        assert_eq!(max_class_points("4x100m medley (20p)"), Some(20));
+        assert_eq!(max_class_points("4x100m fri S1 - S10"), Some(34));
+        assert_eq!(max_class_points("4x100m fri S11-13"), Some(49));
+        assert_eq!(max_class_points("4x50m fri herrer"), None);
+    }
+}
@@ -0,0 +1,732 @@
+//! # Roster
+//!
+//! Athletes of a club and their personal bests. The roster is the input to qualification checks,
+//! entry generation and relay line-ups.
+//!
+//! Rosters are read from plain text files with one personal best per line:
+//!
+//! ```text
+//! # name, club, birth year, gender, sport classes, pool length, distance, style, time
+//! Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,50,100,FREESTYLE,1:02.34
+//! Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,25,200,INDIVIDUALMEDLEY,2:31.90
+//! Per Hansen,Bergen Svømmeklubb,2007,MALE,S8 SB7 SM8,,,,
+//! ```
+//!
+//! Athletes are identified by name, club and birth year. Sport classes are separated by white
+//! space. Pool length, distance, style and time are left empty for athletes without personal
+//! bests. Fields containing ',' or '"' are quoted with '"', like `"Kari ""Kiki"" Nordmann"` or
+//! `"Bergen Svømmeklubb, avd. Fana"`. Empty lines and lines starting with `#` are ignored.
+//!
+//! The three stroke handicap medley is written as `HCMEDLEY`, a code only this crate reads.
+//! `INDIVIDUALMEDLEY` over 75m or 150m is read as the handicap medley as well.
+//!
+//! Files with the `.json` extension are read as JSON:
+//!
+//! ```json
+//! {
+//!   "athletes": [
+//!     {
+//!       "name": "Per Hansen",
+//!       "club": "Bergen Svømmeklubb",
+//!       "birth_year": 2007,
+//!       "gender": "MALE",
+//!       "classification": "S8 SB7 SM8",
+//!       "best_times": [{ "pool_length": 50, "distance": "100", "style": "FREESTYLE", "time": "1:12.40" }]
+//!     }
+//!   ]
+//! }
+//! ```
+extern crate gregorian;
+extern crate serde;
+extern crate serde_json;
+use self::gregorian::Year;
+use self::serde::{Deserialize, Serialize};
+use crate::meet_result::{self, MeetResult, Status};
+use crate::meet_setup::{
+    class::Class, classification::Classification, distance::Distance, gender_group::GenderGroup,
+    meet::Meet, pool_length::PoolLength, style::Style,
+};
+use crate::qualification::{BestTimes, Profile};
+use crate::relay::Swimmer;
+use crate::swim_time;
+use crate::trygg_i_vann::{quote, split};
+use std::{convert::TryFrom, fmt::Display, fmt::Write, fs, path::Path, time::Duration};
+
+/// Athlete of a club.
+#[derive(Debug, Clone)]
+pub struct Athlete {
+    pub name: String,
+    pub club: String,
+    pub birth_year: Year,
+    /// Has to be either `GenderGroup::Male` or `GenderGroup::Female`.
+    pub gender: GenderGroup,
+    /// Sport classes of para athletes.
+    pub classification: Option<Classification>,
+    /// Personal bests by distance, style and pool length.
+    pub best_times: BestTimes,
+}
+
+/// Athletes in the order they are listed in the roster file.
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    pub athletes: Vec<Athlete>,
+}
+
+/// Personal best set in a meet. See `Roster::update`.
+#[derive(Debug, Clone)]
+pub struct NewBest {
+    /// Index of the athlete in `Roster::athletes`.
+    pub athlete: usize,
+    pub distance: Distance,
+    pub style: Style,
+    pub pool_length: PoolLength,
+    /// `None` if the athlete had no time in the event before.
+    pub previous: Option<Duration>,
+    pub time: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Name,
+    Club,
+    BirthYear,
+    Gender,
+    Classification,
+    PoolLength,
+    Distance,
+    Style,
+    Time,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    Json(#[from] serde_json::Error),
+    /// Line number and field that is missing.
+    Missing(usize, Field),
+    /// Line number and field that could not be parsed.
+    Invalid(usize, Field),
+    /// Name of the athlete and field that could not be parsed in a JSON roster.
+    InvalidAthlete(String, Field),
+    /// Line number of an athlete that is listed with another gender or other sport classes
+    /// earlier in the file.
+    Conflict(usize),
+    Results(#[from] meet_result::Error),
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Name => write!(f, "name"),
+                Self::Club => write!(f, "club"),
+                Self::BirthYear => write!(f, "birth year"),
+                Self::Gender => write!(f, "gender"),
+                Self::Classification => write!(f, "sport classes"),
+                Self::PoolLength => write!(f, "pool length"),
+                Self::Distance => write!(f, "distance"),
+                Self::Style => write!(f, "style"),
+                Self::Time => write!(f, "time"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Json(err) => write!(f, "invalid roster file: {err}"),
+                Self::Missing(line, field) => write!(f, "line {line}: missing field: {field}"),
+                Self::Invalid(line, field) => write!(f, "line {line}: invalid field: {field}"),
+                Self::InvalidAthlete(name, field) => {
+                    write!(f, "athlete {name}: invalid field: {field}")
+                }
+                Self::Conflict(line) => write!(
+                    f,
+                    "line {line}: athlete is listed with another gender or other sport classes"
+                ),
+                Self::Results(err) => write!(f, "{err}"),
+            },
+        }
+    }
+}
+
+/// Athlete as stored in JSON rosters.
+#[derive(Serialize, Deserialize)]
+struct AthleteRecord {
+    name: String,
+    club: String,
+    birth_year: i16,
+    gender: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    classification: Option<String>,
+    #[serde(default)]
+    best_times: Vec<BestTimeRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BestTimeRecord {
+    pool_length: u8,
+    distance: String,
+    style: String,
+    time: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RosterRecord {
+    athletes: Vec<AthleteRecord>,
+}
+
+impl TryFrom<&str> for Roster {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut roster = Self::default();
+
+        for (index, line) in value.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = split(line, ',');
+            let mut fields = fields.iter().map(|field| field.trim());
+            let mut next = |field| fields.next().ok_or(Error::Missing(line_number, field));
+
+            let name = next(Field::Name)?;
+            if name.is_empty() {
+                return Err(Error::Missing(line_number, Field::Name));
+            }
+            let club = next(Field::Club)?;
+            let birth_year = Year::new(
+                next(Field::BirthYear)?
+                    .parse::<i16>()
+                    .map_err(invalid(line_number, Field::BirthYear))?,
+            );
+            let gender = GenderGroup::try_from(next(Field::Gender)?)
+                .map_err(invalid(line_number, Field::Gender))?;
+            let classification = match next(Field::Classification).unwrap_or_default() {
+                "" => None,
+                classes => Some(
+                    Classification::try_from(classes)
+                        .map_err(invalid(line_number, Field::Classification))?,
+                ),
+            };
+            let pool_length = next(Field::PoolLength).unwrap_or_default();
+            let best_time = if pool_length.is_empty() {
+                None
+            } else {
+                let distance = Distance::try_from(next(Field::Distance)?)
+                    .map_err(invalid(line_number, Field::Distance))?;
+                let style = Style::try_from(next(Field::Style)?)
+                    .map_err(invalid(line_number, Field::Style))?;
+                Some((
+                    distance,
+                    resolve_style(style, distance, classification.as_ref()),
+                    PoolLength::try_from(pool_length)
+                        .map_err(invalid(line_number, Field::PoolLength))?,
+                    swim_time::parse(next(Field::Time)?)
+                        .map_err(invalid(line_number, Field::Time))?,
+                ))
+            };
+
+            let index = if let Some(index) = roster.position(name, club, Some(birth_year)) {
+                index
+            } else {
+                roster.athletes.push(Athlete {
+                    name: name.to_string(),
+                    club: club.to_string(),
+                    birth_year,
+                    gender,
+                    classification,
+                    best_times: BestTimes::new(),
+                });
+                roster.athletes.len() - 1
+            };
+            let athlete = &mut roster.athletes[index];
+            if athlete.gender != gender || athlete.classification != classification {
+                return Err(Error::Conflict(line_number));
+            }
+            if let Some((distance, style, pool_length, time)) = best_time {
+                athlete.improve(distance, style, pool_length, time);
+            }
+        }
+
+        Ok(roster)
+    }
+}
+
+fn invalid<E>(line_number: usize, field: Field) -> impl FnOnce(E) -> Error {
+    move |_| Error::Invalid(line_number, field)
+}
+
+/// Individual medleys of SM1 - SM4 athletes and of 75m and 150m are handicap medleys, also when
+/// the file says INDIVIDUALMEDLEY. See `Style::resolve_handicap_medley`.
+fn resolve_style(
+    style: Style,
+    distance: Distance,
+    classification: Option<&Classification>,
+) -> Style {
+    let class = classification
+        .and_then(|classes| classes.sport_class(style))
+        .map(Class::Handicap);
+    style.resolve_handicap_medley(distance, "", class.as_ref())
+}
+
+impl TryFrom<AthleteRecord> for Athlete {
+    type Error = Error;
+
+    fn try_from(record: AthleteRecord) -> Result<Self, Self::Error> {
+        let name = record.name;
+        let invalid = |field| Error::InvalidAthlete(name.clone(), field);
+
+        let gender =
+            GenderGroup::try_from(record.gender.as_str()).map_err(|_| invalid(Field::Gender))?;
+        let classification = record
+            .classification
+            .filter(|classes| !classes.is_empty())
+            .map(|classes| Classification::try_from(classes.as_str()))
+            .transpose()
+            .map_err(|_| invalid(Field::Classification))?;
+
+        let mut best_times = BestTimes::new();
+        for best_time in record.best_times {
+            let distance = Distance::try_from(best_time.distance.as_str())
+                .map_err(|_| invalid(Field::Distance))?;
+            let style =
+                Style::try_from(best_time.style.as_str()).map_err(|_| invalid(Field::Style))?;
+            let style = resolve_style(style, distance, classification.as_ref());
+            let pool_length = PoolLength::try_from(best_time.pool_length)
+                .map_err(|_| invalid(Field::PoolLength))?;
+            let time = swim_time::parse(&best_time.time).map_err(|_| invalid(Field::Time))?;
+            best_times
+                .entry((distance, style, pool_length))
+                .and_modify(|best_time| *best_time = time.min(*best_time))
+                .or_insert(time);
+        }
+
+        Ok(Self {
+            club: record.club,
+            birth_year: Year::new(record.birth_year),
+            gender,
+            classification,
+            best_times,
+            name,
+        })
+    }
+}
+
+impl From<&Athlete> for AthleteRecord {
+    fn from(athlete: &Athlete) -> Self {
+        Self {
+            name: athlete.name.clone(),
+            club: athlete.club.clone(),
+            birth_year: athlete.birth_year.to_number(),
+            gender: gender_code(athlete.gender).to_string(),
+            classification: athlete.classification.map(|classes| classes.to_string()),
+            best_times: athlete
+                .sorted_best_times()
+                .into_iter()
+                .map(|(distance, style, pool_length, time)| BestTimeRecord {
+                    pool_length: u8::try_from(pool_length.meters()).unwrap_or_default(),
+                    distance: distance_code(distance),
+                    style: style.code().to_string(),
+                    time: swim_time::format(time),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub(crate) const fn gender_code(gender: GenderGroup) -> &'static str {
+    match gender {
+        GenderGroup::Male => "MALE",
+        GenderGroup::Female => "FEMALE",
+        GenderGroup::Mixed => "MIXED",
+    }
+}
+
+pub(crate) fn distance_code(distance: Distance) -> String {
+    match distance {
+        Distance::Individual(individual) => individual.meters().to_string(),
+        Distance::Team(team) => format!("{}*{}", team.legs(), team.leg_length()),
+    }
+}
+
+impl Athlete {
+    /// Personal best in an event, if the athlete has swum it.
+    #[must_use]
+    pub fn best_time(
+        &self,
+        distance: Distance,
+        style: Style,
+        pool_length: PoolLength,
+    ) -> Option<Duration> {
+        self.best_times
+            .get(&(distance, style, pool_length))
+            .copied()
+    }
+
+    /// Store `time` if it is a new personal best. Returns true if it is.
+    pub fn improve(
+        &mut self,
+        distance: Distance,
+        style: Style,
+        pool_length: PoolLength,
+        time: Duration,
+    ) -> bool {
+        if self
+            .best_time(distance, style, pool_length)
+            .is_some_and(|best_time| best_time <= time)
+        {
+            return false;
+        }
+        self.best_times.insert((distance, style, pool_length), time);
+        true
+    }
+
+    /// Input to `qualification::check`.
+    #[must_use]
+    pub const fn profile(&self) -> Profile<'_> {
+        Profile {
+            birth_year: self.birth_year,
+            gender: self.gender,
+            classification: self.classification.as_ref(),
+            best_times: &self.best_times,
+        }
+    }
+
+    /// Input to `relay::optimize` with the single stroke personal bests swum in `pool_length`.
+    #[must_use]
+    pub fn relay_swimmer(&self, pool_length: PoolLength) -> Swimmer {
+        Swimmer {
+            name: self.name.clone(),
+            gender: self.gender,
+            birth_year: self.birth_year,
+            classification: self.classification,
+            best_times: self
+                .best_times
+                .iter()
+                .filter(|((_, _, length), _)| *length == pool_length)
+                .filter_map(|((distance, style, _), time)| match (distance, style) {
+                    (Distance::Individual(individual), Style::Single(stroke)) => {
+                        Some(((*individual, *stroke), *time))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Personal bests ordered by pool length, style and distance.
+    fn sorted_best_times(&self) -> Vec<(Distance, Style, PoolLength, Duration)> {
+        let mut best_times: Vec<_> = self
+            .best_times
+            .iter()
+            .map(|(&(distance, style, pool_length), &time)| (distance, style, pool_length, time))
+            .collect();
+        best_times.sort_by_key(|(distance, style, pool_length, _)| {
+            (pool_length.meters(), style.code(), distance.meters())
+        });
+        best_times
+    }
+}
+
+impl Roster {
+    /// Read a roster. Files with the `.json` extension are read as JSON, all other files as
+    /// plain text.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or if any of the athletes are invalid.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        if is_json(path) {
+            Self::from_json(&content)
+        } else {
+            Self::try_from(content.as_str())
+        }
+    }
+
+    /// # Errors
+    /// Returns an error if `json` is not a valid roster.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let record: RosterRecord = serde_json::from_str(json)?;
+        Ok(Self {
+            athletes: record
+                .athletes
+                .into_iter()
+                .map(Athlete::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Write the roster in the format given by the extension of `path`. See `Roster::from_file`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let content = if is_json(path) {
+            self.to_json()?
+        } else {
+            self.to_text()
+        };
+        Ok(fs::write(path, content)?)
+    }
+
+    /// # Errors
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, Error> {
+        let record = RosterRecord {
+            athletes: self.athletes.iter().map(AthleteRecord::from).collect(),
+        };
+        Ok(serde_json::to_string_pretty(&record)?)
+    }
+
+    /// Plain text roster with one personal best per line.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "# name, club, birth year, gender, sport classes, pool length, distance, style, time\n",
+        );
+        for athlete in &self.athletes {
+            let athlete_fields = format!(
+                "{},{},{},{},{}",
+                quote(&athlete.name, ','),
+                quote(&athlete.club, ','),
+                athlete.birth_year.to_number(),
+                gender_code(athlete.gender),
+                athlete
+                    .classification
+                    .map(|classes| classes.to_string())
+                    .unwrap_or_default()
+            );
+            let best_times = athlete.sorted_best_times();
+            if best_times.is_empty() {
+                let _ = writeln!(text, "{athlete_fields},,,,");
+            }
+            for (distance, style, pool_length, time) in best_times {
+                let _ = writeln!(
+                    text,
+                    "{athlete_fields},{},{},{},{}",
+                    pool_length.meters(),
+                    distance_code(distance),
+                    style.code(),
+                    swim_time::format(time)
+                );
+            }
+        }
+        text
+    }
+
+    /// Athletes of `club`.
+    pub fn club<'a>(&'a self, club: &'a str) -> impl Iterator<Item = &'a Athlete> + 'a {
+        self.athletes
+            .iter()
+            .filter(move |athlete| athlete.club == club)
+    }
+
+    /// Find an athlete by name and club. The birth year is compared as well when it is known.
+    #[must_use]
+    pub fn find(&self, name: &str, club: &str, birth_year: Option<Year>) -> Option<&Athlete> {
+        self.position(name, club, birth_year)
+            .map(|index| &self.athletes[index])
+    }
+
+    fn position(&self, name: &str, club: &str, birth_year: Option<Year>) -> Option<usize> {
+        self.athletes.iter().position(|athlete| {
+            athlete.name == name
+                && athlete.club == club
+                && birth_year.is_none_or(|birth_year| athlete.birth_year == birth_year)
+        })
+    }
+
+    /// Update personal bests with the finished individual swims in `results` of athletes in the
+    /// roster. Returns the new personal bests in result order.
+    ///
+    /// # Errors
+    /// Returns an error if the results contain an event that is not in `meet`.
+    pub fn update(&mut self, meet: &Meet, results: &MeetResult) -> Result<Vec<NewBest>, Error> {
+        let mut new_bests = Vec::new();
+
+        for (event, event_result) in results.with_events(meet)? {
+            if event.distance.is_team() {
+                continue;
+            }
+            let swims = event_result
+                .classes
+                .iter()
+                .flat_map(|class| &class.results)
+                .filter(|result| result.status == Status::Finished);
+            for result in swims {
+                let Some(time) = result.time else {
+                    continue;
+                };
+                let Some(index) = self.position(&result.name, &result.club, result.birth_year)
+                else {
+                    continue;
+                };
+                let athlete = &mut self.athletes[index];
+                let previous = athlete.best_time(event.distance, event.style, event.pool_length);
+                if !athlete.improve(event.distance, event.style, event.pool_length, time) {
+                    continue;
+                }
+                new_bests.push(NewBest {
+                    athlete: index,
+                    distance: event.distance,
+                    style: event.style,
+                    pool_length: event.pool_length,
+                    previous,
+                    time,
+                });
+            }
+        }
+
+        Ok(new_bests)
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROSTER: &str = r#"# name, club, birth year, gender, sport classes, pool length, distance, style, time
+Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,50,100,FREESTYLE,1:02.34
+Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,50,100,FREESTYLE,1:03.00
+Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,25,200,INDIVIDUALMEDLEY,2:31.90
+
+"Per ""Pelle"" Hansen","Bergen Svømmeklubb, avd. Fana",2007,MALE,S8 SB7 SM3,25,150,INDIVIDUALMEDLEY,3:10.00
+Ola Nordmann,Fana IL,2010,MALE,,,,,
+"#;
+
+    fn roster() -> Roster {
+        Roster::try_from(ROSTER).expect("valid roster")
+    }
+
+    fn event(distance: &str, style: &str) -> (Distance, Style) {
+        (
+            Distance::try_from(distance).expect("valid distance"),
+            Style::try_from(style).expect("valid style"),
+        )
+    }
+
+    #[test]
+    fn lines_of_an_athlete_are_merged_with_the_best_time_kept() {
+        let roster = roster();
+        assert_eq!(roster.athletes.len(), 3);
+
+        let kari = &roster.athletes[0];
+        assert_eq!(kari.birth_year, Year::new(2008));
+        assert_eq!(kari.gender, GenderGroup::Female);
+        assert_eq!(kari.best_times.len(), 2);
+        let (distance, style) = event("100", "FREESTYLE");
+        assert_eq!(
+            kari.best_time(distance, style, PoolLength::PoolLength50),
+            Some(swim_time::parse("1:02.34").expect("valid time"))
+        );
+
+        let ola = &roster.athletes[2];
+        assert!(ola.best_times.is_empty());
+        assert!(ola.classification.is_none());
+    }
+
+    #[test]
+    fn quoted_fields_keep_separators_and_quotes() {
+        let roster = roster();
+        let per = &roster.athletes[1];
+        assert_eq!(per.name, r#"Per "Pelle" Hansen"#);
+        assert_eq!(per.club, "Bergen Svømmeklubb, avd. Fana");
+        assert_eq!(
+            per.classification.map(|classes| classes.to_string()),
+            Some("S8 SB7 SM3".to_string())
+        );
+        let (distance, style) = event("150", "HCMEDLEY");
+        assert!(per
+            .best_time(distance, style, PoolLength::PoolLength25)
+            .is_some());
+        assert_eq!(
+            roster
+                .find(&per.name, "Bergen Svømmeklubb, avd. Fana", None)
+                .map(|athlete| athlete.birth_year),
+            Some(Year::new(2007))
+        );
+    }
+
+    #[test]
+    fn to_text_round_trips() {
+        let roster = roster();
+        let text = roster.to_text();
+        assert!(text.contains(
+            r#""Per ""Pelle"" Hansen","Bergen Svømmeklubb, avd. Fana",2007,MALE,S8 SB7 SM3,25,150,HCMEDLEY,3:10.00"#
+        ));
+        assert!(text.contains("Ola Nordmann,Fana IL,2010,MALE,,,,,\n"));
+
+        let read = Roster::try_from(text.as_str()).expect("valid roster");
+        assert_eq!(read.to_text(), text);
+        for (read, athlete) in read.athletes.iter().zip(&roster.athletes) {
+            assert_eq!(read.name, athlete.name);
+            assert_eq!(read.club, athlete.club);
+            assert_eq!(read.best_times, athlete.best_times);
+        }
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let roster = roster();
+        let json = roster.to_json().expect("serializable roster");
+        let read = Roster::from_json(&json).expect("valid roster");
+        assert_eq!(read.to_text(), roster.to_text());
+    }
+
+    #[test]
+    fn invalid_lines_report_line_and_field() {
+        let error = |text| Roster::try_from(text).expect_err("invalid roster");
+        assert!(matches!(
+            error(",Fana IL,2010,MALE"),
+            Error::Missing(1, Field::Name)
+        ));
+        assert!(matches!(
+            error("# header\nOla Nordmann,Fana IL,2010"),
+            Error::Missing(2, Field::Gender)
+        ));
+        assert!(matches!(
+            error("Ola Nordmann,Fana IL,tjue,MALE"),
+            Error::Invalid(1, Field::BirthYear)
+        ));
+        assert!(matches!(
+            error("Ola Nordmann,Fana IL,2010,MALE,S99"),
+            Error::Invalid(1, Field::Classification)
+        ));
+        assert!(matches!(
+            error("Ola Nordmann,Fana IL,2010,MALE,,50,100,CRAWL,1:00.00"),
+            Error::Invalid(1, Field::Style)
+        ));
+        assert!(matches!(
+            error("Ola Nordmann,Fana IL,2010,MALE,,50,100,FREESTYLE"),
+            Error::Missing(1, Field::Time)
+        ));
+        assert!(matches!(
+            error("Ola Nordmann,Fana IL,2010,MALE\nOla Nordmann,Fana IL,2010,FEMALE"),
+            Error::Conflict(2)
+        ));
+    }
+
+    #[test]
+    fn unquoted_separator_in_a_club_is_an_error() {
+        assert!(matches!(
+            Roster::try_from("Ola Nordmann,Fana IL, avd. Nesttun,2010,MALE"),
+            Err(Error::Invalid(1, Field::BirthYear))
+        ));
+    }
+}
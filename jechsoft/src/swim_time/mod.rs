@@ -0,0 +1,97 @@
+//! Swim times as they are written by officials and in result lists.
+//!
+//! Accepted formats are `ss.hh`, `m:ss.hh` and `mm:ss.hh` where `hh` is hundredths of a second.
+//! Times are represented as `Duration` across the crate.
+use std::{fmt::Display, num::ParseIntError, time::Duration};
+
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum Error {
+    Empty,
+    InvalidFormat,
+    Parse(ParseIntError),
+    SecondsOutOfRange,
+    TooLong,
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Empty => write!(f, "received an empty string, expected a swim time"),
+                Self::InvalidFormat => write!(
+                    f,
+                    "invalid swim time format. Expected 'ss.hh', 'm:ss.hh' or 'mm:ss.hh'"
+                ),
+                Self::Parse(err) => write!(f, "swim time is not a number: {err}"),
+                Self::SecondsOutOfRange => {
+                    write!(f, "seconds has to be less than 60 when minutes are present")
+                }
+                Self::TooLong => write!(f, "swim time is too long"),
+            },
+        }
+    }
+}
+
+/// Parse a swim time.
+///
+/// # Errors
+/// Returns an error if `value` is empty, is not formatted like `mm:ss.hh`, if the seconds part
+/// is 60 or more while minutes are present or if the time does not fit in a `Duration`.
+pub fn parse(value: &str) -> Result<Duration, Error> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    let (minutes, rest) = match value.split_once(':') {
+        Some((minutes, rest)) => (Some(minutes), rest),
+        None => (None, value),
+    };
+    let (seconds, hundredths) = rest.split_once('.').ok_or(Error::InvalidFormat)?;
+
+    if hundredths.len() != 2 || !hundredths.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidFormat);
+    }
+    if seconds.is_empty() || !seconds.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidFormat);
+    }
+
+    let minutes = match minutes {
+        Some(minutes) if minutes.is_empty() || !minutes.chars().all(|c| c.is_ascii_digit()) => {
+            return Err(Error::InvalidFormat)
+        }
+        Some(minutes) => minutes.parse::<u64>().map_err(Error::Parse)?,
+        None => 0,
+    };
+    let seconds = seconds.parse::<u64>().map_err(Error::Parse)?;
+    let hundredths = hundredths.parse::<u64>().map_err(Error::Parse)?;
+
+    if minutes > 0 && seconds >= 60 {
+        return Err(Error::SecondsOutOfRange);
+    }
+
+    minutes
+        .checked_mul(60)
+        .and_then(|minutes| minutes.checked_add(seconds))
+        .and_then(|seconds| seconds.checked_mul(1000))
+        .and_then(|millis| millis.checked_add(hundredths * 10))
+        .map(Duration::from_millis)
+        .ok_or(Error::TooLong)
+}
+
+/// Format a swim time as `ss.hh` or `m:ss.hh`. Anything below hundredths of a second is truncated.
+#[must_use]
+pub fn format(duration: Duration) -> String {
+    let hundredths = duration.as_millis() / 10;
+    let minutes = hundredths / 6000;
+    let seconds = hundredths / 100 % 60;
+    let hundredths = hundredths % 100;
+
+    if minutes == 0 {
+        format!("{seconds}.{hundredths:0>2}")
+    } else {
+        format!("{minutes}:{seconds:0>2}.{hundredths:0>2}")
+    }
+}
@@ -0,0 +1,561 @@
+//! # Trygg i vann
+//!
+//! Parses `tryggivann.csv` exports of water safety certifications ("Trygg i vann") into
+//! `Certificate`s and summarizes them per group.
+//!
+//! Exports are spreadsheets saved as CSV with a header row. Fields are separated by ';' or ','
+//! and may be quoted with '"':
+//!
+//! ```text
+//! Navn;Fødselsår;Gruppe;Dato;Hoppe uti på dypt vann;Svømme 100 m;Flyte på rygg 2,5 min
+//! Kari Nordmann;2016;Delfin;12.06.2024;Bestått;Bestått;Ikke bestått
+//! Ola Nordmann;2016;Delfin;12.06.2024;x;x;x
+//! ```
+//!
+//! Columns are recognized by their header, ignoring case:
+//! - name: "Navn" or "Name", or "Fornavn" and "Etternavn",
+//! - birth year: "Fødselsår", "Født", "Fødselsdato", "Birth year" or "Birth date". Optional,
+//! - group: "Gruppe", "Parti", "Klasse" or "Group". Optional,
+//! - date of the test: "Dato", "Testdato" or "Date",
+//! - overall result: "Resultat", "Bestått", "Godkjent" or "Result". Optional. Certificates
+//!   without it are passed when every test item is passed.
+//!
+//! Every other column is a test item. Items are passed with "Bestått", "Godkjent", "Ja", "x",
+//! "1" or "OK", failed with "Ikke bestått", "Ikke godkjent", "Nei" or "0" and not tested when the
+//! field is empty or "-". Dates are written as 'dd.mm.yyyy' or 'yyyy-mm-dd'.
+//!
+//! Spreadsheets save CSV as UTF-8 or Windows-1252, files are decoded with
+//! `meet_setup::decode::to_utf8`.
+//!
+//! Rows that cannot be parsed are collected in `Export::invalid` with their line number instead
+//! of failing the whole file.
+extern crate chrono;
+extern crate gregorian;
+extern crate serde;
+extern crate serde_json;
+use self::chrono::{Datelike, NaiveDate};
+use self::gregorian::Year;
+use self::serde::Serialize;
+use crate::meet_setup::decode::to_utf8;
+use std::{collections::BTreeMap, convert::TryFrom, fmt::Display, fs, path::Path};
+
+/// Outcome of a single test item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    NotTested,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestItem {
+    /// Name of the item as written in the header.
+    pub name: String,
+    pub outcome: Outcome,
+}
+
+/// Water safety certification of one athlete.
+#[derive(Debug, Clone, Serialize)]
+pub struct Certificate {
+    /// Line number in the export.
+    pub line: usize,
+    pub name: String,
+    pub birth_year: Option<Year>,
+    pub group: Option<String>,
+    #[serde(serialize_with = "date")]
+    pub date: NaiveDate,
+    pub items: Vec<TestItem>,
+    pub passed: bool,
+}
+
+/// Parsed `tryggivann.csv` export.
+#[derive(Debug, Default, Serialize)]
+pub struct Export {
+    pub certificates: Vec<Certificate>,
+    /// Rows that could not be parsed.
+    #[serde(skip)]
+    pub invalid: Vec<Error>,
+}
+
+/// Certificates of one group. See `Export::summary`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSummary {
+    /// `None` for certificates without a group.
+    pub group: Option<String>,
+    pub certificates: usize,
+    pub passed: usize,
+    /// Number of athletes that passed each test item, in header order.
+    pub items: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Name,
+    BirthYear,
+    Group,
+    Date,
+    Result,
+    /// Index of the test item in the header.
+    Item(usize),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    Json(#[from] serde_json::Error),
+    /// The export has no header row.
+    Empty,
+    /// The header has no column for the field.
+    MissingColumn(Field),
+    /// Line number and field that is missing.
+    Missing(usize, Field),
+    /// Line number and field that could not be parsed.
+    Invalid(usize, Field),
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Name => write!(f, "name"),
+                Self::BirthYear => write!(f, "birth year"),
+                Self::Group => write!(f, "group"),
+                Self::Date => write!(f, "date"),
+                Self::Result => write!(f, "result"),
+                Self::Item(index) => write!(f, "test item {}", index + 1),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(err) => write!(f, "{err}"),
+                Self::Json(err) => write!(f, "{err}"),
+                Self::Empty => write!(f, "export has no header row"),
+                Self::MissingColumn(field) => write!(f, "export has no {field} column"),
+                Self::Missing(line, field) => write!(f, "line {line}: missing field: {field}"),
+                Self::Invalid(line, field) => write!(f, "line {line}: invalid field: {field}"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Passed => write!(f, "passed"),
+                Self::Failed => write!(f, "failed"),
+                Self::NotTested => write!(f, "not tested"),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for Outcome {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().as_str() {
+            "bestått" | "godkjent" | "ja" | "x" | "1" | "ok" | "passed" | "yes" => {
+                Ok(Self::Passed)
+            }
+            "ikke bestått" | "ikke godkjent" | "nei" | "0" | "failed" | "no" => Ok(Self::Failed),
+            "" | "-" => Ok(Self::NotTested),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Column of the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    FirstName,
+    LastName,
+    BirthYear,
+    Group,
+    Date,
+    Result,
+    Item,
+}
+
+impl From<&str> for Column {
+    fn from(header: &str) -> Self {
+        match header.trim().to_lowercase().as_str() {
+            "navn" | "name" | "deltaker" => Self::Name,
+            "fornavn" | "first name" => Self::FirstName,
+            "etternavn" | "last name" => Self::LastName,
+            "fødselsår" | "født" | "fødselsdato" | "birth year" | "birth date" => {
+                Self::BirthYear
+            }
+            "gruppe" | "parti" | "klasse" | "group" => Self::Group,
+            "dato" | "testdato" | "date" => Self::Date,
+            "resultat" | "bestått" | "godkjent" | "result" => Self::Result,
+            _ => Self::Item,
+        }
+    }
+}
+
+impl TryFrom<&str> for Export {
+    type Error = Error;
+
+    /// # Errors
+    /// Returns an error if the header is missing or has no name or date column. Invalid rows are
+    /// collected in `Export::invalid`.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut lines = value
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_start_matches('\u{feff}')))
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines.next().ok_or(Error::Empty)?;
+        let separator = if header.contains(';') { ';' } else { ',' };
+        let headers = split(header, separator);
+        let columns: Vec<Column> = headers
+            .iter()
+            .map(|header| Column::from(header.as_str()))
+            .collect();
+        let has_name = columns.contains(&Column::Name)
+            || (columns.contains(&Column::FirstName) && columns.contains(&Column::LastName));
+        if !has_name {
+            return Err(Error::MissingColumn(Field::Name));
+        }
+        if !columns.contains(&Column::Date) {
+            return Err(Error::MissingColumn(Field::Date));
+        }
+
+        let mut export = Self::default();
+        for (line_number, line) in lines {
+            match certificate(line_number, &split(line, separator), &headers, &columns) {
+                Ok(certificate) => export.certificates.push(certificate),
+                Err(error) => export.invalid.push(error),
+            }
+        }
+        Ok(export)
+    }
+}
+
+/// Parse the fields of one row.
+fn certificate(
+    line_number: usize,
+    fields: &[String],
+    headers: &[String],
+    columns: &[Column],
+) -> Result<Certificate, Error> {
+    let mut name = String::new();
+    let mut first_name = String::new();
+    let mut last_name = String::new();
+    let mut birth_year = None;
+    let mut group = None;
+    let mut date = None;
+    let mut result = None;
+    let mut items = Vec::new();
+
+    for (index, (column, header)) in columns.iter().zip(headers).enumerate() {
+        let value = fields.get(index).map_or("", |value| value.trim());
+        match column {
+            Column::Name => value.clone_into(&mut name),
+            Column::FirstName => value.clone_into(&mut first_name),
+            Column::LastName => value.clone_into(&mut last_name),
+            Column::BirthYear if !value.is_empty() => {
+                birth_year = Some(
+                    parse_birth_year(value).ok_or(Error::Invalid(line_number, Field::BirthYear))?,
+                );
+            }
+            Column::Group if !value.is_empty() => group = Some(value.to_string()),
+            Column::Date if !value.is_empty() => {
+                date = Some(parse_date(value).ok_or(Error::Invalid(line_number, Field::Date))?);
+            }
+            Column::Result if !value.is_empty() => {
+                result = Some(
+                    Outcome::try_from(value)
+                        .map_err(|()| Error::Invalid(line_number, Field::Result))?,
+                );
+            }
+            Column::Item => items.push(TestItem {
+                name: header.trim().to_string(),
+                outcome: Outcome::try_from(value)
+                    .map_err(|()| Error::Invalid(line_number, Field::Item(items.len())))?,
+            }),
+            Column::BirthYear | Column::Group | Column::Date | Column::Result => {}
+        }
+    }
+
+    if name.is_empty() {
+        name = format!("{first_name} {last_name}").trim().to_string();
+    }
+    if name.is_empty() {
+        return Err(Error::Missing(line_number, Field::Name));
+    }
+    let date = date.ok_or(Error::Missing(line_number, Field::Date))?;
+    let passed = result.map_or_else(
+        || !items.is_empty() && items.iter().all(|item| item.outcome == Outcome::Passed),
+        |result| result == Outcome::Passed,
+    );
+
+    Ok(Certificate {
+        line: line_number,
+        name,
+        birth_year,
+        group,
+        date,
+        items,
+        passed,
+    })
+}
+
+/// Split a CSV line. Fields quoted with '"' may contain the separator and '""' for a quote.
+pub(crate) fn split(line: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Quote `field` if it contains the separator or '"', so `split` reads it back as one field.
+pub(crate) fn quote(field: &str, separator: char) -> String {
+    if field.contains([separator, '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%d.%m.%Y")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Birth year written as a year or as a birth date.
+fn parse_birth_year(value: &str) -> Option<Year> {
+    value.parse::<i16>().ok().map_or_else(
+        || {
+            parse_date(value)
+                .and_then(|date| i16::try_from(date.year()).ok())
+                .map(Year::new)
+        },
+        |year| Some(Year::new(year)),
+    )
+}
+
+// serde passes fields by reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn date<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&date.format("%Y-%m-%d").to_string())
+}
+
+impl Export {
+    /// # Errors
+    /// Returns an error if the file cannot be read or if the header is invalid.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = to_utf8(&fs::read(path)?);
+        Self::try_from(content.as_str())
+    }
+
+    /// # Errors
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Number of certificates, passed certificates and passed test items per group, ordered by
+    /// group name. Certificates without a group come last.
+    #[must_use]
+    pub fn summary(&self) -> Vec<GroupSummary> {
+        let mut groups: BTreeMap<(bool, Option<&str>), GroupSummary> = BTreeMap::new();
+
+        for certificate in &self.certificates {
+            let group = certificate.group.as_deref();
+            let summary = groups
+                .entry((group.is_none(), group))
+                .or_insert_with(|| GroupSummary {
+                    group: group.map(ToString::to_string),
+                    certificates: 0,
+                    passed: 0,
+                    items: Vec::new(),
+                });
+            summary.certificates += 1;
+            if certificate.passed {
+                summary.passed += 1;
+            }
+            for item in &certificate.items {
+                let index = summary
+                    .items
+                    .iter()
+                    .position(|(name, _)| *name == item.name)
+                    .unwrap_or_else(|| {
+                        summary.items.push((item.name.clone(), 0));
+                        summary.items.len() - 1
+                    });
+                if item.outcome == Outcome::Passed {
+                    summary.items[index].1 += 1;
+                }
+            }
+        }
+
+        groups.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "Navn;Fødselsår;Gruppe;Dato;Hoppe uti;Svømme 100 m
+Kari Nordmann;2016;Delfin;12.06.2024;Bestått;Bestått
+
+Ola Nordmann;2016;Delfin;12.06.2024;x;Ikke bestått
+Per Hansen;2015;Sel;2024-06-13;ja;-
+Anne Hansen;;;13.06.2024;1;1
+";
+
+    #[test]
+    fn split_handles_quotes() {
+        assert_eq!(
+            split(r#"a;"b;c";"d ""e""";"#, ';'),
+            ["a", "b;c", r#"d "e""#, ""]
+        );
+        assert_eq!(split("a,b", ','), ["a", "b"]);
+    }
+
+    #[test]
+    fn columns_are_found_by_header() {
+        let export = Export::try_from(EXPORT).expect("valid export");
+        assert!(export.invalid.is_empty());
+        assert_eq!(export.certificates.len(), 4);
+
+        let kari = &export.certificates[0];
+        assert_eq!(kari.name, "Kari Nordmann");
+        assert_eq!(kari.birth_year, Some(Year::new(2016)));
+        assert_eq!(kari.group.as_deref(), Some("Delfin"));
+        assert_eq!(
+            kari.date,
+            NaiveDate::from_ymd_opt(2024, 6, 12).expect("date")
+        );
+        assert_eq!(kari.items.len(), 2);
+        assert_eq!(kari.items[1].name, "Svømme 100 m");
+        assert!(kari.passed);
+        assert!(!export.certificates[1].passed);
+        assert!(!export.certificates[2].passed);
+        assert_eq!(export.certificates[2].items[1].outcome, Outcome::NotTested);
+    }
+
+    #[test]
+    fn comma_separated_with_first_and_last_name() {
+        let export = Export::try_from(
+            "\u{feff}Fornavn,Etternavn,Testdato,Resultat,\"Flyte, 2,5 min\"\nKari,Nordmann,12.06.2024,Godkjent,Nei\n",
+        )
+        .expect("valid export");
+        let certificate = &export.certificates[0];
+
+        assert_eq!(certificate.name, "Kari Nordmann");
+        assert_eq!(certificate.items[0].name, "Flyte, 2,5 min");
+        // The overall result wins over the test items.
+        assert!(certificate.passed);
+    }
+
+    #[test]
+    fn header_without_name_or_date_is_an_error() {
+        assert!(matches!(Export::try_from(""), Err(Error::Empty)));
+        assert!(matches!(
+            Export::try_from("Fornavn;Dato\n"),
+            Err(Error::MissingColumn(Field::Name))
+        ));
+        assert!(matches!(
+            Export::try_from("Navn;Gruppe\n"),
+            Err(Error::MissingColumn(Field::Date))
+        ));
+    }
+
+    #[test]
+    fn invalid_rows_are_collected_with_their_line_number() {
+        let export = Export::try_from(
+            "Navn;Dato;Svømme 100 m;Dykke
+
+Kari Nordmann;12.06.2024;x;x
+Ola Nordmann;31.02.2024;x;x
+;12.06.2024;x;x
+Per Hansen;12.06.2024;x;kanskje
+Anne Hansen;;x;x
+",
+        )
+        .expect("valid export");
+
+        assert_eq!(export.certificates.len(), 1);
+        assert_eq!(export.certificates[0].line, 3);
+        let invalid: Vec<String> = export.invalid.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            invalid,
+            [
+                "line 4: invalid field: date",
+                "line 5: missing field: name",
+                "line 6: invalid field: test item 2",
+                "line 7: missing field: date",
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_per_group() {
+        let summary = Export::try_from(EXPORT).expect("valid export").summary();
+        let groups: Vec<Option<&str>> =
+            summary.iter().map(|group| group.group.as_deref()).collect();
+        assert_eq!(groups, [Some("Delfin"), Some("Sel"), None]);
+
+        let delfin = &summary[0];
+        assert_eq!(delfin.certificates, 2);
+        assert_eq!(delfin.passed, 1);
+        assert_eq!(
+            delfin.items,
+            [
+                ("Hoppe uti".to_string(), 2),
+                ("Svømme 100 m".to_string(), 1)
+            ]
+        );
+        assert_eq!(summary[2].passed, 1);
+    }
+
+    #[test]
+    fn windows_1252_files_are_decoded() {
+        let path = std::env::temp_dir().join(format!("tryggivann-{}.csv", std::process::id()));
+        let mut bytes = b"Navn;F\xF8dsels\xE5r;Dato\n".to_vec();
+        bytes.extend_from_slice(b"Kari S\xE6ther;2016;12.06.2024\n");
+        fs::write(&path, bytes).expect("write export");
+        let export = Export::from_file(&path);
+        let _ = fs::remove_file(&path);
+        let export = export.expect("valid export");
+
+        assert_eq!(export.certificates[0].name, "Kari Sæther");
+        assert_eq!(export.certificates[0].birth_year, Some(Year::new(2016)));
+    }
+}
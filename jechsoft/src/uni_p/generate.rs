@@ -0,0 +1,651 @@
+//! Generates `uni_p.txt` enrollment files from a `Roster` and a selection of athletes and events.
+//!
+//! The first line holds the club name and every following line one individual entry:
+//!
+//! ```text
+//! Bergen Svømmeklubb
+//! 1,100,FR,Nordmann,Kari,,K,K08,,2008,01:02.34,L
+//! ```
+//!
+//! Fields are event number, distance, style, surname, first name, an empty field, gender ('M' or
+//! 'K'), `GenderClass` code, sport class, birth year, entry time and the pool length of the entry
+//! time ('K' for 25m and 'L' for 50m). Entries without a time leave the last two fields empty.
+//! The handicap medley has no style of its own and is written as individual medley, "IM".
+//!
+//! Fields are not quoted, so names containing ',' cannot be written. Such athletes are skipped,
+//! and a club with such a name gets no entries, see `Warning::Separator`.
+//!
+//! Relay entries are not generated.
+use crate::meet_setup::{
+    class::Class, distance::Distance, event::Event, gender_class::GenderClass,
+    gender_group::GenderGroup, meet::Meet, pool_length::PoolLength, style::Stroke, style::Style,
+    style::TEAM_MEDLEY,
+};
+use crate::qualification::{check_event, is_senior, Status};
+use crate::roster::{Athlete, Roster};
+use std::{fmt::Display, fmt::Write, time::Duration};
+
+/// Ratio between long course and short course times used to convert entry times between pool
+/// lengths. A 50m pool has fewer turns, so long course times are slower.
+const COURSE_FACTOR: f64 = 1.02;
+
+/// Field separator of `uni_p.txt`.
+const SEPARATOR: char = ',';
+
+/// Athlete selected for an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Index of the athlete in `Roster::athletes`.
+    pub athlete: usize,
+    /// Event number.
+    pub event: u32,
+}
+
+/// Entry time taken from a personal best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryTime {
+    pub time: Duration,
+    pub pool_length: PoolLength,
+    /// True if the personal best was swum in the other pool length and converted.
+    pub converted: bool,
+}
+
+/// One entry of an athlete in an event.
+#[derive(Debug, Clone, Copy)]
+pub struct Enrollment<'a> {
+    pub athlete: &'a Athlete,
+    pub event: &'a Event,
+    pub gender_class: GenderClass,
+    /// `None` if the athlete has no eligible personal best.
+    pub entry_time: Option<EntryTime>,
+}
+
+/// Problems found while generating entries. Entries with warnings that are marked as skipped are
+/// left out of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The selected event is not in the meet. Skipped.
+    UnknownEvent(u32),
+    /// The selection refers to an athlete that is not in the roster. Skipped.
+    UnknownAthlete(usize),
+    /// Relay entries are not generated. Skipped.
+    Relay(u32),
+    /// Athlete name and event number of an athlete that cannot swim the event because of gender
+    /// or age. Skipped.
+    NotEligible(String, u32),
+    /// Athlete name and event number of an entry that is selected more than once. Skipped.
+    Duplicate(String, u32),
+    /// Athlete name and event number of an athlete in a class the competition type does not
+    /// allow, see `CompetitionType::rules`. Skipped.
+    ClassNotAllowed(String, u32),
+    /// Athlete name and event number of an athlete that does not meet the qualification limits.
+    /// Skipped if the competition type requires qualification.
+    NotQualified(String, u32),
+    /// Athlete name and event number of an entry without an entry time.
+    NoEntryTime(String, u32),
+    /// Athlete name and event number of an entry time that is converted from the other pool
+    /// length.
+    Converted(String, u32),
+    /// Club or athlete name that contains the field separator ','. Entries of the athlete are
+    /// skipped, and no entries are generated for the club.
+    Separator(String),
+}
+
+/// Entries and warnings of a club. See `generate`.
+#[derive(Debug, Clone)]
+pub struct Enrollments<'a> {
+    pub club: String,
+    pub entries: Vec<Enrollment<'a>>,
+    pub warnings: Vec<Warning>,
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::UnknownEvent(event) => write!(f, "event {event} is not in the meet"),
+                Self::UnknownAthlete(index) => write!(f, "athlete {index} is not in the roster"),
+                Self::Relay(event) => write!(f, "event {event} is a relay and is not entered"),
+                Self::NotEligible(name, event) => {
+                    write!(f, "{name} cannot swim event {event}")
+                }
+                Self::Duplicate(name, event) => {
+                    write!(f, "{name} is selected for event {event} more than once")
+                }
+                Self::ClassNotAllowed(name, event) => {
+                    write!(f, "{name} is in a class that cannot enter event {event}")
+                }
+                Self::NotQualified(name, event) => {
+                    write!(f, "{name} is not qualified for event {event}")
+                }
+                Self::NoEntryTime(name, event) => {
+                    write!(f, "{name} has no entry time in event {event}")
+                }
+                Self::Converted(name, event) => write!(
+                    f,
+                    "entry time of {name} in event {event} is converted from the other pool length"
+                ),
+                Self::Separator(name) => {
+                    write!(f, "{name} contains '{SEPARATOR}' and cannot be written")
+                }
+            },
+        }
+    }
+}
+
+/// Create entries for `club` from `selections`, in the order of the selections. Entries the
+/// competition type of the meet does not allow are skipped, see `CompetitionType::rules`.
+#[must_use]
+pub fn generate<'a>(
+    meet: &'a Meet,
+    roster: &'a Roster,
+    club: &str,
+    selections: &[Selection],
+) -> Enrollments<'a> {
+    let mut entries: Vec<Enrollment> = Vec::new();
+    let mut warnings = Vec::new();
+    let rules = meet.competition_type_id.rules();
+
+    if club.contains(SEPARATOR) {
+        warnings.push(Warning::Separator(club.to_string()));
+        return Enrollments {
+            club: club.to_string(),
+            entries,
+            warnings,
+        };
+    }
+
+    for selection in selections {
+        let Some(event) = meet.events.iter().find(|event| event.id == selection.event) else {
+            warnings.push(Warning::UnknownEvent(selection.event));
+            continue;
+        };
+        let Some(athlete) = roster.athletes.get(selection.athlete) else {
+            warnings.push(Warning::UnknownAthlete(selection.athlete));
+            continue;
+        };
+        let name = || athlete.name.clone();
+        if athlete.name.contains(SEPARATOR) {
+            let warning = Warning::Separator(name());
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+            continue;
+        }
+        if event.distance.is_team() {
+            warnings.push(Warning::Relay(event.id));
+            continue;
+        }
+        if entries
+            .iter()
+            .any(|entry| std::ptr::eq(entry.athlete, athlete) && entry.event.id == event.id)
+        {
+            warnings.push(Warning::Duplicate(name(), event.id));
+            continue;
+        }
+
+        let profile = athlete.profile();
+        match check_event(meet, event, &profile) {
+            Status::NotEligible => {
+                warnings.push(Warning::NotEligible(name(), event.id));
+                continue;
+            }
+            Status::NotQualified(_) => {
+                warnings.push(Warning::NotQualified(name(), event.id));
+                if rules.qualification {
+                    continue;
+                }
+            }
+            Status::Qualified(_) | Status::Open | Status::Exempt => {}
+        }
+
+        let class = if is_senior(meet, event, &profile) {
+            Class::Senior
+        } else {
+            Class::Junior(Some(athlete.birth_year))
+        };
+        let sport_class = athlete
+            .classification
+            .and_then(|classification| classification.sport_class(event.style));
+        if !rules.classes.allows(&class)
+            && !sport_class
+                .is_some_and(|sport_class| rules.classes.allows(&Class::Handicap(sport_class)))
+        {
+            warnings.push(Warning::ClassNotAllowed(name(), event.id));
+            continue;
+        }
+
+        let entry_time = entry_time(meet, event, athlete);
+        match entry_time {
+            None => warnings.push(Warning::NoEntryTime(name(), event.id)),
+            Some(entry_time) if entry_time.converted => {
+                warnings.push(Warning::Converted(name(), event.id));
+            }
+            Some(_) => {}
+        }
+
+        entries.push(Enrollment {
+            athlete,
+            event,
+            gender_class: GenderClass {
+                gender_group: athlete.gender,
+                class,
+            },
+            entry_time,
+        });
+    }
+
+    Enrollments {
+        club: club.to_string(),
+        entries,
+        warnings,
+    }
+}
+
+/// Best personal best of `athlete` that can be used as entry time in `event`.
+///
+/// Times swum in the pool length of the event are preferred. When the meet asks for long course
+/// entry times (`Meet::lcm_entry_times`), only long course times are used, unless short course
+/// times are allowed when there is no long course time
+/// (`Meet::scm_entry_times_if_lcm_does_not_exists`). Times from the other pool length are
+/// converted with `COURSE_FACTOR`.
+fn entry_time(meet: &Meet, event: &Event, athlete: &Athlete) -> Option<EntryTime> {
+    let is_set = |value: &Option<String>| value.as_deref() == Some("TRUE");
+    let (preferred, allow_other) = if is_set(&meet.lcm_entry_times) {
+        (
+            PoolLength::PoolLength50,
+            is_set(&meet.scm_entry_times_if_lcm_does_not_exists),
+        )
+    } else {
+        (event.pool_length, true)
+    };
+
+    if let Some(time) = athlete.best_time(event.distance, event.style, preferred) {
+        return Some(EntryTime {
+            time,
+            pool_length: preferred,
+            converted: false,
+        });
+    }
+    if !allow_other {
+        return None;
+    }
+    let other = match preferred {
+        PoolLength::PoolLength25 => PoolLength::PoolLength50,
+        PoolLength::PoolLength50 => PoolLength::PoolLength25,
+    };
+    athlete
+        .best_time(event.distance, event.style, other)
+        .map(|time| EntryTime {
+            time: match preferred {
+                PoolLength::PoolLength50 => time.mul_f64(COURSE_FACTOR),
+                PoolLength::PoolLength25 => time.div_f64(COURSE_FACTOR),
+            },
+            pool_length: preferred,
+            converted: true,
+        })
+}
+
+impl Enrollments<'_> {
+    /// Write the entries in the `uni_p.txt` format.
+    #[must_use]
+    pub fn to_uni_p(&self) -> String {
+        let mut uni_p = String::new();
+        let _ = writeln!(uni_p, "{}", self.club);
+
+        for entry in &self.entries {
+            let athlete = entry.athlete;
+            let event = entry.event;
+            let (first_name, surname) = athlete
+                .name
+                .trim()
+                .rsplit_once(' ')
+                .unwrap_or_else(|| ("", athlete.name.trim()));
+            let gender = match athlete.gender {
+                GenderGroup::Female => "K",
+                _ => "M",
+            };
+            let sport_class = athlete
+                .classification
+                .and_then(|classification| classification.sport_class(event.style))
+                .map(|sport_class| sport_class.to_string())
+                .unwrap_or_default();
+            let (time, pool_length) = entry.entry_time.map_or((String::new(), ""), |entry_time| {
+                (
+                    format_time(entry_time.time),
+                    match entry_time.pool_length {
+                        PoolLength::PoolLength25 => "K",
+                        PoolLength::PoolLength50 => "L",
+                    },
+                )
+            });
+
+            let _ = writeln!(
+                uni_p,
+                "{},{},{},{surname},{first_name},,{gender},{},{sport_class},{},{time},{pool_length}",
+                event.id,
+                distance_code(event.distance),
+                style_code(event.style),
+                entry.gender_class.code(),
+                athlete.birth_year.to_number(),
+            );
+        }
+
+        uni_p
+    }
+}
+
+fn distance_code(distance: Distance) -> String {
+    match distance {
+        Distance::Individual(individual) => individual.meters().to_string(),
+        Distance::Team(team) => format!("{}*{}", team.legs(), team.leg_length()),
+    }
+}
+
+/// Norwegian style abbreviations. See `Style::try_from`.
+const fn style_code(style: Style) -> &'static str {
+    match style {
+        Style::Single(Stroke::FreeStyle) => "FR",
+        Style::Single(Stroke::Butterfly) => "BU",
+        Style::Single(Stroke::BackStroke) => "RY",
+        Style::Single(Stroke::BreastStroke) => "BR",
+        Style::Medley(TEAM_MEDLEY) => "LM",
+        Style::Medley(_) | Style::MedleyHC(_) => "IM",
+    }
+}
+
+/// Entry time formatted as 'MM:ss.hh'.
+fn format_time(time: Duration) -> String {
+    let hundredths = time.as_millis() / 10;
+    format!(
+        "{:02}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meet_setup::competition_type::CompetitionType;
+
+    /// Athletes with best times in 100m freestyle, event 1 of the corpus meet.
+    const ROSTER: &str = r#"Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,25,100,FREESTYLE,1:00.00
+Lise Nordmann,Bergen Svømmeklubb,2000,FEMALE,,50,100,FREESTYLE,1:05.00
+Lise Nordmann,Bergen Svømmeklubb,2000,FEMALE,,25,100,FREESTYLE,1:01.00
+Anne Nordmann,Bergen Svømmeklubb,2000,FEMALE,S14,,,,
+Ida Nordmann,Bergen Svømmeklubb,2000,FEMALE,S8,,,,
+"Nordmann, Kari",Bergen Svømmeklubb,2009,FEMALE,,,,,
+Ola Nordmann,Bergen Svømmeklubb,2000,MALE,,,,,
+"#;
+
+    const KARI: usize = 0;
+    const LISE: usize = 1;
+    const ANNE: usize = 2;
+    const IDA: usize = 3;
+    const COMMA: usize = 4;
+    const OLA: usize = 5;
+
+    fn meet() -> Meet {
+        Meet::from_str(include_str!("../../tests/corpus/basic.xml")).expect("valid meet")
+    }
+
+    fn roster() -> Roster {
+        Roster::try_from(ROSTER).expect("valid roster")
+    }
+
+    fn select(athletes: &[usize], event: u32) -> Vec<Selection> {
+        athletes
+            .iter()
+            .map(|&athlete| Selection { athlete, event })
+            .collect()
+    }
+
+    fn time(time: &str) -> Duration {
+        crate::swim_time::parse(time).expect("valid time")
+    }
+
+    #[test]
+    fn short_course_time_is_converted_for_a_long_course_event() {
+        let (meet, roster) = (meet(), roster());
+        let enrollments = generate(&meet, &roster, "Bergen Svømmeklubb", &select(&[KARI], 1));
+
+        let entry_time = enrollments.entries[0].entry_time.expect("entry time");
+        assert_eq!(entry_time.time, time("1:01.20"));
+        assert_eq!(entry_time.pool_length, PoolLength::PoolLength50);
+        assert!(entry_time.converted);
+        // Qualification limits only accept times from the pool length of the limit.
+        assert_eq!(
+            enrollments.warnings,
+            [
+                Warning::NotQualified("Kari Nordmann".to_string(), 1),
+                Warning::Converted("Kari Nordmann".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            enrollments.to_uni_p(),
+            "Bergen Svømmeklubb\n1,100,FR,Nordmann,Kari,,K,K08,,2008,01:01.20,L\n"
+        );
+    }
+
+    #[test]
+    fn long_course_time_is_converted_for_a_short_course_event() {
+        let mut meet = meet();
+        meet.events[0].pool_length = PoolLength::PoolLength25;
+        let roster = Roster::try_from(
+            "Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,50,100,FREESTYLE,1:01.20",
+        )
+        .expect("valid roster");
+        let enrollments = generate(&meet, &roster, "Bergen Svømmeklubb", &select(&[0], 1));
+
+        let entry_time = enrollments.entries[0].entry_time.expect("entry time");
+        assert_eq!(entry_time.time, time("1:00.00"));
+        assert_eq!(entry_time.pool_length, PoolLength::PoolLength25);
+        assert!(entry_time.converted);
+    }
+
+    #[test]
+    fn time_in_the_pool_length_of_the_event_is_preferred() {
+        let (meet, roster) = (meet(), roster());
+        let enrollments = generate(&meet, &roster, "Bergen Svømmeklubb", &select(&[LISE], 1));
+
+        assert_eq!(
+            enrollments.entries[0].entry_time,
+            Some(EntryTime {
+                time: time("1:05.00"),
+                pool_length: PoolLength::PoolLength50,
+                converted: false,
+            })
+        );
+        assert!(enrollments.warnings.is_empty());
+    }
+
+    #[test]
+    fn lcm_entry_times_only_accept_long_course_times() {
+        let mut meet = meet();
+        meet.events[0].pool_length = PoolLength::PoolLength25;
+        meet.lcm_entry_times = Some("TRUE".to_string());
+        let roster = roster();
+        let selections = select(&[KARI, LISE], 1);
+
+        let enrollments = generate(&meet, &roster, "Bergen Svømmeklubb", &selections);
+        assert_eq!(enrollments.entries[0].entry_time, None);
+        assert_eq!(
+            enrollments.entries[1].entry_time,
+            Some(EntryTime {
+                time: time("1:05.00"),
+                pool_length: PoolLength::PoolLength50,
+                converted: false,
+            })
+        );
+        assert!(enrollments
+            .warnings
+            .contains(&Warning::NoEntryTime("Kari Nordmann".to_string(), 1)));
+        assert!(enrollments
+            .to_uni_p()
+            .contains("1,100,FR,Nordmann,Kari,,K,K08,,2008,,\n"));
+
+        meet.scm_entry_times_if_lcm_does_not_exists = Some("TRUE".to_string());
+        let enrollments = generate(&meet, &roster, "Bergen Svømmeklubb", &selections);
+        let entry_time = enrollments.entries[0].entry_time.expect("entry time");
+        assert_eq!(entry_time.time, time("1:01.20"));
+        assert_eq!(entry_time.pool_length, PoolLength::PoolLength50);
+        assert!(entry_time.converted);
+    }
+
+    #[test]
+    fn juniors_only_meet_skips_seniors() {
+        let mut meet = meet();
+        meet.competition_type_id = CompetitionType::RegionalAgeGroupMeet;
+        meet.events[0].qualification_time_long_course = None;
+        let roster = roster();
+        let enrollments = generate(
+            &meet,
+            &roster,
+            "Bergen Svømmeklubb",
+            &select(&[KARI, LISE], 1),
+        );
+
+        assert_eq!(enrollments.entries.len(), 1);
+        assert_eq!(enrollments.entries[0].athlete.name, "Kari Nordmann");
+        assert!(enrollments
+            .warnings
+            .contains(&Warning::ClassNotAllowed("Lise Nordmann".to_string(), 1)));
+    }
+
+    #[test]
+    fn intellectual_impairment_meet_takes_the_sport_class_of_the_event() {
+        let mut meet = meet();
+        meet.competition_type_id = CompetitionType::MentallyDisabledMeet;
+        let roster = roster();
+        let enrollments = generate(
+            &meet,
+            &roster,
+            "Bergen Svømmeklubb",
+            &select(&[ANNE, IDA], 1),
+        );
+
+        assert_eq!(enrollments.entries.len(), 1);
+        assert_eq!(enrollments.entries[0].athlete.name, "Anne Nordmann");
+        assert!(enrollments
+            .to_uni_p()
+            .contains("1,100,FR,Nordmann,Anne,,K,KSR,S14,2000,,\n"));
+        assert!(enrollments
+            .warnings
+            .contains(&Warning::ClassNotAllowed("Ida Nordmann".to_string(), 1)));
+    }
+
+    #[test]
+    fn qualification_is_required_only_when_the_competition_type_says_so() {
+        let mut meet = meet();
+        let roster = roster();
+        let selections = select(&[LISE], 1);
+        meet.events[0].qualification_time_long_course = Some(time("1:04.00"));
+
+        let open = generate(&meet, &roster, "Bergen Svømmeklubb", &selections);
+        assert_eq!(open.entries.len(), 1);
+        assert_eq!(
+            open.warnings,
+            [Warning::NotQualified("Lise Nordmann".to_string(), 1)]
+        );
+
+        meet.competition_type_id = CompetitionType::NorwegianChampionship;
+        let championship = generate(&meet, &roster, "Bergen Svømmeklubb", &selections);
+        assert!(championship.entries.is_empty());
+    }
+
+    #[test]
+    fn names_with_the_separator_are_not_written() {
+        let (meet, roster) = (meet(), roster());
+        let enrollments = generate(
+            &meet,
+            &roster,
+            "Bergen Svømmeklubb",
+            &[
+                Selection {
+                    athlete: COMMA,
+                    event: 1,
+                },
+                Selection {
+                    athlete: COMMA,
+                    event: 1,
+                },
+                Selection {
+                    athlete: KARI,
+                    event: 1,
+                },
+            ],
+        );
+        assert_eq!(enrollments.entries.len(), 1);
+        assert_eq!(
+            enrollments.warnings[0],
+            Warning::Separator("Nordmann, Kari".to_string())
+        );
+        assert_eq!(
+            enrollments
+                .warnings
+                .iter()
+                .filter(|warning| matches!(warning, Warning::Separator(_)))
+                .count(),
+            1
+        );
+        assert_eq!(enrollments.to_uni_p().lines().count(), 2);
+
+        let club = generate(&meet, &roster, "Bergen SK, avd. Fana", &select(&[KARI], 1));
+        assert!(club.entries.is_empty());
+        assert_eq!(
+            club.warnings,
+            [Warning::Separator("Bergen SK, avd. Fana".to_string())]
+        );
+    }
+
+    #[test]
+    fn selections_that_cannot_be_entered_are_skipped() {
+        let (meet, roster) = (meet(), roster());
+        let enrollments = generate(
+            &meet,
+            &roster,
+            "Bergen Svømmeklubb",
+            &[
+                Selection {
+                    athlete: KARI,
+                    event: 9,
+                },
+                Selection {
+                    athlete: 99,
+                    event: 1,
+                },
+                Selection {
+                    athlete: OLA,
+                    event: 2,
+                },
+                Selection {
+                    athlete: OLA,
+                    event: 1,
+                },
+                Selection {
+                    athlete: LISE,
+                    event: 1,
+                },
+                Selection {
+                    athlete: LISE,
+                    event: 1,
+                },
+            ],
+        );
+        assert_eq!(enrollments.entries.len(), 1);
+        assert_eq!(
+            enrollments.warnings,
+            [
+                Warning::UnknownEvent(9),
+                Warning::UnknownAthlete(99),
+                Warning::Relay(2),
+                Warning::NotEligible("Ola Nordmann".to_string(), 1),
+                Warning::Duplicate("Lise Nordmann".to_string(), 1),
+            ]
+        );
+    }
+}
@@ -7,6 +7,10 @@ extern crate serde;
 use self::serde::Deserialize;
 use std::{fmt::Display, num::ParseIntError, time::Duration};
 
+mod generate;
+
+pub use self::generate::{generate, Enrollment, Enrollments, EntryTime, Selection, Warning};
+
 #[derive(Debug, Deserialize)]
 pub struct EnrollmentEntry {
     pub event_number: u8,
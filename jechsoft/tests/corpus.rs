@@ -0,0 +1,232 @@
+//! # Corpus
+//!
+//! Regression test for the meet setup parser. Every `tests/corpus/<name>.xml` is parsed and the
+//! result is compared with the snapshot in `tests/corpus/<name>.snap`: a summary of the parsed
+//! `Meet` and its validation issues, or the error if the file cannot be parsed.
+//!
+//! ```text
+//! cargo test -p jechsoft --test corpus
+//! cargo test -p jechsoft --test corpus -- --update
+//! ```
+//!
+//! The summary holds the fields the corpus files exercise, see `summary`. Fields added to `Meet`
+//! only change the snapshots when they are added to the summary.
+//!
+//! `--update` writes the snapshots of the current parser. Review the snapshot diff before
+//! committing it. Files added to the corpus have to be free of personal data, see
+//! `jechsoft::meet_setup::scrub` and `moisty scrub`.
+use jechsoft::meet_setup::{meet::Meet, person::Person};
+use jechsoft::swim_time;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs};
+
+fn main() -> ExitCode {
+    let update = env::args().any(|argument| argument == "--update");
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut files: Vec<PathBuf> = fs::read_dir(&corpus)
+        .expect("corpus directory")
+        .map(|entry| entry.expect("corpus entry").path())
+        .collect();
+    files.sort();
+
+    let mut failed = 0;
+    let mut checked = 0;
+    for path in &files {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("xml") => {}
+            Some("snap") if !path.with_extension("xml").is_file() => {
+                println!("corpus {name} ... FAILED: snapshot without meet setup file");
+                failed += 1;
+                continue;
+            }
+            _ => continue,
+        }
+
+        checked += 1;
+        let actual = snapshot(path);
+        let snapshot_path = path.with_extension("snap");
+        if update {
+            fs::write(&snapshot_path, &actual).expect("write snapshot");
+            println!("corpus {name} ... updated");
+            continue;
+        }
+        match fs::read_to_string(&snapshot_path) {
+            Ok(expected) if expected == actual => println!("corpus {name} ... ok"),
+            Ok(expected) => {
+                failed += 1;
+                println!("corpus {name} ... FAILED");
+                print!("{}", difference(&expected, &actual));
+            }
+            Err(_) => {
+                failed += 1;
+                println!("corpus {name} ... FAILED: no snapshot, run with --update");
+            }
+        }
+    }
+
+    if update {
+        println!("\nupdated {checked} snapshots");
+        return ExitCode::SUCCESS;
+    }
+    println!("\ncorpus: {checked} files, {failed} failed");
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        println!("run `cargo test -p jechsoft --test corpus -- --update` to accept the changes");
+        ExitCode::FAILURE
+    }
+}
+
+/// Summary of the parsed meet and validation issues, or the parse error.
+fn snapshot(path: &Path) -> String {
+    match Meet::try_from(path) {
+        Ok(meet) => {
+            let mut snapshot = summary(&meet);
+            snapshot.push_str("\nissues:\n");
+            for issue in meet.validate() {
+                let _ = writeln!(snapshot, "- {issue}");
+            }
+            snapshot
+        }
+        Err(why) => format!("error: {why}\n"),
+    }
+}
+
+/// The fields of `meet` that the corpus files test: text that has to survive decoding, dates,
+/// contacts, the competition type, qualification times, sessions and events.
+fn summary(meet: &Meet) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let date = |date: Option<chrono::NaiveDate>| optional(date.map(|date| date.to_string()));
+    let person = |person: Option<&Person>| {
+        optional(
+            person.map(|person| format!("{} {}, {}", person.name, person.surname, person.club)),
+        )
+    };
+    let time = |time: Option<std::time::Duration>| optional(time.map(swim_time::format));
+
+    let mut summary = String::new();
+    let _ = writeln!(summary, "name: {}", meet.name);
+    let _ = writeln!(
+        summary,
+        "nsf meet id: {}",
+        optional(meet.nsf_meet_id.map(|id| id.to_string()))
+    );
+    let _ = writeln!(summary, "date: {}", meet.date);
+    let _ = writeln!(summary, "location: {}", meet.location);
+    let _ = writeln!(
+        summary,
+        "days: {} - {}",
+        date(meet.date_start),
+        date(meet.date_end)
+    );
+    let _ = writeln!(
+        summary,
+        "entries: {} - {}, final {}",
+        meet.first_entry_date, meet.last_entry_date, meet.final_entry_date
+    );
+    let _ = writeln!(
+        summary,
+        "competition type: {} ({})",
+        meet.competition_type_id, meet.competition_type
+    );
+    let _ = writeln!(summary, "cancelled: {}", meet.cancelled);
+    let _ = writeln!(
+        summary,
+        "pool: {} with {} lanes, touch pads {:?}",
+        meet.pool_length, meet.lanes, meet.touch_pads
+    );
+    let _ = writeln!(
+        summary,
+        "home page: {}",
+        optional(meet.home_page.as_ref().map(ToString::to_string))
+    );
+    let _ = writeln!(summary, "host club: {}", optional(meet.host_club.clone()));
+    let _ = writeln!(
+        summary,
+        "entry manager: {}",
+        person(meet.entry_manager.as_ref())
+    );
+    let _ = writeln!(
+        summary,
+        "competition manager: {}",
+        person(meet.competition_manager.as_ref())
+    );
+
+    if let Some(qualification_set) = &meet.qualification_set {
+        let _ = writeln!(summary, "\nqualification set: {}", qualification_set.name);
+        for qualification in &qualification_set.qualifications {
+            let _ = writeln!(
+                summary,
+                "- {} {} {} {} {}: {}",
+                qualification.athlete_class,
+                qualification.athlete_gender,
+                qualification.pool_length,
+                qualification.distance,
+                qualification.style,
+                swim_time::format(qualification.time)
+            );
+        }
+    }
+
+    let _ = writeln!(summary, "\nsessions:");
+    for session in &meet.sessions {
+        let _ = writeln!(
+            summary,
+            "- {} {} {:02}:{:02} {}",
+            session.id,
+            session.date,
+            session.start_time.hour(),
+            session.start_time.minute(),
+            session.name
+        );
+    }
+
+    let _ = writeln!(summary, "\nevents:");
+    for event in &meet.events {
+        let _ = writeln!(
+            summary,
+            "- {} {} {} {} {} pool, {}, session {}, senior {}, qualification {} / {}: {}",
+            event.id,
+            event.distance,
+            event.style,
+            event.gender_group,
+            event.pool_length,
+            event.date,
+            optional(event.ses_id.map(|id| id.to_string())),
+            event.senior,
+            time(event.qualification_time_long_course),
+            time(event.qualification_time_short_course),
+            event.description
+        );
+    }
+    summary
+}
+
+/// The lines that differ between the snapshots, with line numbers. Lists at most 20 lines.
+fn difference(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut difference = String::new();
+    let mut shown = 0;
+    for line in 0..expected.len().max(actual.len()) {
+        let (old, new) = (expected.get(line), actual.get(line));
+        if old == new {
+            continue;
+        }
+        if shown == 20 {
+            difference.push_str("    ...\n");
+            break;
+        }
+        shown += 1;
+        if let Some(old) = old {
+            let _ = writeln!(difference, "    {:>5} - {old}", line + 1);
+        }
+        if let Some(new) = new {
+            let _ = writeln!(difference, "    {:>5} + {new}", line + 1);
+        }
+    }
+    difference
+}
@@ -0,0 +1,240 @@
+//! # Parsers
+//!
+//! Property tests for the hand written parsers. Invalid input has to give an error, never a
+//! panic, and values written by the crate have to parse back to the same value. The fuzz targets
+//! in `fuzz/` run the same parsers on generated input for as long as you let them.
+use jechsoft::heat_list::Entries;
+use jechsoft::meet_result::MeetResult;
+use jechsoft::meet_setup::{
+    classification::Classification,
+    competition_type::CompetitionType,
+    distance::Distance,
+    gender_class::GenderClass,
+    gender_group::GenderGroup,
+    handicap::{Handicap, StyleGroup},
+    individual_distance::Individual,
+    meet::Meet,
+    pool_length::PoolLength,
+    style::Style,
+    team_distance::Team,
+    touch_pad_set::TouchPadSet,
+};
+use jechsoft::points::BaseTimes;
+use jechsoft::records::{AgeClass, Records, Scope};
+use jechsoft::roster::Roster;
+use jechsoft::swim_time;
+use jechsoft::trygg_i_vann::{Export, Outcome};
+use proptest::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Characters that appear in the values of meet setup files, so generated strings get past the
+/// first checks of the parsers more often than random unicode.
+const VALUE: &str = "[0-9SMBRJKXFEAL:.,*+ \\-øå]{0,12}";
+
+/// Run every `TryFrom<&str>` of the crate on `value`. Errors are fine, panics are not.
+fn parse_everything(value: &str) {
+    let _ = Distance::try_from(value);
+    let _ = Individual::try_from(value);
+    let _ = Team::try_from(value);
+    let _ = Style::try_from(value);
+    let _ = GenderGroup::try_from(value);
+    let _ = GenderClass::try_from(value);
+    let _ = Handicap::try_from(value);
+    let _ = Classification::try_from(value);
+    let _ = CompetitionType::try_from(value);
+    let _ = PoolLength::try_from(value);
+    let _ = TouchPadSet::try_from(value);
+    let _ = swim_time::parse(value);
+    let _ = Entries::try_from(value);
+    let _ = BaseTimes::try_from(value);
+    let _ = Records::try_from(value);
+    let _ = Records::from_lenex(value, Scope::National);
+    let _ = AgeClass::try_from(value);
+    let _ = Roster::try_from(value);
+    let _ = MeetResult::try_from(value);
+    let _ = Export::try_from(value);
+    let _ = Outcome::try_from(value);
+}
+
+fn corpus(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/corpus")
+        .join(name);
+    fs::read_to_string(path).expect("corpus file")
+}
+
+/// Byte ranges of the text of every element without children in `xml`.
+fn values(xml: &str) -> Vec<(usize, usize)> {
+    let mut values = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = xml[offset..].find('>') {
+        let start = offset + start + 1;
+        let Some(end) = xml[start..].find('<') else {
+            break;
+        };
+        if xml[start + end..].starts_with("</") {
+            values.push((start, start + end));
+        }
+        offset = start + end;
+    }
+    values
+}
+
+proptest! {
+    #[test]
+    fn any_string_is_an_error_or_a_value(value in any::<String>()) {
+        parse_everything(&value);
+    }
+
+    #[test]
+    fn meet_setup_values_are_an_error_or_a_value(value in VALUE) {
+        parse_everything(&value);
+    }
+
+    #[test]
+    fn csv_lines_are_an_error_or_a_value(fields in prop::collection::vec(VALUE, 0..10)) {
+        parse_everything(&fields.join(","));
+    }
+
+    /// Replace the value of one element of a valid meet setup file. Covers the deserializers
+    /// that are only reachable through `Meet`.
+    #[test]
+    fn corrupt_meet_setup_values_are_an_error_or_a_meet(
+        index in any::<prop::sample::Index>(),
+        value in prop_oneof![Just(String::new()), VALUE, any::<String>()],
+    ) {
+        let xml = corpus("qualification_set.xml");
+        let values = values(&xml);
+        let (start, end) = values[index.index(values.len())];
+        let value = value.replace('&', "&amp;").replace('<', "&lt;");
+        let corrupt = format!("{}{value}{}", &xml[..start], &xml[end..]);
+
+        if let Ok(meet) = Meet::from_str(&corrupt) {
+            let _ = meet.validate();
+            let _ = meet.deadlines();
+        }
+    }
+
+    /// Arbitrary bytes in the text of a meet setup file, in the encodings `decode` has to detect.
+    #[test]
+    fn meet_setup_bytes_are_an_error_or_a_meet(
+        bytes in prop::collection::vec(any::<u8>(), 0..16),
+        bom in any::<bool>(),
+    ) {
+        let xml = corpus("basic.xml");
+        let mut file = if bom { b"\xEF\xBB\xBF".to_vec() } else { Vec::new() };
+        let name = xml.find("Corpus Open").expect("meet name");
+        file.extend_from_slice(&xml.as_bytes()[..name]);
+        file.extend(bytes.into_iter().filter(|byte| *byte != b'<' && *byte != b'&'));
+        file.extend_from_slice(&xml.as_bytes()[name..]);
+        let _ = Meet::from_reader(file.as_slice());
+    }
+
+    #[test]
+    fn handicap_medley_descriptions_never_panic(
+        description in "[SM0-9 \\-øå]{0,20}",
+        meters in 25u16..=1500,
+    ) {
+        if let Ok(distance) = Distance::try_from(meters.to_string().as_str()) {
+            let _ = Style::try_from("INDIVIDUALMEDLEY")
+                .expect("style")
+                .resolve_handicap_medley(distance, &description, None);
+        }
+    }
+
+    #[test]
+    fn long_swim_times_are_an_error_or_a_time(time in "[0-9]{1,25}(:[0-9]{1,25})?\\.[0-9]{2}") {
+        let _ = swim_time::parse(&time);
+    }
+
+    #[test]
+    fn swim_time_round_trip(hundredths in 0u64..360_000) {
+        let time = Duration::from_millis(hundredths * 10);
+        prop_assert_eq!(swim_time::parse(&swim_time::format(time)).ok(), Some(time));
+    }
+
+    #[test]
+    fn handicap_round_trip(
+        style_group in prop_oneof![
+            Just(StyleGroup::FreestyleBackstrokeButterfly),
+            Just(StyleGroup::BreastStroke),
+            Just(StyleGroup::Medley),
+        ],
+        disability_type in 1u8..=15,
+    ) {
+        let handicap = Handicap::new(style_group, disability_type).expect("valid sport class");
+        prop_assert_eq!(Handicap::try_from(handicap.to_string().as_str()).ok(), Some(handicap));
+    }
+
+    #[test]
+    fn classification_round_trip(
+        freestyle in prop::option::of(1u8..=15),
+        breaststroke in prop::option::of(1u8..=15),
+        medley in prop::option::of(1u8..=15),
+    ) {
+        let text = [("S", freestyle), ("SB", breaststroke), ("SM", medley)]
+            .into_iter()
+            .filter_map(|(prefix, class)| class.map(|class| format!("{prefix}{class}")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let classification = Classification::try_from(text.as_str()).expect("valid classes");
+        prop_assert_eq!(
+            Classification::try_from(classification.to_string().as_str()).ok(),
+            Some(classification)
+        );
+    }
+
+    #[test]
+    fn gender_class_code_round_trip(
+        gender in prop_oneof![Just('M'), Just('K')],
+        class in prop_oneof![Just("SR".to_string()), Just("JR".to_string()), "[0-9]{2}"],
+    ) {
+        let code = format!("{gender}{class}");
+        let gender_class = GenderClass::try_from(code.as_str()).expect("valid code");
+        prop_assert_eq!(gender_class.code(), code);
+    }
+
+    #[test]
+    fn competition_type_id_round_trip(id in any::<u8>()) {
+        let competition_type = CompetitionType::try_from(id.to_string().as_str());
+        prop_assert_eq!(competition_type.ok().map(CompetitionType::id), Some(id));
+    }
+
+    #[test]
+    fn records_round_trip(
+        scope in prop_oneof![Just("CLUB"), Just("DISTRICT"), Just("NATIONAL"), Just("MEET")],
+        age_class in prop_oneof![Just("SR".to_string()), Just("JR".to_string()), "[0-9]{1,2}"],
+        hundredths in 1u64..360_000,
+        name in "[A-Za-zøå][A-Za-zøå ]{0,20}[A-Za-zøå]",
+        date in prop::option::of((1900i32..2100, 1u32..=12, 1u32..=28)),
+    ) {
+        let date = date
+            .map(|(year, month, day)| format!("{year}-{month:02}-{day:02}"))
+            .unwrap_or_default();
+        let time = swim_time::format(Duration::from_millis(hundredths * 10));
+        let line = format!(
+            "{scope},FEMALE,25,100,FREESTYLE,{age_class},{time},{name},Bergen SK,{date},"
+        );
+        let records = Records::try_from(line.as_str()).expect("valid record");
+        let text = records.to_text();
+        prop_assert_eq!(Records::try_from(text.as_str()).expect("written records").to_text(), text);
+    }
+
+    #[test]
+    fn style_code_round_trip(
+        style in prop_oneof![
+            Just("FREESTYLE"),
+            Just("BUTTERFLY"),
+            Just("BACKSTROKE"),
+            Just("BREASTSTROKE"),
+            Just("INDIVIDUALMEDLEY"),
+            Just("HCMEDLEY"),
+            Just("MEDLEYRELAY"),
+        ],
+    ) {
+        let parsed = Style::try_from(style).expect("valid style");
+        prop_assert_eq!(parsed.code(), style);
+    }
+}
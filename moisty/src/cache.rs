@@ -0,0 +1,33 @@
+use crate::cli::OutputFormat;
+use crate::output::Records;
+use std::{fs, io, path::Path};
+
+const FIELDS: &[&str] = &["path", "files", "bytes"];
+
+/// Print the location and size of the meet cache. With `clear` the cache is removed first.
+pub fn run(cache_dir: &Path, meet_dir: &Path, clear: bool, format: OutputFormat) -> io::Result<()> {
+    if clear && cache_dir.exists() {
+        fs::remove_dir_all(cache_dir)?;
+        log::info!("removed {}", cache_dir.display());
+    }
+
+    let mut files = 0_u64;
+    let mut bytes = 0_u64;
+    if meet_dir.exists() {
+        for entry in fs::read_dir(meet_dir)?.filter_map(Result::ok) {
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+    }
+
+    let mut records = Records::new(FIELDS);
+    records.push(vec![
+        meet_dir.display().to_string().into(),
+        files.into(),
+        bytes.into(),
+    ]);
+    records.print(format)
+}
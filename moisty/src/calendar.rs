@@ -0,0 +1,29 @@
+use crate::select;
+use jechsoft::calendar::{to_ical, Options};
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Write sessions and entry deadlines of a meet to an `.ics` file.
+pub fn run(
+    meet: &str,
+    meet_dir: &Path,
+    output: Option<PathBuf>,
+    no_events: bool,
+) -> io::Result<()> {
+    let select::Loaded { path, meet } = select::select(meet, meet_dir)?;
+    let options = Options {
+        include_events: !no_events,
+        ..Options::default()
+    };
+
+    let output = output
+        .unwrap_or_else(|| select::output(&path, meet_dir, |path| path.with_extension("ics")));
+    fs::write(&output, to_ical(&meet, &options))?;
+
+    log::info!(
+        "wrote {} sessions of {} to {}",
+        meet.sessions.len(),
+        meet.name,
+        output.display()
+    );
+    Ok(())
+}
@@ -1,56 +1,544 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use jechsoft::meet_setup::{
+    distance::Distance, gender_group::GenderGroup, pool_length::PoolLength, style::Style,
+};
 use std::path::PathBuf;
-use clap::Parser;
+use std::time::Duration;
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, after_help = EXIT_CODES_HELP)]
 pub struct Cli {
-    /// Download latest meets from medley.no
-    #[arg(
-        short,
-        long,
-        value_name = "download new meets from server and cache them",
-        default_value_t = false
-    )]
-    pub download: bool,
-
-    /// Output table of events from meets
-    #[arg(
-        short,
-        long,
-        value_name = "display a table with meets content",
-        default_value_t = false
-    )]
-    pub table: bool,
-
-    /// clear out cache directory of saved meets
-    #[arg(
-        short,
-        long,
-        value_name = "clear cached meets",
-        default_value_t = false
-    )]
-    pub clear_cache: bool,
-
-    #[arg(
-        short,
-        long,
-        value_name = "list available meets",
-        long_help = "lists all the meets that are avaialble locally",
-        default_value_t = false
-    )]
-    pub list: bool,
-
-    #[arg(
-        long,
-        value_name = "meet date",
-        long_help = "Sets the search date for the meet to be downloaded. This needs to be used together with the download flag. Meet date needs to be in following format: YYYY-MM-DD"
-    )]
-    pub date: Option<chrono::NaiveDate>,
-
-    /// Path to meetsetup file
-    #[arg(
-        value_name = "meet setup files",
-        long_help = "path to meetsetup file. Usually exported as meetsetup.xml"
-    )]
-    pub meetsetup_path: Option<String>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Download meets published on medley.no into the cache
+    Fetch {
+        #[arg(
+            long,
+            value_name = "meet date",
+            long_help = "download meets from this date. Defaults to today. Meet date needs to be in following format: YYYY-MM-DD"
+        )]
+        date: Option<chrono::NaiveDate>,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// List cached meets
+    List {
+        #[arg(
+            value_name = "meet",
+            long_help = "only list meets matching this NSF meet id or name"
+        )]
+        meet: Option<String>,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Show details and sessions of a meet
+    Show {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// List the events of a meet
+    Events {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Check meets for inconsistencies like events in sessions that do not exist
+    Validate {
+        #[arg(
+            value_name = "meet",
+            long_help = "meet to validate. Validates all cached meets if left out. See the show command for how meets are selected"
+        )]
+        meet: Option<String>,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Export a meet with its sessions and events
+    Export {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(
+            short,
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Json,
+            long_help = "JSON and NDJSON hold the whole meet, CSV and table hold the events only"
+        )]
+        format: OutputFormat,
+
+        /// Path to the file to write. Defaults to stdout
+        #[arg(short, long, value_name = "output file")]
+        output: Option<PathBuf>,
+    },
+
+    /// Show differences between two versions of a meet
+    Diff {
+        #[arg(value_name = "old meet", long_help = MEET_HELP)]
+        old: String,
+
+        #[arg(value_name = "new meet", long_help = MEET_HELP)]
+        new: String,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Show the location and size of the meet cache
+    Cache {
+        /// Remove all cached meets
+        #[arg(long, default_value_t = false)]
+        clear: bool,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Read lane events from a timing console or a captured byte log
+    Timing {
+        #[arg(
+            value_name = "source",
+            long_help = "serial device of the timing console, like /dev/ttyUSB0, or a file with bytes captured from a console. Serial devices have to be configured first, for example with 'stty -F /dev/ttyUSB0 9600 raw'"
+        )]
+        source: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = TouchPads::OneSet, conflicts_with = "meet")]
+        touch_pads: TouchPads,
+
+        #[arg(
+            long,
+            value_name = "meet",
+            long_help = "take the touch pads from this meet. See the show command for how meets are selected"
+        )]
+        meet: Option<String>,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Browse the cached meets in a terminal user interface
+    Tui,
+
+    /// Serve the cached meets, heat lists and live results over HTTP
+    Serve {
+        #[arg(
+            long,
+            value_name = "address",
+            default_value = "0.0.0.0:8080",
+            long_help = "address to listen on. The default listens on every network interface so phones on the pool network can connect"
+        )]
+        address: String,
+
+        #[arg(
+            long,
+            value_name = "results directory",
+            long_help = "directory with result files named after the NSF meet id, like 1234.json, or after the meet setup file for meets without an id. See the results command for the format. Files are read again when they change"
+        )]
+        results: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "entries directory",
+            long_help = "directory with entries files named like the result files, like 1234.txt, used for heat lists. See the heat-list command for the format"
+        )]
+        entries: Option<PathBuf>,
+    },
+
+    /// Pick the official time of each lane from the touch pads and backup buttons
+    Reconcile {
+        #[arg(
+            value_name = "source",
+            long_help = "serial device of the timing console or a file with bytes captured from a console. See the timing command"
+        )]
+        source: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = TouchPads::OneSet, conflicts_with = "meet")]
+        touch_pads: TouchPads,
+
+        #[arg(
+            long,
+            value_name = "meet",
+            long_help = "take the touch pads from this meet. See the show command for how meets are selected"
+        )]
+        meet: Option<String>,
+
+        #[arg(long, value_name = "time", value_parser = |s: &str| jechsoft::swim_time::parse(s), long_help = "largest accepted difference between pads and backup buttons formatted as 'ss.hh'. Lanes with larger differences need referee review. Defaults to 0.30")]
+        threshold: Option<Duration>,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Calculate World Aquatics points for a swim time
+    Points {
+        #[arg(
+            long,
+            value_name = "base times file",
+            long_help = "path to a file with base times. One base time per line formatted as 'gender group,pool length,distance,style,base time'. Example: 'FEMALE,25,50,FREESTYLE,23.61'"
+        )]
+        base_times: PathBuf,
+
+        #[arg(value_name = "distance", value_parser = |s: &str| Distance::try_from(s), long_help = "event distance. Example: '100', '4*50'")]
+        distance: Distance,
+
+        #[arg(value_name = "style", value_parser = |s: &str| Style::try_from(s), long_help = "event style. Example: 'FREESTYLE', 'BR', 'INDIVIDUALMEDLEY', 'HCMEDLEY'")]
+        style: Style,
+
+        #[arg(value_name = "gender group", value_parser = |s: &str| GenderGroup::try_from(s), long_help = "'MALE', 'FEMALE' or 'MIXED'")]
+        gender_group: GenderGroup,
+
+        #[arg(value_name = "pool length", value_parser = |s: &str| PoolLength::try_from(s), long_help = "'25' or '50'")]
+        pool_length: PoolLength,
+
+        #[arg(value_name = "time", value_parser = |s: &str| jechsoft::swim_time::parse(s), long_help = "swim time formatted as 'ss.hh' or 'mm:ss.hh'")]
+        time: Duration,
+    },
+
+    /// Export meet sessions and entry deadlines as an iCalendar file
+    Calendar {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "output file",
+            long_help = "path to the .ics file to write. Defaults to the meet setup file name with .ics extension, in the current directory for cached meets"
+        )]
+        output: Option<PathBuf>,
+
+        /// Leave out the list of events in each session
+        #[arg(long, default_value_t = false)]
+        no_events: bool,
+    },
+
+    /// List upcoming entry, withdrawal and relay deadlines of all cached meets
+    Deadlines {
+        /// Include deadlines that have already passed
+        #[arg(short, long, default_value_t = false)]
+        all: bool,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Seed entries into heats and write a printable heat list
+    HeatList {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(
+            value_name = "entries file",
+            long_help = "path to a file with one entry per line formatted as 'event,name,club,birth year,entry time,nation,first leg'. Birth year, entry time, nation and the swimmer of the first relay leg can be left empty. Example: '1,Kari Nordmann,Bergen Svømmeklubb,2008,1:02.34,NOR'"
+        )]
+        entries: PathBuf,
+
+        #[arg(short, long, value_enum, default_value_t = HeatListFormat::Html)]
+        format: HeatListFormat,
+
+        #[arg(
+            long,
+            value_name = "records file",
+            long_help = "print the records of each event above the heats if the meet setup asks for it. See the records command"
+        )]
+        records: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "output file",
+            long_help = "path to the file to write. Defaults to the meet setup file name with .html or .pdf extension, in the current directory for cached meets"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// Show the heats of an event on a scoreboard while reading lane events from a timing console
+    Scoreboard {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(
+            value_name = "entries file",
+            long_help = "path to a file with one entry per line. See the heat-list command"
+        )]
+        entries: PathBuf,
+
+        #[arg(
+            value_name = "source",
+            long_help = "serial device of the timing console or a file with bytes captured from a console. See the timing command"
+        )]
+        source: PathBuf,
+
+        #[arg(long, value_name = "event", long_help = "event number to show")]
+        event: u32,
+
+        #[arg(
+            long,
+            value_name = "heat",
+            default_value_t = 1,
+            long_help = "heat shown at the first start signal. Every following start signal shows the next heat"
+        )]
+        heat: usize,
+
+        #[arg(
+            long,
+            value_name = "device",
+            conflicts_with = "tcp",
+            long_help = "send the board to a scoreboard on this serial device instead of the terminal. See the timing crate documentation for the protocol"
+        )]
+        serial: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "address",
+            long_help = "send the board to a scoreboard listening on this TCP address, like 192.168.1.50:4000, instead of the terminal"
+        )]
+        tcp: Option<String>,
+
+        #[arg(
+            long,
+            long_help = "wait between events as long as the console did. Use this to replay a captured log at the speed of the race"
+        )]
+        realtime: bool,
+    },
+
+    /// Write a result list with places, splits, points and records
+    Results {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        /// Path to a JSON file with results
+        #[arg(value_name = "results file")]
+        results: PathBuf,
+
+        #[arg(short, long, value_enum, default_value_t = ResultListFormat::Text)]
+        format: ResultListFormat,
+
+        #[arg(
+            short,
+            long,
+            value_name = "output file",
+            long_help = "path to the file to write. Text is written to stdout by default, HTML and PDF to the meet setup file name with .html or .pdf extension, in the current directory for cached meets"
+        )]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "base times file",
+            long_help = "calculate World Aquatics points for results without points using this base times file. See the points command"
+        )]
+        base_times: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "records file",
+            long_help = "flag the swims that break a record in this records file. The records file is not changed, use the records command to save the new records"
+        )]
+        records: Option<PathBuf>,
+    },
+
+    /// Check which events of a meet the athletes of a roster are qualified for
+    Qualification {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(
+            value_name = "roster file",
+            long_help = ROSTER_HELP
+        )]
+        roster: PathBuf,
+
+        /// Only report athletes of this club
+        #[arg(long)]
+        club: Option<String>,
+    },
+
+    /// List the athletes of a roster or update their personal bests from meet results
+    Roster {
+        #[arg(value_name = "roster file", long_help = ROSTER_HELP)]
+        roster: PathBuf,
+
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["meet setup file", "results file"],
+            long_help = "update personal bests with the results of a meet and save the roster. See the results command for the results file format"
+        )]
+        update: Option<Vec<PathBuf>>,
+
+        /// Only list athletes of this club
+        #[arg(long)]
+        club: Option<String>,
+    },
+
+    /// List club, district, national and meet records, import Lenex record lists or update the records from meet results
+    Records {
+        #[arg(value_name = "records file", long_help = RECORDS_HELP)]
+        records: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "Lenex file",
+            requires = "scope",
+            long_help = "import the record lists of an uncompressed Lenex file (.lef) and save the records file. Imported records replace records of the same event and age class. The records file is created if it does not exist"
+        )]
+        import: Option<PathBuf>,
+
+        /// Scope of the imported records. Lenex does not say whose records a list holds
+        #[arg(long, value_enum)]
+        scope: Option<RecordScope>,
+
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["meet setup file", "results file"],
+            conflicts_with = "import",
+            long_help = "update the records with the results of a meet and save the records file. See the results command for the results file format"
+        )]
+        update: Option<Vec<PathBuf>>,
+    },
+
+    /// Find the fastest relay teams of a club from the personal bests in a roster
+    Relay {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        /// Event number of the relay
+        #[arg(value_name = "event")]
+        event: u32,
+
+        #[arg(value_name = "roster file", long_help = ROSTER_HELP)]
+        roster: PathBuf,
+
+        /// Club to pick swimmers from
+        #[arg(long)]
+        club: String,
+
+        /// Number of teams. Each swimmer swims in at most one team
+        #[arg(long, default_value_t = 1)]
+        teams: usize,
+    },
+
+    /// Generate a uni_p.txt enrollment file from a roster and a selection of events
+    Enroll {
+        #[arg(value_name = "meet", long_help = MEET_HELP)]
+        meet: String,
+
+        #[arg(value_name = "roster file", long_help = ROSTER_HELP)]
+        roster: PathBuf,
+
+        #[arg(
+            value_name = "selections file",
+            long_help = "path to a file with one athlete per line formatted as 'name,event numbers'. Event numbers are separated by white space. Example: 'Kari Nordmann,1 5 12'"
+        )]
+        selections: PathBuf,
+
+        /// Club to enroll. Athletes are looked up in the roster by name within this club
+        #[arg(long)]
+        club: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "output file",
+            long_help = "path to the file to write. Defaults to uni_p.txt next to the meet setup file, in the current directory for cached meets"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// Summarize a tryggivann.csv export of water safety certifications per group
+    TryggIVann {
+        /// Path to the tryggivann.csv export
+        #[arg(value_name = "export file")]
+        export: PathBuf,
+
+        #[arg(short, long, value_enum, default_value_t = TryggIVannFormat::Summary)]
+        format: TryggIVannFormat,
+
+        /// Only include certificates of this group
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Path to the file to write. Defaults to stdout
+        #[arg(short, long, value_name = "output file")]
+        output: Option<PathBuf>,
+    },
+
+    /// Replace names, e-mail addresses and other personal data in a meet setup file before sharing it
+    Scrub {
+        /// Path to meetsetup file
+        #[arg(value_name = "meet setup file")]
+        meetsetup_path: PathBuf,
+
+        /// Path to the file to write. Defaults to stdout
+        #[arg(short, long, value_name = "output file")]
+        output: Option<PathBuf>,
+    },
+}
+
+const EXIT_CODES_HELP: &str = "Exit codes:\n  0  success\n  1  the command failed\n  2  a meet setup file could not be parsed or a meet did not pass validation or a lane time needs referee review. Output is still written for the meets that could be read";
+
+const MEET_HELP: &str = "meet to use. Either a path to a meetsetup file, an NSF meet id or a part of the meet name. Ids and names are looked up in the cached meets. Names match if every word is found in the meet name, ignoring case";
+
+const ROSTER_HELP: &str = "path to a roster file with one personal best per line formatted as 'name,club,birth year,gender,sport classes,pool length,distance,style,time'. Example: 'Kari Nordmann,Bergen Svømmeklubb,2008,FEMALE,,50,100,FREESTYLE,1:02.34'. Files with the .json extension are read as JSON";
+
+const RECORDS_HELP: &str = "path to a records file with one record per line formatted as 'scope,gender group,pool length,distance,style,age class,time,name,club,date,meet'. Scope is CLUB, DISTRICT, NATIONAL or MEET. Age class is SR for open records, JR for junior records or an age. Date and meet can be left empty. Example: 'CLUB,FEMALE,25,100,FREESTYLE,14,1:01.20,Kari Nordmann,Bergen Svømmeklubb,2022-03-12,Bergen Open'";
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TouchPads {
+    OneSet,
+    TwoSet,
+    None,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TryggIVannFormat {
+    Summary,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ResultListFormat {
+    Text,
+    Html,
+    Pdf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HeatListFormat {
+    Html,
+    Pdf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum RecordScope {
+    Club,
+    District,
+    National,
+    Meet,
 }
@@ -0,0 +1,96 @@
+use crate::cli::{OutputFormat, TouchPads};
+use crate::output::{optional, Records};
+use crate::select::select;
+use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+use serde_json::Value;
+use std::{io, path::Path};
+use timing::{event::format_time, Combined, Combiner, Console, LaneEvent};
+
+const FIELDS: &[&str] = &["event", "lane", "lap", "pad_a", "pad_b", "button", "time"];
+
+/// Print the lane events read from `source` with the pad sets combined. Frames that cannot be
+/// decoded are logged and reported as an error after the events are printed.
+pub fn run(
+    source: &Path,
+    touch_pads: TouchPads,
+    meet: Option<&str>,
+    meet_dir: &Path,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let mut combiner = Combiner::new(pad_set(touch_pads, meet, meet_dir)?);
+    let mut records = Records::new(FIELDS);
+    let mut invalid = 0;
+    for event in Console::open(source)? {
+        match event {
+            Ok(event) => combiner
+                .push(event)
+                .into_iter()
+                .for_each(|combined| records.push(row(combined))),
+            Err(timing::protocol::Error::Io(why)) => return Err(why),
+            Err(why) => {
+                invalid += 1;
+                log::warn!("{why}");
+            }
+        }
+    }
+    for combined in combiner.flush() {
+        records.push(row(combined));
+    }
+    records.print(format)?;
+
+    if invalid == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{invalid} frames could not be decoded"),
+        ))
+    }
+}
+
+/// Touch pads of the selected meet, or `touch_pads` if no meet is given.
+pub fn pad_set(
+    touch_pads: TouchPads,
+    meet: Option<&str>,
+    meet_dir: &Path,
+) -> io::Result<TouchPadSet> {
+    Ok(match meet {
+        Some(meet) => select(meet, meet_dir)?.meet.touch_pads,
+        None => match touch_pads {
+            TouchPads::OneSet => TouchPadSet::OneSet,
+            TouchPads::TwoSet => TouchPadSet::TwoSet,
+            TouchPads::None => TouchPadSet::None,
+        },
+    })
+}
+
+fn row(combined: Combined) -> Vec<Value> {
+    let time = |time: std::time::Duration| Value::from(format_time(time));
+    match combined {
+        Combined::Touch(touch) => vec![
+            if touch.is_finish() { "finish" } else { "split" }.into(),
+            touch.lane.into(),
+            optional(touch.lap),
+            optional(touch.pads[0].map(time)),
+            optional(touch.pads[1].map(time)),
+            Value::Null,
+            optional(touch.time().map(time)),
+        ],
+        Combined::Event(event) => {
+            let (name, button) = match event {
+                LaneEvent::Backup { button, .. } => ("backup", Some(button)),
+                LaneEvent::FalseStart { .. } => ("false_start", None),
+                _ => ("start", None),
+            };
+            vec![
+                name.into(),
+                optional(event.lane()),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                optional(button),
+                time(event.time()),
+            ]
+        }
+    }
+}
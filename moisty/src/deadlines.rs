@@ -0,0 +1,58 @@
+use crate::cli::OutputFormat;
+use crate::output::{optional, Records};
+use crate::select::{load_all, unreadable_error};
+use chrono::Local;
+use serde_json::Value;
+use std::{io, path::Path};
+
+const FIELDS: &[&str] = &["date", "time", "days_left", "deadline", "events", "meet"];
+
+/// Print deadlines of all meets in `meet_dir` in date order.
+pub fn run(meet_dir: &Path, all: bool, format: OutputFormat) -> io::Result<()> {
+    let now = Local::now().naive_local();
+    let (meets, failed) = load_all(meet_dir)?;
+    for (path, why) in &failed {
+        log::warn!("skipping {}: {why}", path.display());
+    }
+
+    let mut rows = Vec::new();
+    for loaded in &meets {
+        let meet = &loaded.meet;
+        if meet.cancelled {
+            continue;
+        }
+
+        for deadline in meet.deadlines() {
+            let date_time = deadline.date_time();
+            if !all && date_time < now {
+                continue;
+            }
+            rows.push((date_time, deadline, meet.name.clone()));
+        }
+    }
+
+    rows.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+
+    let mut records = Records::new(FIELDS);
+    for (date_time, deadline, meet_name) in rows {
+        records.push(vec![
+            deadline.date.to_string().into(),
+            optional(
+                deadline
+                    .time
+                    .map(|time| format!("{:02}:{:02}", time.hour(), time.minute())),
+            ),
+            (date_time.date() - now.date()).num_days().into(),
+            deadline.kind.to_string().into(),
+            Value::Array(deadline.events.into_iter().map(Value::from).collect()),
+            meet_name.into(),
+        ]);
+    }
+    records.print(format)?;
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(unreadable_error(failed.len(), meets.len() + failed.len()))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::cli::OutputFormat;
+use crate::events::{event_row, session_row, EVENT_FIELDS, SESSION_FIELDS};
+use crate::output::{text, Records};
+use crate::select::{select, Loaded};
+use crate::show::{meet_row, MEET_FIELDS};
+use serde_json::Value;
+use std::{io, path::Path};
+
+const FIELDS: &[&str] = &["scope", "id", "field", "old", "new"];
+
+/// Print the differences between two versions of a meet, like a `meetsetup.xml` file that has
+/// been published again by the host. Sessions and events are matched by id.
+pub fn run(old: &str, new: &str, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let old = select(old, meet_dir)?;
+    let new = select(new, meet_dir)?;
+
+    let mut records = Records::new(FIELDS);
+    compare_rows(
+        &mut records,
+        "meet",
+        &Value::Null,
+        MEET_FIELDS,
+        &without_path(&old),
+        &without_path(&new),
+    );
+    compare(
+        &mut records,
+        "session",
+        SESSION_FIELDS,
+        old.meet.sessions.iter().map(session_row).collect(),
+        new.meet.sessions.iter().map(session_row).collect(),
+    );
+    compare(
+        &mut records,
+        "event",
+        EVENT_FIELDS,
+        old.meet.events.iter().map(event_row).collect(),
+        new.meet.events.iter().map(event_row).collect(),
+    );
+
+    if records.is_empty() && format == OutputFormat::Table {
+        println!("no differences");
+        return Ok(());
+    }
+    records.print(format)
+}
+
+/// Meet row where the path is left out, because two versions of a meet are always read from
+/// different files.
+fn without_path(loaded: &Loaded) -> Vec<Value> {
+    let mut row = meet_row(loaded);
+    if let Some(path) = MEET_FIELDS.iter().position(|field| *field == "path") {
+        row[path] = Value::Null;
+    }
+    row
+}
+
+/// Compare rows that have their id as first value. Rows only found in `old` are reported as
+/// removed and rows only found in `new` as added.
+fn compare(
+    records: &mut Records,
+    scope: &str,
+    fields: &'static [&'static str],
+    old: Vec<Vec<Value>>,
+    new: Vec<Vec<Value>>,
+) {
+    for old_row in &old {
+        match new.iter().find(|new_row| new_row[0] == old_row[0]) {
+            Some(new_row) => compare_rows(records, scope, &old_row[0], fields, old_row, new_row),
+            None => records.push(vec![
+                scope.into(),
+                old_row[0].clone(),
+                "removed".into(),
+                summary(old_row).into(),
+                Value::Null,
+            ]),
+        }
+    }
+    for new_row in &new {
+        if !old.iter().any(|old_row| old_row[0] == new_row[0]) {
+            records.push(vec![
+                scope.into(),
+                new_row[0].clone(),
+                "added".into(),
+                Value::Null,
+                summary(new_row).into(),
+            ]);
+        }
+    }
+}
+
+fn compare_rows(
+    records: &mut Records,
+    scope: &str,
+    id: &Value,
+    fields: &'static [&'static str],
+    old: &[Value],
+    new: &[Value],
+) {
+    for ((field, old), new) in fields.iter().zip(old).zip(new) {
+        if old != new {
+            records.push(vec![
+                scope.into(),
+                id.clone(),
+                (*field).into(),
+                old.clone(),
+                new.clone(),
+            ]);
+        }
+    }
+}
+
+/// Non empty values of a row after the id, separated by spaces.
+fn summary(row: &[Value]) -> String {
+    row.iter()
+        .skip(1)
+        .map(text)
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
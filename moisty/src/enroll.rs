@@ -0,0 +1,81 @@
+use crate::select;
+use jechsoft::roster::Roster;
+use jechsoft::uni_p::{generate, Selection};
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Write a `uni_p.txt` with the selected entries of `club`.
+pub fn run(
+    meet: &str,
+    meet_dir: &Path,
+    roster: &Path,
+    selections: &Path,
+    club: &str,
+    output: Option<PathBuf>,
+) -> io::Result<()> {
+    let select::Loaded { path, meet } = select::select(meet, meet_dir)?;
+    let roster = Roster::from_file(roster).map_err(io::Error::other)?;
+    let selections = parse_selections(&fs::read_to_string(selections)?, &roster, club)?;
+
+    let enrollments = generate(&meet, &roster, club, &selections);
+    for warning in &enrollments.warnings {
+        log::warn!("{warning}");
+    }
+
+    let output = output.unwrap_or_else(|| {
+        select::output(&path, meet_dir, |path| path.with_file_name("uni_p.txt"))
+    });
+    fs::write(&output, enrollments.to_uni_p())?;
+    log::info!(
+        "wrote {} entries to {}",
+        enrollments.entries.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Parse a selections file with one athlete per line formatted as 'name,event numbers'. Event
+/// numbers are separated by white space.
+fn parse_selections(content: &str, roster: &Roster, club: &str) -> io::Result<Vec<Selection>> {
+    let mut selections = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, events) = line.split_once(',').ok_or_else(|| {
+            io::Error::other(format!("line {line_number}: missing field: events"))
+        })?;
+        let name = name.trim();
+        let mut athletes = roster
+            .athletes
+            .iter()
+            .enumerate()
+            .filter(|(_, athlete)| athlete.club == club && athlete.name == name)
+            .map(|(index, _)| index);
+        let athlete = match (athletes.next(), athletes.next()) {
+            (Some(athlete), None) => athlete,
+            (None, _) => {
+                return Err(io::Error::other(format!(
+                    "line {line_number}: {name} is not in the roster of {club}"
+                )))
+            }
+            (Some(_), Some(_)) => {
+                return Err(io::Error::other(format!(
+                    "line {line_number}: more than one athlete in {club} is named {name}"
+                )))
+            }
+        };
+
+        for event in events.split_whitespace() {
+            let event = event.parse::<u32>().map_err(|_| {
+                io::Error::other(format!("line {line_number}: invalid field: events"))
+            })?;
+            selections.push(Selection { athlete, event });
+        }
+    }
+
+    Ok(selections)
+}
@@ -0,0 +1,75 @@
+use crate::cli::OutputFormat;
+use crate::output::{optional, Records};
+use crate::select::select;
+use jechsoft::meet_setup::{event::Event, meet::Meet, session::Session};
+use jechsoft::swim_time;
+use serde_json::Value;
+use std::{io, path::Path};
+
+pub const EVENT_FIELDS: &[&str] = &[
+    "event",
+    "distance",
+    "style",
+    "gender",
+    "date",
+    "session",
+    "pool_length",
+    "sorting",
+    "qualification_long_course",
+    "qualification_short_course",
+    "description",
+];
+
+pub const SESSION_FIELDS: &[&str] = &["session", "name", "date", "start_time"];
+
+pub fn event_row(event: &Event) -> Vec<Value> {
+    vec![
+        event.id.into(),
+        event.distance.to_string().into(),
+        event.style.to_string().into(),
+        event.gender_group.to_string().into(),
+        event.date.to_string().into(),
+        optional(event.ses_id),
+        event.pool_length.to_string().into(),
+        event.sorting.to_string().into(),
+        optional(event.qualification_time_long_course.map(swim_time::format)),
+        optional(event.qualification_time_short_course.map(swim_time::format)),
+        event.description.trim().into(),
+    ]
+}
+
+pub fn session_row(session: &Session) -> Vec<Value> {
+    vec![
+        session.id.into(),
+        session.name.trim().into(),
+        session.date.to_string().into(),
+        format!(
+            "{:02}:{:02}",
+            session.start_time.hour(),
+            session.start_time.minute()
+        )
+        .into(),
+    ]
+}
+
+pub fn event_records(meet: &Meet) -> Records {
+    let mut records = Records::new(EVENT_FIELDS);
+    for event in &meet.events {
+        records.push(event_row(event));
+    }
+    records
+}
+
+pub fn session_records(meet: &Meet) -> Records {
+    let mut records = Records::new(SESSION_FIELDS);
+    for session in &meet.sessions {
+        records.push(session_row(session));
+    }
+    records
+}
+
+/// Print the events of the selected meet.
+pub fn run(selector: &str, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let loaded = select(selector, meet_dir)?;
+    event_records(&loaded.meet).print(format)
+}
@@ -0,0 +1,46 @@
+use crate::cli::OutputFormat;
+use crate::events::{event_records, session_records};
+use crate::output::Records;
+use crate::select::select;
+use crate::show::{meet_row, MEET_FIELDS};
+use serde_json::Value;
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Write the selected meet with its sessions and events to `output`, or stdout if no output is
+/// given. JSON holds the whole meet, NDJSON the same on a single line. CSV and table hold the
+/// events only.
+pub fn run(
+    selector: &str,
+    meet_dir: &Path,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+) -> io::Result<()> {
+    let loaded = select(selector, meet_dir)?;
+    let events = event_records(&loaded.meet);
+
+    let content = match format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let mut records = Records::new(MEET_FIELDS);
+            records.push(meet_row(&loaded));
+            let mut meet = records.objects().remove(0);
+            meet["sessions"] = Value::Array(session_records(&loaded.meet).objects());
+            meet["events"] = Value::Array(events.objects());
+            if format == OutputFormat::Json {
+                format!("{meet:#}\n")
+            } else {
+                format!("{meet}\n")
+            }
+        }
+        OutputFormat::Csv => events.to_csv(),
+        OutputFormat::Table => events.to_table() + "\n",
+    };
+
+    match output {
+        Some(output) => {
+            fs::write(&output, content)?;
+            log::info!("wrote {}", output.display());
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
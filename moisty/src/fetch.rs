@@ -0,0 +1,78 @@
+use crate::cli::OutputFormat;
+use crate::output::{optional, Records};
+use crate::select::{load, unreadable_error};
+use chrono::{Local, NaiveDate};
+use jechsoft::medley::utils::{download_meets, get_meet_list, DownloadStatus};
+use std::{io, path::Path};
+
+const FIELDS: &[&str] = &[
+    "nsf_meet_id",
+    "date_start",
+    "date_end",
+    "name",
+    "host",
+    "path",
+    "status",
+    "parsed",
+    "error",
+];
+
+/// Download the meets published on medley.no from `date`, or today, into `meet_dir`. Meets that
+/// are already cached are not downloaded again.
+///
+/// Prints a summary with one row per published meet. Status is "downloaded", "cached" or
+/// "failed", and every downloaded or cached meet is parsed to report files that cannot be read.
+pub fn run(date: Option<NaiveDate>, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let date = date.unwrap_or_else(|| Local::now().naive_local().date());
+    let meet_infos = get_meet_list(date).map_err(|why| io::Error::other(why.to_string()))?;
+
+    let downloads = download_meets(meet_dir, meet_infos);
+    let mut records = Records::new(FIELDS);
+    let mut failed = 0;
+    let mut unreadable = 0;
+    for download in &downloads {
+        let (status, parsed, error) = match &download.status {
+            DownloadStatus::Failed(why) => {
+                failed += 1;
+                ("failed", None, Some(why.clone()))
+            }
+            status => {
+                let status = match status {
+                    DownloadStatus::Cached => "cached",
+                    _ => "downloaded",
+                };
+                match load(&download.path) {
+                    Ok(_) => (status, Some(true), None),
+                    Err(why) => {
+                        unreadable += 1;
+                        (status, Some(false), Some(why.to_string()))
+                    }
+                }
+            }
+        };
+        let meet_info = &download.meet_info;
+        records.push(vec![
+            meet_info.id.into(),
+            meet_info.date_start.to_string().into(),
+            meet_info.date_end.to_string().into(),
+            meet_info.name.trim().into(),
+            meet_info.host.trim().into(),
+            download.path.display().to_string().into(),
+            status.into(),
+            optional(parsed),
+            optional(error),
+        ]);
+    }
+    records.print(format)?;
+
+    if failed > 0 {
+        return Err(io::Error::other(format!(
+            "{failed} of {} meets could not be downloaded",
+            downloads.len()
+        )));
+    }
+    if unreadable > 0 {
+        return Err(unreadable_error(unreadable, downloads.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::cli::HeatListFormat;
+use crate::select;
+use jechsoft::heat_list::{to_html, to_pdf, Entries};
+use jechsoft::records::Records;
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Seed the entries of a meet and write the heat list to a file.
+pub fn run(
+    meet: &str,
+    meet_dir: &Path,
+    entries: &Path,
+    format: HeatListFormat,
+    records: Option<PathBuf>,
+    output: Option<PathBuf>,
+) -> io::Result<()> {
+    let select::Loaded { path, meet } = select::select(meet, meet_dir)?;
+    let entries = Entries::from_file(entries).map_err(io::Error::other)?;
+    let config = meet.heat_list_config();
+    let start_lists = entries
+        .start_lists(&meet, &config)
+        .map_err(io::Error::other)?;
+    let records = match records {
+        Some(records) => {
+            if !config.records_in_heat_list {
+                log::warn!("the meet setup does not print records in heat lists");
+            }
+            Records::from_file(&records).map_err(io::Error::other)?
+        }
+        None => Records::default(),
+    };
+
+    let (extension, content) = match format {
+        HeatListFormat::Html => (
+            "html",
+            to_html(&meet, &config, &start_lists, &records).into_bytes(),
+        ),
+        HeatListFormat::Pdf => (
+            "pdf",
+            to_pdf(&meet, &config, &start_lists, &records).map_err(io::Error::other)?,
+        ),
+    };
+    let output = output
+        .unwrap_or_else(|| select::output(&path, meet_dir, |path| path.with_extension(extension)));
+    fs::write(&output, content)?;
+
+    log::info!(
+        "wrote heat list for {} events to {}",
+        start_lists.len(),
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::cli::OutputFormat;
+use crate::output::{optional, optional_text, Records};
+use crate::select::{load_all, matches, unreadable_error};
+use std::{io, path::Path};
+
+const FIELDS: &[&str] = &[
+    "nsf_meet_id",
+    "date_start",
+    "date_end",
+    "name",
+    "location",
+    "path",
+];
+
+/// List the cached meets in `meet_dir`, optionally only those matching `selector`. Meets that
+/// cannot be parsed are logged and reported as an error after the list is printed.
+pub fn run(selector: Option<&str>, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let (meets, failed) = load_all(meet_dir)?;
+    for (path, why) in &failed {
+        log::warn!("skipping {}: {why}", path.display());
+    }
+
+    let mut records = Records::new(FIELDS);
+    for loaded in meets
+        .iter()
+        .filter(|loaded| selector.is_none_or(|selector| matches(&loaded.meet, selector)))
+    {
+        let meet = &loaded.meet;
+        records.push(vec![
+            optional(meet.nsf_meet_id),
+            optional_text(meet.date_start),
+            optional_text(meet.date_end),
+            meet.name.trim().into(),
+            meet.location.trim().into(),
+            loaded.path.display().to_string().into(),
+        ]);
+    }
+    records.print(format)?;
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(unreadable_error(failed.len(), meets.len() + failed.len()))
+    }
+}
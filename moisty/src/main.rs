@@ -8,18 +8,41 @@ extern crate jechsoft;
 extern crate tabled;
 extern crate url;
 
+mod cache;
+mod calendar;
 mod cli;
+mod console;
+mod deadlines;
+mod diff;
+mod enroll;
+mod events;
+mod export;
+mod fetch;
+mod heat_list;
+mod list;
+mod output;
+mod points;
+mod qualification;
+mod reconcile;
+mod records;
+mod relay;
+mod results;
+mod roster;
+mod scoreboard;
+mod scrub;
+mod select;
+mod serve;
+mod show;
+mod trygg_i_vann;
+mod tui;
+mod validate;
 // mod validators;
 
 use crate::clap::Parser;
-use crate::cli::Cli;
-use chrono::Local;
+use crate::cli::{Cli, Command};
+use crate::select::select;
 use directories::BaseDirs;
-use jechsoft::medley::utils::{download_meets, get_meet_list};
-use jechsoft::meet_setup::meet::Meet;
-use std::fs;
 use std::{io, path::PathBuf};
-use tabled::{builder::Builder, settings::Style};
 
 // TODO: download meet files into one directory and move them if parsing is successful.
 // TODO: auto complete on command line the parsed meets?
@@ -27,130 +50,172 @@ fn main() -> io::Result<()> {
     colog::init();
     let cli = Cli::parse();
 
-    let base_dir = BaseDirs::new();
-    let cache_dir = match base_dir {
-        None => unimplemented!("cannot deal with system without configured cache directory"),
-        Some(base_dir) => base_dir.cache_dir().join("moisty/meets"),
-    };
-    let download_dir = cache_dir.join("downloads");
-    let parsed_dir = cache_dir.join("parsed");
-
-    if cli.clear_cache {
-        fs::remove_dir_all(&cache_dir)?;
-    }
-    fs::create_dir_all(&cache_dir).unwrap();
-    fs::create_dir_all(&download_dir).unwrap();
-    fs::create_dir_all(parsed_dir).unwrap();
-
-    if cli.download {
-        let search_date_start = cli.date.unwrap_or(Local::now().naive_local().date());
-        match get_meet_list(search_date_start) {
-            Ok(meets_to_download) => download_meets(&download_dir, meets_to_download),
-            Err(why) => panic!("{why}"),
-        };
+    if let Err(why) = run(cli.command) {
+        log::error!("{why}");
+        std::process::exit(exit_code(&why));
     }
+    Ok(())
+}
 
-    let meet_setup_paths = match cli.meetsetup_path {
-        Some(path_meetsetup_file) => {
-            vec![PathBuf::from(path_meetsetup_file)]
+/// Run `command`. The cache directory is only looked up by commands that use cached meets.
+fn run(command: Command) -> io::Result<()> {
+    let meet_dir = cache_dir().map(|cache_dir| cache_dir.join("downloads"));
+
+    match command {
+        Command::Fetch { date, format } => fetch::run(date, &meet_dir?, format),
+        Command::List { meet, format } => list::run(meet.as_deref(), &meet_dir?, format),
+        Command::Show { meet, format } => show::run(&meet, &meet_dir?, format),
+        Command::Events { meet, format } => events::run(&meet, &meet_dir?, format),
+        Command::Validate { meet, format } => validate::run(meet.as_deref(), &meet_dir?, format),
+        Command::Export {
+            meet,
+            format,
+            output,
+        } => export::run(&meet, &meet_dir?, format, output),
+        Command::Diff { old, new, format } => diff::run(&old, &new, &meet_dir?, format),
+        Command::Cache { clear, format } => cache::run(&cache_dir()?, &meet_dir?, clear, format),
+        Command::Timing {
+            source,
+            touch_pads,
+            meet,
+            format,
+        } => console::run(&source, touch_pads, meet.as_deref(), &meet_dir?, format),
+        Command::Tui => tui::run(&meet_dir?),
+        Command::Serve {
+            address,
+            results,
+            entries,
+        } => serve::run(&meet_dir?, &address, results, entries),
+        Command::Reconcile {
+            source,
+            touch_pads,
+            meet,
+            threshold,
+            format,
+        } => reconcile::run(
+            &source,
+            touch_pads,
+            meet.as_deref(),
+            &meet_dir?,
+            threshold,
+            format,
+        ),
+        Command::Points {
+            base_times,
+            distance,
+            style,
+            gender_group,
+            pool_length,
+            time,
+        } => points::run(
+            &base_times,
+            distance,
+            style,
+            gender_group,
+            pool_length,
+            time,
+        ),
+        Command::Calendar {
+            meet,
+            output,
+            no_events,
+        } => calendar::run(&meet, &meet_dir?, output, no_events),
+        Command::Deadlines { all, format } => deadlines::run(&meet_dir?, all, format),
+        Command::HeatList {
+            meet,
+            entries,
+            format,
+            records,
+            output,
+        } => heat_list::run(&meet, &meet_dir?, &entries, format, records, output),
+        Command::Scoreboard {
+            meet,
+            entries,
+            source,
+            event,
+            heat,
+            serial,
+            tcp,
+            realtime,
+        } => {
+            let target = match (serial, tcp) {
+                (Some(device), _) => scoreboard::Target::Serial(device),
+                (None, Some(address)) => scoreboard::Target::Tcp(address),
+                (None, None) => scoreboard::Target::Terminal,
+            };
+            let meet = select(&meet, &meet_dir?)?.meet;
+            scoreboard::run(&meet, &entries, &source, event, heat, target, realtime)
         }
-        None => fs::read_dir(&download_dir)?
-            .filter_map(Result::ok)
-            .filter(|entry| entry.path().is_file())
-            .map(|entry| entry.path())
-            .collect::<Vec<_>>(),
-    };
-
-    let (meets, failed): (Vec<_>, Vec<_>) = meet_setup_paths
-        .into_iter()
-        .map(|meet_setup_file| Meet::try_from(&meet_setup_file))
-        .partition(Result::is_ok);
-
-    let meets: Vec<_> = meets.into_iter().map(Result::unwrap).collect();
-    let failed: Vec<_> = failed.into_iter().map(Result::unwrap_err).collect();
-
-    let meets_count = meets.len();
-    let failed_count = failed.len();
-
-    log::info!(
-        "parsed {} out of {} meets",
-        meets_count,
-        meets_count + failed_count
-    );
-
-    for fail in failed {
-        log::error!("{fail}");
-    }
-
-    for meet in meets {
-        if cli.list {
-            let mut header_builder = Builder::default();
-            header_builder.push_record::<&[String; 3]>(&[
-                "NSF meet id".into(),
-                "Date".into(),
-                "Name".into(),
-            ]);
-
-            if meet.date_start.is_some() && meet.date_end.is_some() {
-                println!(
-                    "[{:0>10}] [{} {}] {}",
-                    meet.nsf_meet_id.unwrap_or(0),
-                    meet.date_start.unwrap(),
-                    meet.date_end.unwrap(),
-                    meet.name
-                )
-            } else {
-                println!(
-                    "[{:0>10}] {}, {}",
-                    meet.nsf_meet_id.unwrap_or(0),
-                    meet.date,
-                    meet.name
-                )
-            }
+        Command::Results {
+            meet,
+            results,
+            format,
+            output,
+            base_times,
+            records,
+        } => results::run(
+            &meet, &meet_dir?, &results, format, output, base_times, records,
+        ),
+        Command::Qualification { meet, roster, club } => {
+            qualification::run(&meet, &meet_dir?, &roster, club.as_deref())
         }
+        Command::Roster {
+            roster,
+            update,
+            club,
+        } => roster::run(&roster, update.as_deref(), club.as_deref()),
+        Command::Records {
+            records,
+            import,
+            scope,
+            update,
+        } => records::run(&records, import.as_deref(), scope, update.as_deref()),
+        Command::Relay {
+            meet,
+            event,
+            roster,
+            club,
+            teams,
+        } => relay::run(&meet, &meet_dir?, event, &roster, &club, teams),
+        Command::Enroll {
+            meet,
+            roster,
+            selections,
+            club,
+            output,
+        } => enroll::run(&meet, &meet_dir?, &roster, &selections, &club, output),
+        Command::TryggIVann {
+            export,
+            format,
+            group,
+            output,
+        } => trygg_i_vann::run(&export, format, group.as_deref(), output),
+        Command::Scrub {
+            meetsetup_path,
+            output,
+        } => scrub::run(&meetsetup_path, output),
+    }
+}
 
-        if cli.table {
-            let mut header_builder = Builder::default();
-            header_builder.push_record::<[&str; 5]>([
-                "Meet name",
-                "Date",
-                "Sessions",
-                "Location",
-                "NSF id",
-            ]);
-            header_builder.push_record(&[
-                meet.name,
-                meet.date,
-                meet.sessions.len().to_string(),
-                meet.location,
-                match meet.nsf_meet_id {
-                    Some(nsf_meet_id) => format!("{:0>10}", nsf_meet_id),
-                    None => "".to_string(),
-                },
-            ]);
-            let mut table = header_builder.build();
-            table.with(Style::rounded());
-            println!("{}", table);
-
-            let mut builder = Builder::default();
-            builder.push_record([
-                "Event", "Distance", "Style", "Gender", "Date", "Sorting", //"Description",
-            ]);
-            for event in meet.events {
-                let row = [
-                    event.id.to_string(),
-                    event.distance.to_string(),
-                    event.style.to_string(),
-                    event.gender_group.to_string(),
-                    event.date.to_string(),
-                    event.sorting.to_string(),
-                    //event.description.to_string(),
-                ];
-                builder.push_record(row);
-            }
-            let table = builder.build().with(Style::rounded()).to_string();
-            println!("{table}");
-        }
+/// Exit code of a failed command. Meet setup files that cannot be parsed or do not pass
+/// validation are reported as `io::ErrorKind::InvalidData` and exit with 2, everything else with 1.
+/// See `cli::EXIT_CODES_HELP`.
+fn exit_code(error: &io::Error) -> i32 {
+    match error.kind() {
+        io::ErrorKind::InvalidData => 2,
+        _ => 1,
     }
-    Ok(())
+}
+
+/// # Errors
+/// Returns an error if the system has no cache directory, for example without a home directory.
+fn cache_dir() -> io::Result<PathBuf> {
+    BaseDirs::new()
+        .map(|base_dir| base_dir.cache_dir().join("moisty/meets"))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "cannot find the cache directory of this system",
+            )
+        })
 }
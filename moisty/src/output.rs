@@ -0,0 +1,155 @@
+use crate::cli::OutputFormat;
+use serde_json::{Map, Value};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use tabled::{builder::Builder, settings::Style};
+
+/// Rows of values with a fixed set of field names. Field names are used as table headers, CSV
+/// headers and JSON keys, so they are part of the output format and should not be renamed.
+/// Values keep their JSON type in JSON and NDJSON: numbers and booleans are not quoted and missing
+/// values are `null`. Dates are written as 'YYYY-MM-DD' and swim times as 'mm:ss.hh'.
+pub struct Records {
+    fields: &'static [&'static str],
+    rows: Vec<Vec<Value>>,
+}
+
+impl Records {
+    pub const fn new(fields: &'static [&'static str]) -> Self {
+        Self {
+            fields,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row. Values are matched with the fields by position.
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.fields.len());
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub const fn fields(&self) -> &'static [&'static str] {
+        self.fields
+    }
+
+    pub fn rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// One JSON object per row.
+    pub fn objects(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|row| {
+                Value::Object(
+                    self.fields
+                        .iter()
+                        .map(|field| (*field).to_string())
+                        .zip(row.iter().cloned())
+                        .collect::<Map<String, Value>>(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.objects()).unwrap_or_default()
+    }
+
+    /// One JSON object per line.
+    pub fn to_ndjson(&self) -> String {
+        self.objects()
+            .iter()
+            .map(|object| object.to_string() + "\n")
+            .collect()
+    }
+
+    /// Comma separated values with a header line. Values with commas, quotes or line breaks are
+    /// quoted.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let _ = writeln!(csv, "{}", self.fields.join(","));
+        for row in &self.rows {
+            let line = row
+                .iter()
+                .map(|value| csv_field(&text(value)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = writeln!(csv, "{line}");
+        }
+        csv
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut builder = Builder::default();
+        builder.push_record(self.fields.iter().map(|field| field.replace('_', " ")));
+        for row in &self.rows {
+            builder.push_record(row.iter().map(text));
+        }
+        builder.build().with(Style::rounded()).to_string()
+    }
+
+    /// Table with one line per field. Used for single records that are too wide for a table.
+    pub fn to_vertical_table(&self) -> String {
+        let mut builder = Builder::default();
+        for (index, field) in self.fields.iter().enumerate() {
+            let mut record = vec![field.replace('_', " ")];
+            record.extend(self.rows.iter().map(|row| text(&row[index])));
+            builder.push_record(record);
+        }
+        builder.build().with(Style::rounded()).to_string()
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table => self.to_table(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Csv => self.to_csv(),
+            OutputFormat::Ndjson => self.to_ndjson(),
+        }
+    }
+
+    /// Print the records to stdout. Empty NDJSON output prints nothing.
+    ///
+    /// # Errors
+    /// Returns an error if stdout is closed, like when the output is piped into `head`.
+    pub fn print(&self, format: OutputFormat) -> io::Result<()> {
+        let mut output = self.render(format);
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        io::stdout().lock().write_all(output.as_bytes())
+    }
+}
+
+/// Value as plain text. Strings are written without quotes, arrays as a comma separated list and
+/// `null` as an empty string.
+pub fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        Value::Array(values) => values.iter().map(text).collect::<Vec<_>>().join(", "),
+        value => value.to_string(),
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// `Some(value)` as a JSON value and `None` as `null`.
+pub fn optional<T: Into<Value>>(value: Option<T>) -> Value {
+    value.map_or(Value::Null, Into::into)
+}
+
+/// `Some(value)` as a JSON string and `None` as `null`.
+pub fn optional_text<T: ToString>(value: Option<T>) -> Value {
+    value.map_or(Value::Null, |value| Value::String(value.to_string()))
+}
@@ -0,0 +1,28 @@
+use jechsoft::meet_setup::{
+    distance::Distance, gender_group::GenderGroup, pool_length::PoolLength, style::Style,
+};
+use jechsoft::points::BaseTimes;
+use jechsoft::swim_time;
+use std::{io, path::Path, time::Duration};
+
+/// Print World Aquatics points for a single swim.
+pub fn run(
+    base_times: &Path,
+    distance: Distance,
+    style: Style,
+    gender_group: GenderGroup,
+    pool_length: PoolLength,
+    time: Duration,
+) -> io::Result<()> {
+    let base_times = BaseTimes::from_file(base_times).map_err(io::Error::other)?;
+    let style = style.resolve_handicap_medley(distance, "", None);
+    let points = base_times
+        .points(time, distance, style, gender_group, pool_length)
+        .map_err(io::Error::other)?;
+
+    println!(
+        "{distance} {style} {gender_group} {pool_length} pool {}: {points} points",
+        swim_time::format(time)
+    );
+    Ok(())
+}
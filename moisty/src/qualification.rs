@@ -0,0 +1,70 @@
+use crate::select;
+use jechsoft::qualification::{check, Limit, Status};
+use jechsoft::roster::Roster;
+use jechsoft::swim_time;
+use std::{io, path::Path};
+use tabled::{builder::Builder, settings::Style as TableStyle};
+
+/// Print a qualification report for every athlete in `roster`.
+pub fn run(meet: &str, meet_dir: &Path, roster: &Path, club: Option<&str>) -> io::Result<()> {
+    let meet = select::select(meet, meet_dir)?.meet;
+    let roster = Roster::from_file(roster).map_err(io::Error::other)?;
+
+    for athlete in &roster.athletes {
+        if club.is_some_and(|club| club != athlete.club) {
+            continue;
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record(["Event", "Status", "Best", "Limit", "Diff"]);
+        let mut qualified = 0;
+        let mut eligible = 0;
+        for qualification in check(&meet, &athlete.profile()) {
+            let event = qualification.event;
+            let (status, limit) = match qualification.status {
+                Status::NotEligible => continue,
+                Status::Qualified(limit) => ("qualified", Some(limit)),
+                Status::NotQualified(limit) => ("not qualified", Some(limit)),
+                Status::Open => ("open", None),
+                Status::Exempt => ("exempt", None),
+            };
+            eligible += 1;
+            if qualification.status.is_qualified() {
+                qualified += 1;
+            }
+            builder.push_record([
+                format!("{} {} {}", event.id, event.distance, event.style),
+                status.to_string(),
+                limit
+                    .and_then(|limit| limit.best_time)
+                    .map_or_else(String::new, swim_time::format),
+                limit.map_or_else(String::new, |limit| {
+                    format!("{} ({})", swim_time::format(limit.limit), limit.pool_length)
+                }),
+                limit.map_or_else(String::new, diff),
+            ]);
+        }
+
+        let name = &athlete.name;
+        let birth_year = athlete.birth_year.to_number();
+        let athlete_club = &athlete.club;
+        if eligible == 0 {
+            println!("{name} ({birth_year}), {athlete_club}: not eligible for any events");
+            continue;
+        }
+        println!(
+            "{name} ({birth_year}), {athlete_club}: qualified for {qualified} of {eligible} events"
+        );
+        println!("{}", builder.build().with(TableStyle::rounded()));
+    }
+    Ok(())
+}
+
+/// Difference to the limit. Negative when the athlete is faster than the limit.
+fn diff(limit: Limit) -> String {
+    match (limit.margin(), limit.missing()) {
+        (Some(margin), _) => format!("-{}", swim_time::format(margin)),
+        (None, Some(missing)) => format!("+{}", swim_time::format(missing)),
+        (None, None) => String::from("no time"),
+    }
+}
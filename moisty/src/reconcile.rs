@@ -0,0 +1,101 @@
+use crate::cli::{OutputFormat, TouchPads};
+use crate::console::pad_set;
+use crate::output::{optional, Records};
+use serde_json::Value;
+use std::time::Duration;
+use std::{io, path::Path};
+use timing::event::format_time;
+use timing::{Combined, Combiner, Console, LaneEvent, LaneTimes, Reconciler};
+
+const FIELDS: &[&str] = &[
+    "heat", "lane", "time", "source", "pad_a", "pad_b", "button_1", "button_2", "button_3",
+    "review", "flags",
+];
+
+/// Print the official time of every lane in the heats read from `source`. Heats start at each
+/// start signal. Lanes with a time that needs referee review are reported as an error after the
+/// times are printed.
+pub fn run(
+    source: &Path,
+    touch_pads: TouchPads,
+    meet: Option<&str>,
+    meet_dir: &Path,
+    threshold: Option<Duration>,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let pad_set = pad_set(touch_pads, meet, meet_dir)?;
+    let mut combiner = Combiner::new(pad_set);
+    let mut heats: Vec<Vec<Combined>> = Vec::new();
+    for event in Console::open(source)? {
+        match event {
+            Ok(event) => {
+                for combined in combiner.push(event) {
+                    push(&mut heats, combined);
+                }
+            }
+            Err(timing::protocol::Error::Io(why)) => return Err(why),
+            Err(why) => log::warn!("{why}"),
+        }
+    }
+    for combined in combiner.flush() {
+        push(&mut heats, combined);
+    }
+
+    let mut reconciler = Reconciler::new(pad_set);
+    if let Some(threshold) = threshold {
+        reconciler = reconciler.with_threshold(threshold);
+    }
+    let mut records = Records::new(FIELDS);
+    let mut review = 0;
+    for (heat, events) in (1..).zip(&heats) {
+        for times in LaneTimes::from_events(events) {
+            let reconciled = reconciler.reconcile(&times);
+            if reconciled.needs_review() {
+                review += 1;
+            }
+            let time = |time: Duration| Value::from(format_time(time));
+            let mut row = vec![
+                Value::from(heat),
+                times.lane.into(),
+                optional(reconciled.time.map(time)),
+                optional(reconciled.source.map(|source| source.to_string())),
+            ];
+            row.extend(times.pads.into_iter().map(|pad| optional(pad.map(time))));
+            row.extend(
+                times
+                    .buttons
+                    .into_iter()
+                    .map(|button| optional(button.map(time))),
+            );
+            row.push(reconciled.needs_review().into());
+            row.push(
+                reconciled
+                    .flags
+                    .iter()
+                    .map(|flag| Value::from(flag.to_string()))
+                    .collect(),
+            );
+            records.push(row);
+        }
+    }
+    records.print(format)?;
+
+    if review == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{review} lanes need referee review"),
+        ))
+    }
+}
+
+/// Add an event to the current heat. A start signal begins a new heat.
+fn push(heats: &mut Vec<Vec<Combined>>, combined: Combined) {
+    match heats.last_mut() {
+        Some(heat) if !matches!(combined, Combined::Event(LaneEvent::Start { .. })) => {
+            heat.push(combined);
+        }
+        _ => heats.push(vec![combined]),
+    }
+}
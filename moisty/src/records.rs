@@ -0,0 +1,110 @@
+use crate::cli::RecordScope;
+use crate::select;
+use jechsoft::meet_result::MeetResult;
+use jechsoft::records::{Records, Scope};
+use jechsoft::swim_time;
+use std::{fs, io, path::Path, path::PathBuf};
+use tabled::{builder::Builder, settings::Style};
+
+/// Print the records in `records_path`, import the records of a Lenex file when `import` is set,
+/// or update the records from the results of a meet when `update` holds a meet setup file and a
+/// results file. Imports and updates save the records file.
+pub fn run(
+    records_path: &Path,
+    import: Option<&Path>,
+    scope: Option<RecordScope>,
+    update: Option<&[PathBuf]>,
+) -> io::Result<()> {
+    if let (Some(import), Some(scope)) = (import, scope) {
+        let mut records = if records_path.exists() {
+            Records::from_file(records_path).map_err(io::Error::other)?
+        } else {
+            Records::default()
+        };
+        let lenex = fs::read_to_string(import)?;
+        let imported = Records::from_lenex(&lenex, scope.into()).map_err(io::Error::other)?;
+        let count = records.merge(imported);
+        records.save(records_path).map_err(io::Error::other)?;
+        log::info!(
+            "imported {count} records from {} to {}",
+            import.display(),
+            records_path.display()
+        );
+        return Ok(());
+    }
+
+    let mut records = Records::from_file(records_path).map_err(io::Error::other)?;
+
+    let Some([meetsetup_path, results]) = update else {
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Scope",
+            "Event",
+            "Pool",
+            "Age class",
+            "Time",
+            "Name",
+            "Club",
+            "Date",
+            "Meet",
+        ]);
+        for (key, record) in records.sorted() {
+            builder.push_record([
+                key.scope.to_string(),
+                format!("{} {} {}", key.distance, key.style, key.gender_group),
+                key.pool_length.to_string(),
+                key.age_class.to_string(),
+                swim_time::format(record.time),
+                record.name.clone(),
+                record.club.clone(),
+                record.date.map(|date| date.to_string()).unwrap_or_default(),
+                record.meet.clone().unwrap_or_default(),
+            ]);
+        }
+        println!("{}", builder.build().with(Style::rounded()));
+        return Ok(());
+    };
+
+    let meet = select::load(meetsetup_path)?;
+    let mut results = MeetResult::from_file(results).map_err(io::Error::other)?;
+    let new_records = records
+        .update(&meet, &mut results)
+        .map_err(io::Error::other)?;
+
+    let mut builder = Builder::default();
+    builder.push_record(["Record", "Event", "Name", "Club", "Previous", "New"]);
+    for new_record in &new_records {
+        let key = new_record.key;
+        builder.push_record([
+            key.label(),
+            format!(
+                "{} {} {} {}",
+                new_record.event, key.distance, key.style, key.pool_length
+            ),
+            new_record.record.name.clone(),
+            new_record.record.club.clone(),
+            swim_time::format(new_record.previous.time),
+            swim_time::format(new_record.record.time),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::rounded()));
+
+    records.save(records_path).map_err(io::Error::other)?;
+    log::info!(
+        "saved {} new records to {}",
+        new_records.len(),
+        records_path.display()
+    );
+    Ok(())
+}
+
+impl From<RecordScope> for Scope {
+    fn from(scope: RecordScope) -> Self {
+        match scope {
+            RecordScope::Club => Self::Club,
+            RecordScope::District => Self::District,
+            RecordScope::National => Self::National,
+            RecordScope::Meet => Self::Meet,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::select::select;
+use jechsoft::meet_setup::style::Style;
+use jechsoft::relay::{optimize, Constraints, Swimmer};
+use jechsoft::roster::Roster;
+use jechsoft::swim_time;
+use std::{io, path::Path};
+use tabled::{builder::Builder, settings::Style as TableStyle};
+
+/// Print the `teams` fastest line-ups of the athletes of `club` for relay `event_id` of `meet`.
+pub fn run(
+    meet: &str,
+    meet_dir: &Path,
+    event_id: u32,
+    roster: &Path,
+    club: &str,
+    teams: usize,
+) -> io::Result<()> {
+    let meet = select(meet, meet_dir)?.meet;
+    let event = meet
+        .events
+        .iter()
+        .find(|event| event.id == event_id)
+        .ok_or_else(|| io::Error::other(format!("{} has no event {event_id}", meet.name)))?;
+    let roster = Roster::from_file(roster).map_err(io::Error::other)?;
+    let swimmers: Vec<Swimmer> = roster
+        .club(club)
+        .map(|athlete| athlete.relay_swimmer(event.pool_length))
+        .collect();
+
+    let constraints = Constraints::from(event);
+    let line_ups = optimize(event, &constraints, &swimmers, teams).map_err(io::Error::other)?;
+    if let Some(max) = constraints.max_class_points {
+        log::info!("line-ups are limited to {max} class points");
+    }
+
+    for (team, line_up) in line_ups.iter().enumerate() {
+        let mut builder = Builder::default();
+        builder.push_record(["Leg", "Swimmer", "Stroke", "Time"]);
+        for (number, leg) in line_up.legs.iter().enumerate() {
+            builder.push_record([
+                (number + 1).to_string(),
+                swimmers[leg.swimmer].name.clone(),
+                Style::Single(leg.stroke).to_string(),
+                swim_time::format(leg.time),
+            ]);
+        }
+        builder.push_record([
+            String::new(),
+            "Total".to_string(),
+            String::new(),
+            swim_time::format(line_up.total),
+        ]);
+
+        let team = char::from(b'A' + u8::try_from(team % 26).unwrap_or_default());
+        println!(
+            "{team} team {} {} {}",
+            event.id, event.distance, event.style
+        );
+        println!("{}", builder.build().with(TableStyle::rounded()));
+    }
+    if line_ups.len() < teams {
+        log::info!(
+            "only enough eligible swimmers for {} of {teams} teams",
+            line_ups.len()
+        );
+    }
+    Ok(())
+}
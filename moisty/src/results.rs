@@ -0,0 +1,57 @@
+use crate::cli::ResultListFormat;
+use crate::select;
+use jechsoft::meet_result::{to_html, to_pdf, to_text, MeetResult};
+use jechsoft::points::BaseTimes;
+use jechsoft::records::Records;
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Render the results of a meet as a result list.
+pub fn run(
+    meet: &str,
+    meet_dir: &Path,
+    results: &Path,
+    format: ResultListFormat,
+    output: Option<PathBuf>,
+    base_times: Option<PathBuf>,
+    records: Option<PathBuf>,
+) -> io::Result<()> {
+    let select::Loaded { path, meet } = select::select(meet, meet_dir)?;
+    let mut results = MeetResult::from_file(results).map_err(io::Error::other)?;
+    if let Some(base_times) = base_times {
+        let base_times = BaseTimes::from_file(&base_times).map_err(io::Error::other)?;
+        results
+            .add_points(&meet, &base_times)
+            .map_err(io::Error::other)?;
+    }
+    if let Some(records) = records {
+        let mut records = Records::from_file(&records).map_err(io::Error::other)?;
+        let new_records = records
+            .update(&meet, &mut results)
+            .map_err(io::Error::other)?;
+        log::info!("{} records broken", new_records.len());
+    }
+
+    let (extension, content) = match format {
+        ResultListFormat::Text => {
+            let text = to_text(&meet, &results).map_err(io::Error::other)?;
+            if output.is_none() {
+                print!("{text}");
+                return Ok(());
+            }
+            ("txt", text.into_bytes())
+        }
+        ResultListFormat::Html => (
+            "html",
+            to_html(&meet, &results)
+                .map_err(io::Error::other)?
+                .into_bytes(),
+        ),
+        ResultListFormat::Pdf => ("pdf", to_pdf(&meet, &results).map_err(io::Error::other)?),
+    };
+    let output = output
+        .unwrap_or_else(|| select::output(&path, meet_dir, |path| path.with_extension(extension)));
+    fs::write(&output, content)?;
+
+    log::info!("wrote result list to {}", output.display());
+    Ok(())
+}
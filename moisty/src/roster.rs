@@ -0,0 +1,73 @@
+use crate::select;
+use jechsoft::meet_result::MeetResult;
+use jechsoft::roster::Roster;
+use jechsoft::swim_time;
+use std::{io, path::Path, path::PathBuf};
+use tabled::{builder::Builder, settings::Style};
+
+/// Print the athletes in `roster`, or update their personal bests from the results of a meet and
+/// save the roster when `update` holds a meet setup file and a results file.
+pub fn run(roster_path: &Path, update: Option<&[PathBuf]>, club: Option<&str>) -> io::Result<()> {
+    let mut roster = Roster::from_file(roster_path).map_err(io::Error::other)?;
+
+    let Some([meetsetup_path, results]) = update else {
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Name",
+            "Birth year",
+            "Gender",
+            "Club",
+            "Sport classes",
+            "Personal bests",
+        ]);
+        for athlete in &roster.athletes {
+            if club.is_some_and(|club| club != athlete.club) {
+                continue;
+            }
+            builder.push_record([
+                athlete.name.clone(),
+                athlete.birth_year.to_number().to_string(),
+                athlete.gender.to_string(),
+                athlete.club.clone(),
+                athlete
+                    .classification
+                    .map(|classes| classes.to_string())
+                    .unwrap_or_default(),
+                athlete.best_times.len().to_string(),
+            ]);
+        }
+        println!("{}", builder.build().with(Style::rounded()));
+        return Ok(());
+    };
+
+    let meet = select::load(meetsetup_path)?;
+    let results = MeetResult::from_file(results).map_err(io::Error::other)?;
+    let new_bests = roster.update(&meet, &results).map_err(io::Error::other)?;
+
+    let mut builder = Builder::default();
+    builder.push_record(["Name", "Event", "Pool", "Previous", "New", "Improvement"]);
+    for new_best in &new_bests {
+        let athlete = &roster.athletes[new_best.athlete];
+        builder.push_record([
+            athlete.name.clone(),
+            format!("{} {}", new_best.distance, new_best.style),
+            new_best.pool_length.to_string(),
+            new_best
+                .previous
+                .map_or_else(String::new, swim_time::format),
+            swim_time::format(new_best.time),
+            new_best.previous.map_or_else(String::new, |previous| {
+                swim_time::format(previous.saturating_sub(new_best.time))
+            }),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::rounded()));
+
+    roster.save(roster_path).map_err(io::Error::other)?;
+    log::info!(
+        "saved {} new personal bests to {}",
+        new_bests.len(),
+        roster_path.display()
+    );
+    Ok(())
+}
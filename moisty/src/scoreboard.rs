@@ -0,0 +1,122 @@
+use jechsoft::heat_list::{event_title, heat_title, Entries};
+use jechsoft::meet_setup::meet::Meet;
+use std::io::{self, IsTerminal, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fs::OpenOptions, thread};
+use timing::{Combiner, Console, LaneEvent, Scoreboard};
+
+/// Where the scoreboard is shown.
+pub enum Target {
+    Terminal,
+    Serial(PathBuf),
+    Tcp(String),
+}
+
+/// Show the heats of event `event` on a scoreboard while the lane events are read from `source`.
+/// The first start signal shows heat `heat`, every following start signal the next heat.
+pub fn run(
+    meet: &Meet,
+    entries: &Path,
+    source: &Path,
+    event: u32,
+    heat: usize,
+    target: Target,
+    realtime: bool,
+) -> io::Result<()> {
+    let entries = Entries::from_file(entries).map_err(io::Error::other)?;
+    let config = meet.heat_list_config();
+    let start_lists = entries
+        .start_lists(meet, &config)
+        .map_err(io::Error::other)?;
+    let start_list = start_lists
+        .iter()
+        .find(|start_list| start_list.event.id == event)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("event {event} has no entries"),
+            )
+        })?;
+    let board = |index: usize| {
+        start_list.heats.get(index).map(|heat| {
+            Scoreboard::new(
+                event_title(start_list.event, &config),
+                heat_title(heat, start_list.heats.len(), &config),
+                heat,
+                meet.touch_pads,
+            )
+        })
+    };
+
+    let mut index = heat.saturating_sub(1);
+    let mut scoreboard = board(index).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("event {event} has {} heats", start_list.heats.len()),
+        )
+    })?;
+    let terminal = matches!(target, Target::Terminal);
+    let mut output: Box<dyn Write> = match target {
+        Target::Terminal => Box::new(io::stdout().lock()),
+        Target::Serial(device) => Box::new(OpenOptions::new().write(true).open(device)?),
+        Target::Tcp(address) => Box::new(TcpStream::connect(address)?),
+    };
+    // Redirected terminal output only gets the final board.
+    let live = !terminal || io::stdout().is_terminal();
+
+    let mut combiner = Combiner::new(meet.touch_pads);
+    let mut started: Option<(Instant, Duration)> = None;
+    for lane_event in Console::open(source)? {
+        let lane_event = match lane_event {
+            Ok(lane_event) => lane_event,
+            Err(timing::protocol::Error::Io(why)) => return Err(why),
+            Err(why) => {
+                log::warn!("{why}");
+                continue;
+            }
+        };
+        if let LaneEvent::Start { time } = lane_event {
+            if started.is_some() {
+                for combined in combiner.flush() {
+                    scoreboard.push(&combined);
+                }
+                index += 1;
+                match board(index) {
+                    Some(next) => scoreboard = next,
+                    None => log::warn!("start signal after the last heat of event {event}"),
+                }
+            }
+            started = Some((Instant::now(), time));
+        }
+        if let (true, Some((instant, start))) = (realtime, started) {
+            let due = lane_event.time().saturating_sub(start);
+            thread::sleep(due.saturating_sub(instant.elapsed()));
+        }
+
+        for combined in combiner.push(lane_event) {
+            scoreboard.push(&combined);
+        }
+        if live {
+            show(&mut output, &scoreboard, terminal)?;
+        }
+    }
+    for combined in combiner.flush() {
+        scoreboard.push(&combined);
+    }
+    show(&mut output, &scoreboard, terminal)
+}
+
+/// Write the board as text for a terminal or in the scoreboard protocol.
+fn show(output: &mut dyn Write, scoreboard: &Scoreboard, terminal: bool) -> io::Result<()> {
+    if terminal {
+        if io::stdout().is_terminal() {
+            write!(output, "\x1b[2J\x1b[H")?;
+        }
+        output.write_all(scoreboard.to_text().as_bytes())?;
+    } else {
+        output.write_all(scoreboard.to_protocol().as_bytes())?;
+    }
+    output.flush()
+}
@@ -0,0 +1,15 @@
+use jechsoft::meet_setup::scrub::scrub;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Write a copy of a meet setup file without personal data, for example to add it to the parser
+/// corpus in `jechsoft/tests/corpus`. The file is not parsed so files the parser rejects can be
+/// shared too.
+pub fn run(meetsetup_path: &Path, output: Option<PathBuf>) -> io::Result<()> {
+    let scrubbed = scrub(&fs::read(meetsetup_path)?);
+    match output {
+        Some(output) => fs::write(output, scrubbed),
+        None => io::stdout().lock().write_all(&scrubbed),
+    }
+}
@@ -0,0 +1,142 @@
+use jechsoft::meet_setup::meet::Meet;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A parsed meet and the file it was read from.
+pub struct Loaded {
+    pub path: PathBuf,
+    pub meet: Meet,
+}
+
+/// Parse the meet setup file at `path`.
+///
+/// # Errors
+/// Returns an error of kind `io::ErrorKind::InvalidData` if the file cannot be parsed. See
+/// `exit_code` in `main.rs`.
+pub fn load(path: &Path) -> io::Result<Meet> {
+    Meet::try_from(path).map_err(|why| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {why}", path.display()),
+        )
+    })
+}
+
+/// Error reported after the output is written when some of the meets could not be parsed.
+pub fn unreadable_error(unreadable: usize, total: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{unreadable} of {total} meets could not be parsed"),
+    )
+}
+
+/// Files that cannot be parsed together with the reason.
+pub type Unreadable = Vec<(PathBuf, String)>;
+
+/// Parse every file in `meet_dir`, sorted by file name. A missing directory is an empty cache.
+pub fn load_all(meet_dir: &Path) -> io::Result<(Vec<Loaded>, Unreadable)> {
+    let mut paths = match fs::read_dir(meet_dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>(),
+        Err(why) if why.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(why) => return Err(why),
+    };
+    paths.sort();
+
+    let mut meets = Vec::new();
+    let mut failed = Vec::new();
+    for path in paths {
+        match Meet::try_from(&path) {
+            Ok(meet) => meets.push(Loaded { path, meet }),
+            Err(why) => failed.push((path, why.to_string())),
+        }
+    }
+    Ok((meets, failed))
+}
+
+/// True if `meet` is selected by `selector`. A selector is either an NSF meet id or a part of the
+/// meet name. Names match if every word of the selector is found in the name, ignoring case.
+pub fn matches(meet: &Meet, selector: &str) -> bool {
+    if let Ok(nsf_meet_id) = selector.trim().parse::<u32>() {
+        return meet.nsf_meet_id == Some(nsf_meet_id);
+    }
+    let name = meet.name.to_lowercase();
+    selector
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| name.contains(word))
+}
+
+/// Find the meet selected by `selector`. Paths to existing files are parsed directly, anything
+/// else is matched against the cached meets in `meet_dir` with `matches`. A meet with exactly the
+/// selector as name is preferred when more than one meet matches.
+///
+/// # Errors
+/// Returns an error if the meet cannot be parsed or if the selector matches no meet or more than
+/// one meet.
+pub fn select(selector: &str, meet_dir: &Path) -> io::Result<Loaded> {
+    let path = Path::new(selector);
+    if path.is_file() {
+        return Ok(Loaded {
+            path: path.to_path_buf(),
+            meet: load(path)?,
+        });
+    }
+
+    let (meets, _) = load_all(meet_dir)?;
+    let mut selected: Vec<Loaded> = meets
+        .into_iter()
+        .filter(|loaded| matches(&loaded.meet, selector))
+        .collect();
+    if selected.len() > 1 {
+        if let Some(index) = selected
+            .iter()
+            .position(|loaded| loaded.meet.name.eq_ignore_ascii_case(selector.trim()))
+        {
+            return Ok(selected.swap_remove(index));
+        }
+    }
+
+    match selected.len() {
+        0 => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no cached meet matches '{selector}'"),
+        )),
+        1 => Ok(selected.remove(0)),
+        count => {
+            let candidates = selected
+                .iter()
+                .map(|loaded| format!("{} ({})", describe(&loaded.meet), loaded.path.display()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(io::Error::other(format!(
+                "'{selector}' matches {count} meets: {candidates}"
+            )))
+        }
+    }
+}
+
+/// Default output file derived from the meet setup file at `path` by `change`. Meets selected from
+/// the cache in `meet_dir` write to the current directory instead of into the cache.
+pub fn output(path: &Path, meet_dir: &Path, change: impl FnOnce(&Path) -> PathBuf) -> PathBuf {
+    let output = change(path);
+    if !path.starts_with(meet_dir) {
+        return output;
+    }
+    output
+        .file_name()
+        .map_or_else(|| output.clone(), PathBuf::from)
+}
+
+/// NSF meet id and name of a meet.
+pub fn describe(meet: &Meet) -> String {
+    meet.nsf_meet_id.map_or_else(
+        || meet.name.clone(),
+        |nsf_meet_id| format!("[{nsf_meet_id:0>10}] {}", meet.name),
+    )
+}
@@ -0,0 +1,358 @@
+use crate::events::{event_records, session_records};
+use crate::output::{optional, optional_text, Records};
+use crate::select::{load_all, Loaded};
+use crate::show::{meet_row, MEET_FIELDS};
+use jechsoft::heat_list::{event_title, Entries};
+use jechsoft::meet_result::MeetResult;
+use jechsoft::swim_time;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use std::{fs, io, thread};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tungstenite::{handshake::derive_accept_key, protocol::Role, Message, WebSocket};
+
+const LIVE_PAGE: &str = include_str!("live.html");
+
+const HEAT_FIELDS: &[&str] = &[
+    "event",
+    "event_name",
+    "heat",
+    "lane",
+    "name",
+    "club",
+    "birth_year",
+    "entry_time",
+];
+
+const RESULT_FIELDS: &[&str] = &[
+    "event",
+    "event_name",
+    "class",
+    "place",
+    "name",
+    "club",
+    "birth_year",
+    "nation",
+    "time",
+    "status",
+    "dq_code",
+    "points",
+    "records",
+    "review",
+];
+
+/// Threads answering HTTP requests. WebSocket connections get a thread each.
+const WORKERS: usize = 4;
+
+/// How often live connections look for changed result files and ping the client.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where results and entries of the served meets are read from. Files are named after the meet
+/// key, see `key`, and are read again on every request so they can be updated during the meet.
+struct Files {
+    results: Option<PathBuf>,
+    entries: Option<PathBuf>,
+}
+
+/// Serve the cached meets in `meet_dir` as a JSON API and a live results page on `address`.
+///
+/// - `GET /`: live results page.
+/// - `GET /api/meets`: all meets.
+/// - `GET /api/meets/{key}`: one meet with its sessions.
+/// - `GET /api/meets/{key}/events` and `/sessions`.
+/// - `GET /api/meets/{key}/heats`: heat lists seeded from `{entries}/{key}.txt`.
+/// - `GET /api/meets/{key}/results`: results from `{results}/{key}.json`.
+/// - `GET /api/meets/{key}/live`: WebSocket that sends the results every time the file changes.
+///
+/// Meets are read once at startup.
+pub fn run(
+    meet_dir: &Path,
+    address: &str,
+    results: Option<PathBuf>,
+    entries: Option<PathBuf>,
+) -> io::Result<()> {
+    let (meets, failed) = load_all(meet_dir)?;
+    for (path, why) in &failed {
+        log::warn!("skipping {}: {why}", path.display());
+    }
+    let meets = Arc::new(meets);
+    let files = Arc::new(Files { results, entries });
+    let server = Arc::new(Server::http(address).map_err(io::Error::other)?);
+    log::info!("serving {} meets on http://{address}", meets.len());
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let (server, meets, files) = (server.clone(), meets.clone(), files.clone());
+            thread::spawn(move || loop {
+                match server.recv() {
+                    Ok(request) => handle(request, &meets, &files),
+                    Err(why) => log::error!("{why}"),
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| io::Error::other("server thread panicked"))?;
+    }
+    Ok(())
+}
+
+fn handle(request: Request, meets: &Arc<Vec<Loaded>>, files: &Arc<Files>) {
+    log::debug!("{} {}", request.method(), request.url());
+    if request.method() != &Method::Get {
+        return respond(request, 405, &json!({ "error": "only GET is supported" }));
+    }
+    let url = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let parts: Vec<&str> = url.split('/').filter(|part| !part.is_empty()).collect();
+
+    let (key, resource) = match parts.as_slice() {
+        [] => {
+            let header = header("Content-Type", "text/html; charset=utf-8");
+            let response = Response::from_string(LIVE_PAGE).with_header(header);
+            if let Err(why) = request.respond(response) {
+                log::warn!("{why}");
+            }
+            return;
+        }
+        ["api", "meets"] => {
+            let meets: Vec<Value> = meets.iter().map(meet_object).collect();
+            return respond(request, 200, &Value::Array(meets));
+        }
+        ["api", "meets", key] => (*key, None),
+        ["api", "meets", key, resource] => (*key, Some(*resource)),
+        _ => return respond(request, 404, &json!({ "error": "not found" })),
+    };
+    let Some(index) = meets.iter().position(|loaded| self::key(loaded) == key) else {
+        return respond(request, 404, &json!({ "error": format!("no meet {key}") }));
+    };
+    let loaded = &meets[index];
+
+    let body = match resource {
+        None => {
+            let mut meet = meet_object(loaded);
+            meet["sessions"] = Value::Array(session_records(&loaded.meet).objects());
+            Ok(meet)
+        }
+        Some("events") => Ok(Value::Array(event_records(&loaded.meet).objects())),
+        Some("sessions") => Ok(Value::Array(session_records(&loaded.meet).objects())),
+        Some("heats") => heats(loaded, files),
+        Some("results") => results(loaded, files),
+        Some("live") => return live(request, meets.clone(), index, files.clone()),
+        Some(_) => return respond(request, 404, &json!({ "error": "not found" })),
+    };
+    match body {
+        Ok(body) => respond(request, 200, &body),
+        Err(why) if why.kind() == io::ErrorKind::NotFound => {
+            respond(request, 404, &json!({ "error": why.to_string() }));
+        }
+        Err(why) => respond(request, 500, &json!({ "error": why.to_string() })),
+    }
+}
+
+/// Meet in URLs and file names: the NSF meet id, or the file name for meets without one.
+fn key(loaded: &Loaded) -> String {
+    loaded.meet.nsf_meet_id.map_or_else(
+        || {
+            loaded
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        },
+        |id| id.to_string(),
+    )
+}
+
+fn meet_object(loaded: &Loaded) -> Value {
+    let mut records = Records::new(MEET_FIELDS);
+    records.push(meet_row(loaded));
+    let mut meet = records.objects().remove(0);
+    // Spectators have no use for paths on the server.
+    if let Some(meet) = meet.as_object_mut() {
+        meet.shift_remove("path");
+    }
+    meet["key"] = key(loaded).into();
+    meet
+}
+
+/// Heat lists of the meet seeded from its entries file.
+fn heats(loaded: &Loaded, files: &Files) -> io::Result<Value> {
+    let path = file(files.entries.as_deref(), loaded, "txt", "entries")?;
+    let entries = Entries::from_file(&path).map_err(io::Error::other)?;
+    let config = loaded.meet.heat_list_config();
+    let start_lists = entries
+        .start_lists(&loaded.meet, &config)
+        .map_err(io::Error::other)?;
+
+    let mut records = Records::new(HEAT_FIELDS);
+    for start_list in &start_lists {
+        for heat in &start_list.heats {
+            for lane in &heat.lanes {
+                records.push(vec![
+                    start_list.event.id.into(),
+                    event_title(start_list.event, &config).into(),
+                    heat.number.into(),
+                    lane.lane.into(),
+                    lane.entry.name.clone().into(),
+                    lane.entry.club.clone().into(),
+                    optional_text(lane.entry.birth_year),
+                    optional(lane.entry.entry_time.map(swim_time::format)),
+                ]);
+            }
+        }
+    }
+    Ok(Value::Array(records.objects()))
+}
+
+/// Results of the meet in result list order.
+fn results(loaded: &Loaded, files: &Files) -> io::Result<Value> {
+    let path = result_file(loaded, files)?;
+    let results = MeetResult::from_file(&path).map_err(io::Error::other)?;
+    let config = loaded.meet.heat_list_config();
+
+    let mut records = Records::new(RESULT_FIELDS);
+    for (event, event_result) in results
+        .with_events(&loaded.meet)
+        .map_err(io::Error::other)?
+    {
+        for class_result in &event_result.classes {
+            for placed in class_result.placed() {
+                let result = placed.result;
+                records.push(vec![
+                    event.id.into(),
+                    event_title(event, &config).into(),
+                    class_result.class.to_string().into(),
+                    optional(placed.place),
+                    result.name.clone().into(),
+                    result.club.clone().into(),
+                    optional_text(result.birth_year),
+                    optional(result.nation.clone()),
+                    optional(result.time.map(swim_time::format)),
+                    result.status.to_string().into(),
+                    optional(result.dq_code.clone()),
+                    optional(result.points),
+                    result
+                        .records
+                        .iter()
+                        .map(|record| Value::from(record.to_string()))
+                        .collect(),
+                    result.review.into(),
+                ]);
+            }
+        }
+    }
+    Ok(Value::Array(records.objects()))
+}
+
+fn result_file(loaded: &Loaded, files: &Files) -> io::Result<PathBuf> {
+    file(files.results.as_deref(), loaded, "json", "results")
+}
+
+/// File of the meet in `dir`.
+fn file(dir: Option<&Path>, loaded: &Loaded, extension: &str, what: &str) -> io::Result<PathBuf> {
+    let not_found = || {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no {what} for meet {}", key(loaded)),
+        )
+    };
+    let path = dir
+        .ok_or_else(not_found)?
+        .join(key(loaded))
+        .with_extension(extension);
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(not_found())
+    }
+}
+
+/// Upgrade `request` to a WebSocket and send the results every time the result file changes.
+fn live(request: Request, meets: Arc<Vec<Loaded>>, index: usize, files: Arc<Files>) {
+    let Some(key) = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| header.value.to_string())
+    else {
+        return respond(
+            request,
+            400,
+            &json!({ "error": "expected a WebSocket request" }),
+        );
+    };
+    let response = Response::empty(101).with_header(header(
+        "Sec-WebSocket-Accept",
+        &derive_accept_key(key.as_bytes()),
+    ));
+    let stream = request.upgrade("websocket", response);
+
+    thread::spawn(move || {
+        let loaded = &meets[index];
+        let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+        let mut modified: Option<SystemTime> = None;
+        loop {
+            let changed = result_file(loaded, &files)
+                .and_then(fs::metadata)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            let mut messages = vec![Message::Ping(Vec::new())];
+            if changed.is_some() && changed != modified {
+                modified = changed;
+                let results = match results(loaded, &files) {
+                    Ok(results) => results,
+                    Err(why) => json!({ "error": why.to_string() }),
+                };
+                messages.insert(0, Message::Text(results.to_string()));
+            }
+            for message in messages {
+                if let Err(why) = socket.send(message) {
+                    log::debug!("closing live connection: {why}");
+                    return;
+                }
+            }
+            // `Request::upgrade` hides the TCP stream, so reads cannot time out. The ping makes
+            // the client answer, and everything it sent until the pong is read. Reading also
+            // answers its pings and its close frame.
+            loop {
+                match socket.read() {
+                    Ok(Message::Pong(_)) => break,
+                    Ok(Message::Close(_)) => {
+                        // Send the close frame tungstenite queued as the reply.
+                        let _ = socket.flush();
+                        log::debug!("live connection closed by the client");
+                        return;
+                    }
+                    Ok(_) => {}
+                    Err(why) => {
+                        log::debug!("closing live connection: {why}");
+                        return;
+                    }
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+fn respond(request: Request, status: u16, body: &Value) {
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"));
+    if let Err(why) = request.respond(response) {
+        log::warn!("{why}");
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("valid header")
+}
@@ -0,0 +1,76 @@
+use crate::cli::OutputFormat;
+use crate::events::session_records;
+use crate::output::{optional, optional_text, Records};
+use crate::select::{select, Loaded};
+use serde_json::Value;
+use std::{io, path::Path};
+
+pub const MEET_FIELDS: &[&str] = &[
+    "nsf_meet_id",
+    "name",
+    "location",
+    "date_start",
+    "date_end",
+    "first_entry_date",
+    "last_entry_date",
+    "final_entry_date",
+    "host_club",
+    "competition_type",
+    "pool_length",
+    "lanes",
+    "sessions",
+    "events",
+    "cancelled",
+    "path",
+];
+
+pub fn meet_row(loaded: &Loaded) -> Vec<Value> {
+    let meet = &loaded.meet;
+    vec![
+        optional(meet.nsf_meet_id),
+        meet.name.trim().into(),
+        meet.location.trim().into(),
+        optional_text(meet.date_start),
+        optional_text(meet.date_end),
+        meet.first_entry_date.to_string().into(),
+        meet.last_entry_date.to_string().into(),
+        meet.final_entry_date.to_string().into(),
+        optional(meet.host_club.clone()),
+        meet.competition_type_id.to_string().into(),
+        meet.pool_length.to_string().into(),
+        meet.lanes.into(),
+        meet.sessions.len().into(),
+        meet.events.len().into(),
+        meet.cancelled.into(),
+        loaded.path.display().to_string().into(),
+    ]
+}
+
+/// Print the details and sessions of the selected meet. CSV output holds the meet details only and
+/// NDJSON output is the JSON object on a single line.
+pub fn run(selector: &str, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let loaded = select(selector, meet_dir)?;
+    let mut records = Records::new(MEET_FIELDS);
+    records.push(meet_row(&loaded));
+    let sessions = session_records(&loaded.meet);
+
+    match format {
+        OutputFormat::Table => {
+            println!("{}", records.to_vertical_table());
+            if !sessions.is_empty() {
+                println!("{}", sessions.to_table());
+            }
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let mut meet = records.objects().remove(0);
+            meet["sessions"] = Value::Array(sessions.objects());
+            if format == OutputFormat::Json {
+                println!("{meet:#}");
+            } else {
+                println!("{meet}");
+            }
+        }
+        OutputFormat::Csv => print!("{}", records.to_csv()),
+    }
+    Ok(())
+}
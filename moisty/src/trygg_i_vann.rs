@@ -0,0 +1,55 @@
+use crate::cli::TryggIVannFormat;
+use jechsoft::trygg_i_vann::Export;
+use std::{fs, io, path::Path, path::PathBuf};
+use tabled::{builder::Builder, settings::Style};
+
+/// Print a summary per group of a `tryggivann.csv` export or convert it to JSON.
+pub fn run(
+    export: &Path,
+    format: TryggIVannFormat,
+    group: Option<&str>,
+    output: Option<PathBuf>,
+) -> io::Result<()> {
+    let mut export = Export::from_file(export).map_err(io::Error::other)?;
+    for invalid in &export.invalid {
+        log::warn!("{invalid}");
+    }
+    if let Some(group) = group {
+        export
+            .certificates
+            .retain(|certificate| certificate.group.as_deref() == Some(group));
+    }
+
+    let content = match format {
+        TryggIVannFormat::Json => export.to_json().map_err(io::Error::other)?,
+        TryggIVannFormat::Summary => {
+            let mut text = String::new();
+            for summary in export.summary() {
+                let mut builder = Builder::default();
+                builder.push_record(["Test item", "Passed"]);
+                for (item, passed) in &summary.items {
+                    builder.push_record([
+                        item.clone(),
+                        format!("{passed} of {}", summary.certificates),
+                    ]);
+                }
+                text.push_str(&format!(
+                    "{}: {} of {} passed\n{}\n",
+                    summary.group.as_deref().unwrap_or("no group"),
+                    summary.passed,
+                    summary.certificates,
+                    builder.build().with(Style::rounded())
+                ));
+            }
+            text
+        }
+    };
+
+    match output {
+        Some(output) => fs::write(output, content),
+        None => {
+            print!("{content}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,644 @@
+use crate::events::{event_records, session_records};
+use crate::output::{optional, optional_text, text, Records};
+use crate::select::{describe, load_all, matches, Loaded, Unreadable};
+use crate::show::{meet_row, MEET_FIELDS};
+use chrono::Local;
+use jechsoft::meet_setup::validation::Issue;
+use jechsoft::meet_setup::{distance::Distance, meet::Meet, person::Person};
+use jechsoft::swim_time;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+use std::{io, path::Path};
+
+const MEET_LIST_FIELDS: &[&str] = &[
+    "nsf_meet_id",
+    "date_start",
+    "date_end",
+    "name",
+    "location",
+    "host_club",
+    "events",
+    "issues",
+];
+
+const PROBLEM_FIELDS: &[&str] = &["meet", "path", "status", "issue"];
+
+const DETAIL_FIELDS: &[&str] = &["field", "value"];
+
+const QUALIFICATION_FIELDS: &[&str] = &[
+    "class",
+    "gender",
+    "pool_length",
+    "distance",
+    "style",
+    "time",
+];
+
+/// Widest column in tables. Longer values are cut off.
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Rows moved by page up and page down.
+const PAGE: usize = 20;
+
+/// Browse the cached meets in `meet_dir` in a terminal user interface.
+pub fn run(meet_dir: &Path) -> io::Result<()> {
+    let (meets, failed) = load_all(meet_dir)?;
+    let mut app = App::new(meets, failed);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// Meet filters. Empty filters match every meet.
+#[derive(Default)]
+struct Filter {
+    /// Words in the meet name or the NSF meet id, see `select::matches`.
+    search: String,
+    host: String,
+    /// Meets with an event of this distance, like '100' or '4*50'.
+    distance: String,
+    /// Meets starting or ending in this period, like '2024', '2024-06' or '2024-06-15'.
+    date: String,
+    /// Meets that have not ended yet.
+    upcoming: bool,
+}
+
+/// Filter edited in the footer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Search,
+    Host,
+    Distance,
+    Date,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Overview,
+    Sessions,
+    Events,
+    Qualification,
+    Prices,
+    Contacts,
+    Issues,
+}
+
+const TABS: [Tab; 7] = [
+    Tab::Overview,
+    Tab::Sessions,
+    Tab::Events,
+    Tab::Qualification,
+    Tab::Prices,
+    Tab::Contacts,
+    Tab::Issues,
+];
+
+enum Screen {
+    Meets,
+    /// Details of the meet at this index in `App::meets`.
+    Meet(usize, Tab),
+    /// Files that cannot be parsed and meets with issues.
+    Problems,
+}
+
+struct App {
+    meets: Vec<Loaded>,
+    /// Issues of every meet, in the order of `meets`.
+    issues: Vec<Vec<Issue>>,
+    failed: Unreadable,
+    filter: Filter,
+    /// Indexes into `meets` that pass the filter.
+    visible: Vec<usize>,
+    input: Option<Input>,
+    screen: Screen,
+    meet_table: TableState,
+    detail_table: TableState,
+    problem_table: TableState,
+    quit: bool,
+}
+
+impl App {
+    fn new(meets: Vec<Loaded>, failed: Unreadable) -> Self {
+        let issues = meets.iter().map(|loaded| loaded.meet.validate()).collect();
+        let mut app = Self {
+            meets,
+            issues,
+            failed,
+            filter: Filter::default(),
+            visible: Vec::new(),
+            input: None,
+            screen: Screen::Meets,
+            meet_table: TableState::default(),
+            detail_table: TableState::default(),
+            problem_table: TableState::default(),
+            quit: false,
+        };
+        app.apply_filter();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_filter(&mut self) {
+        let today = Local::now().date_naive();
+        let filter = &self.filter;
+        let distance = Distance::try_from(filter.distance.as_str()).ok();
+        self.visible = self
+            .meets
+            .iter()
+            .enumerate()
+            .filter(|(_, loaded)| {
+                let meet = &loaded.meet;
+                let host = meet.host_club.as_deref().unwrap_or_default();
+                let dates = [meet.date_start, meet.date_end];
+                (filter.search.is_empty() || matches(meet, &filter.search))
+                    && host.to_lowercase().contains(&filter.host.to_lowercase())
+                    && (filter.distance.is_empty()
+                        || meet.events.iter().any(|event| {
+                            Some(event.distance) == distance
+                                || event.distance.to_string().starts_with(&filter.distance)
+                        }))
+                    && (filter.date.is_empty()
+                        || dates
+                            .iter()
+                            .flatten()
+                            .any(|date| date.to_string().starts_with(&filter.date)))
+                    && (!filter.upcoming
+                        || meet
+                            .date_end
+                            .or(meet.date_start)
+                            .is_none_or(|end| end >= today))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.meet_table
+            .select((!self.visible.is_empty()).then_some(0));
+    }
+
+    fn handle(&mut self, key: KeyEvent) {
+        if let Some(input) = self.input {
+            let value = self.filter_value(input);
+            match key.code {
+                KeyCode::Char(character) => value.push(character),
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                KeyCode::Esc => value.clear(),
+                KeyCode::Enter => {}
+                _ => return,
+            }
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                self.input = None;
+            }
+            return self.apply_filter();
+        }
+
+        match self.screen {
+            Screen::Meets => self.handle_meets(key.code),
+            Screen::Meet(index, tab) => self.handle_meet(key.code, index, tab),
+            Screen::Problems => self.handle_problems(key.code),
+        }
+    }
+
+    fn filter_value(&mut self, input: Input) -> &mut String {
+        match input {
+            Input::Search => &mut self.filter.search,
+            Input::Host => &mut self.filter.host,
+            Input::Distance => &mut self.filter.distance,
+            Input::Date => &mut self.filter.date,
+        }
+    }
+
+    fn handle_meets(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('/') => self.input = Some(Input::Search),
+            KeyCode::Char('h') => self.input = Some(Input::Host),
+            KeyCode::Char('d') => self.input = Some(Input::Distance),
+            KeyCode::Char('t') => self.input = Some(Input::Date),
+            KeyCode::Char('u') => {
+                self.filter.upcoming = !self.filter.upcoming;
+                self.apply_filter();
+            }
+            KeyCode::Char('c') => {
+                self.filter = Filter::default();
+                self.apply_filter();
+            }
+            KeyCode::Char('v') => {
+                self.problem_table.select(Some(0));
+                self.screen = Screen::Problems;
+            }
+            KeyCode::Enter => {
+                if let Some(&index) = self
+                    .meet_table
+                    .selected()
+                    .and_then(|selected| self.visible.get(selected))
+                {
+                    self.open(index);
+                }
+            }
+            code => scroll(&mut self.meet_table, code, self.visible.len()),
+        }
+    }
+
+    fn handle_meet(&mut self, code: KeyCode, index: usize, tab: Tab) {
+        let position = TABS
+            .iter()
+            .position(|other| *other == tab)
+            .unwrap_or_default();
+        let tab = match code {
+            KeyCode::Char('q') => {
+                self.quit = true;
+                return;
+            }
+            KeyCode::Esc | KeyCode::Backspace => {
+                self.screen = Screen::Meets;
+                return;
+            }
+            KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => TABS[(position + 1) % TABS.len()],
+            KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+                TABS[(position + TABS.len() - 1) % TABS.len()]
+            }
+            code => {
+                let rows = self.details(index, tab).rows().len();
+                return scroll(&mut self.detail_table, code, rows);
+            }
+        };
+        self.detail_table.select(Some(0));
+        self.screen = Screen::Meet(index, tab);
+    }
+
+    fn handle_problems(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc | KeyCode::Backspace => self.screen = Screen::Meets,
+            KeyCode::Enter => {
+                if let Some(Some(index)) = self
+                    .problem_table
+                    .selected()
+                    .and_then(|selected| self.problems().1.get(selected).copied())
+                {
+                    self.open(index);
+                }
+            }
+            code => {
+                let rows = self.problems().0.rows().len();
+                scroll(&mut self.problem_table, code, rows);
+            }
+        }
+    }
+
+    fn open(&mut self, index: usize) {
+        self.detail_table.select(Some(0));
+        self.screen = Screen::Meet(index, Tab::Overview);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        match self.screen {
+            Screen::Meets => {
+                let heading = format!(
+                    "moisty: {} of {} meets",
+                    self.visible.len(),
+                    self.meets.len()
+                );
+                frame.render_widget(Paragraph::new(heading).bold(), title);
+                let records = self.meet_list();
+                render_table(frame, body, &records, None, &mut self.meet_table);
+                let help = self.input.map_or_else(
+                    || {
+                        format!(
+                            "{}  enter open  / search  h host  d distance  t date  u upcoming  c clear  v problems ({})  q quit",
+                            self.filter_summary(),
+                            self.failed.len()
+                                + self.issues.iter().filter(|issues| !issues.is_empty()).count()
+                        )
+                    },
+                    |input| {
+                        let name = match input {
+                            Input::Search => "search",
+                            Input::Host => "host",
+                            Input::Distance => "distance",
+                            Input::Date => "date (YYYY, YYYY-MM or YYYY-MM-DD)",
+                        };
+                        let value = match input {
+                            Input::Search => &self.filter.search,
+                            Input::Host => &self.filter.host,
+                            Input::Distance => &self.filter.distance,
+                            Input::Date => &self.filter.date,
+                        };
+                        format!("{name}: {value}_  enter done  esc clear")
+                    },
+                );
+                frame.render_widget(Paragraph::new(help).reversed(), footer);
+            }
+            Screen::Meet(index, tab) => {
+                let [tabs, body] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(body);
+                frame.render_widget(
+                    Paragraph::new(describe(&self.meets[index].meet)).bold(),
+                    title,
+                );
+                let titles = TABS.iter().map(|tab| tab_title(*tab));
+                let selected = TABS.iter().position(|other| *other == tab);
+                frame.render_widget(Tabs::new(titles).select(selected), tabs);
+                let records = self.details(index, tab);
+                render_table(frame, body, &records, None, &mut self.detail_table);
+                let help = "←/→ tabs  ↑/↓ scroll  esc back  q quit";
+                frame.render_widget(Paragraph::new(help).reversed(), footer);
+            }
+            Screen::Problems => {
+                let (records, _) = self.problems();
+                let heading = format!(
+                    "Problems: {} files cannot be parsed, {} meets with issues",
+                    self.failed.len(),
+                    self.issues
+                        .iter()
+                        .filter(|issues| !issues.is_empty())
+                        .count()
+                );
+                frame.render_widget(Paragraph::new(heading).bold(), title);
+                render_table(frame, body, &records, Some(3), &mut self.problem_table);
+                let help = "enter open meet  ↑/↓ scroll  esc back  q quit";
+                frame.render_widget(Paragraph::new(help).reversed(), footer);
+            }
+        }
+    }
+
+    fn filter_summary(&self) -> String {
+        let filter = &self.filter;
+        let mut summary: Vec<String> = [
+            ("search", &filter.search),
+            ("host", &filter.host),
+            ("distance", &filter.distance),
+            ("date", &filter.date),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect();
+        if filter.upcoming {
+            summary.push(String::from("upcoming"));
+        }
+        if summary.is_empty() {
+            String::from("no filter")
+        } else {
+            format!("[{}]", summary.join(", "))
+        }
+    }
+
+    fn meet_list(&self) -> Records {
+        let mut records = Records::new(MEET_LIST_FIELDS);
+        for &index in &self.visible {
+            let meet = &self.meets[index].meet;
+            records.push(vec![
+                optional(meet.nsf_meet_id),
+                optional_text(meet.date_start),
+                optional_text(meet.date_end),
+                meet.name.trim().into(),
+                meet.location.trim().into(),
+                optional(meet.host_club.clone()),
+                meet.events.len().into(),
+                self.issues[index].len().into(),
+            ]);
+        }
+        records
+    }
+
+    /// Files that cannot be parsed and issues of the meets, with the meet index of every row.
+    fn problems(&self) -> (Records, Vec<Option<usize>>) {
+        let mut records = Records::new(PROBLEM_FIELDS);
+        let mut indexes = Vec::new();
+        for (path, why) in &self.failed {
+            records.push(vec![
+                "".into(),
+                path.display().to_string().into(),
+                "unreadable".into(),
+                why.clone().into(),
+            ]);
+            indexes.push(None);
+        }
+        for (index, issues) in self.issues.iter().enumerate() {
+            let loaded = &self.meets[index];
+            for issue in issues {
+                records.push(vec![
+                    describe(&loaded.meet).into(),
+                    loaded.path.display().to_string().into(),
+                    "invalid".into(),
+                    issue.to_string().into(),
+                ]);
+                indexes.push(Some(index));
+            }
+        }
+        (records, indexes)
+    }
+
+    fn details(&self, index: usize, tab: Tab) -> Records {
+        let loaded = &self.meets[index];
+        let meet = &loaded.meet;
+        match tab {
+            Tab::Overview => {
+                let mut records = Records::new(DETAIL_FIELDS);
+                for (field, value) in MEET_FIELDS.iter().zip(meet_row(loaded)) {
+                    records.push(vec![(*field).into(), text(&value).into()]);
+                }
+                if let Some(info) = meet.info.as_ref().filter(|info| !info.trim().is_empty()) {
+                    records.push(vec!["info".into(), info.trim().into()]);
+                }
+                records
+            }
+            Tab::Sessions => session_records(meet),
+            Tab::Events => event_records(meet),
+            Tab::Qualification => qualifications(meet),
+            Tab::Prices => prices(meet),
+            Tab::Contacts => contacts(meet),
+            Tab::Issues => {
+                let mut records = Records::new(DETAIL_FIELDS);
+                for issue in &self.issues[index] {
+                    records.push(vec!["issue".into(), issue.to_string().into()]);
+                }
+                records
+            }
+        }
+    }
+}
+
+const fn tab_title(tab: Tab) -> &'static str {
+    match tab {
+        Tab::Overview => "Overview",
+        Tab::Sessions => "Sessions",
+        Tab::Events => "Events",
+        Tab::Qualification => "Qualification",
+        Tab::Prices => "Prices",
+        Tab::Contacts => "Contacts",
+        Tab::Issues => "Issues",
+    }
+}
+
+/// Qualification times of the meet's qualification set.
+fn qualifications(meet: &Meet) -> Records {
+    let mut records = Records::new(QUALIFICATION_FIELDS);
+    let qualifications = meet
+        .qualification_set
+        .iter()
+        .flat_map(|set| &set.qualifications);
+    for qualification in qualifications {
+        records.push(vec![
+            qualification.athlete_class.to_string().into(),
+            qualification.athlete_gender.to_string().into(),
+            qualification.pool_length.to_string().into(),
+            qualification.distance.to_string().into(),
+            qualification.style.to_string().into(),
+            swim_time::format(qualification.time).into(),
+        ]);
+    }
+    records
+}
+
+/// Entry prices in NOK and other payments.
+fn prices(meet: &Meet) -> Records {
+    let mut records = Records::new(DETAIL_FIELDS);
+    let prices = [
+        ("individual", meet.individual_price),
+        ("team", meet.team_price),
+        ("individual 2", meet.individual_price2),
+        ("team 2", meet.team_price2),
+        ("one price for all entries", meet.one_price_all),
+    ];
+    for (name, price) in prices {
+        records.push(vec![name.into(), price.into()]);
+    }
+    let others = [
+        (&meet.other_payment1, meet.other_price1),
+        (&meet.other_payment2, meet.other_price2),
+        (&meet.other_payment3, meet.other_price3),
+        (&meet.other_payment4, meet.other_price4),
+        (&meet.other_payment5, meet.other_price5),
+        (&meet.other_payment6, meet.other_price6),
+        (&meet.other_payment7, meet.other_price7),
+        (&meet.other_payment8, meet.other_price8),
+    ];
+    for (name, price) in others {
+        if let Some(name) = name.as_ref().filter(|name| !name.trim().is_empty()) {
+            records.push(vec![name.trim().into(), optional(price)]);
+        }
+    }
+    if let Some(years) = &meet.birth_years_pay_once {
+        let years: Vec<String> = years.iter().map(ToString::to_string).collect();
+        records.push(vec!["pay once birth years".into(), years.join(", ").into()]);
+    }
+    records.push(vec!["pay account".into(), meet.pay_account.trim().into()]);
+    records
+}
+
+/// People and addresses to contact about the meet.
+fn contacts(meet: &Meet) -> Records {
+    let person = |person: &Option<Person>| {
+        person.as_ref().map_or(Value::Null, |person| {
+            format!("{} {}, {}", person.name, person.surname, person.club).into()
+        })
+    };
+    let mut records = Records::new(DETAIL_FIELDS);
+    records.push(vec!["host club".into(), optional(meet.host_club.clone())]);
+    records.push(vec!["entry manager".into(), person(&meet.entry_manager)]);
+    records.push(vec![
+        "competition manager".into(),
+        person(&meet.competition_manager),
+    ]);
+    records.push(vec![
+        "entry email".into(),
+        optional_text(meet.entry_email.as_ref()),
+    ]);
+    records.push(vec![
+        "home page".into(),
+        optional_text(meet.home_page.as_ref()),
+    ]);
+    records.push(vec![
+        "results".into(),
+        optional_text(meet.result_web_address.as_ref()),
+    ]);
+    records
+}
+
+/// Move the selection of a table with `rows` rows.
+fn scroll(table: &mut TableState, code: KeyCode, rows: usize) {
+    let last = rows.saturating_sub(1);
+    let selected = table.selected().unwrap_or_default();
+    let selected = match code {
+        KeyCode::Down | KeyCode::Char('j') => selected.saturating_add(1).min(last),
+        KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+        KeyCode::PageDown => selected.saturating_add(PAGE).min(last),
+        KeyCode::PageUp => selected.saturating_sub(PAGE),
+        KeyCode::Home | KeyCode::Char('g') => 0,
+        KeyCode::End | KeyCode::Char('G') => last,
+        _ => return,
+    };
+    table.select((rows > 0).then_some(selected));
+}
+
+/// Render records as a table. Columns are as wide as their widest value up to
+/// `MAX_COLUMN_WIDTH`, except column `fill` that takes the remaining width.
+fn render_table(
+    frame: &mut Frame,
+    area: Rect,
+    records: &Records,
+    fill: Option<usize>,
+    state: &mut TableState,
+) {
+    let rows: Vec<Vec<String>> = records
+        .rows()
+        .iter()
+        .map(|row| row.iter().map(text).collect())
+        .collect();
+    let widths = records
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(column, field)| {
+            if Some(column) == fill {
+                return Constraint::Fill(1);
+            }
+            let width = rows
+                .iter()
+                .map(|row| row[column].chars().count())
+                .chain([field.len()])
+                .max()
+                .unwrap_or_default()
+                .min(MAX_COLUMN_WIDTH);
+            Constraint::Length(u16::try_from(width).unwrap_or(u16::MAX))
+        })
+        .collect::<Vec<_>>();
+    let header = Row::new(
+        records
+            .fields()
+            .iter()
+            .map(|field| Cell::from(field.replace('_', " "))),
+    )
+    .bold();
+    let table = Table::new(rows.into_iter().map(Row::new), widths)
+        .header(header)
+        .block(Block::default().borders(Borders::TOP))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, state);
+}
@@ -0,0 +1,72 @@
+use crate::cli::OutputFormat;
+use crate::output::Records;
+use crate::select::{describe, load_all, select, Loaded};
+use std::{io, path::Path, path::PathBuf};
+
+const FIELDS: &[&str] = &["meet", "path", "status", "issue"];
+
+/// Validate the selected meet, or every cached meet if no meet is selected. Prints one row per
+/// issue, one row with status "ok" for meets without issues and one row with status "unreadable"
+/// for files that cannot be parsed. Issues and files that cannot be parsed are reported as an
+/// error after the rows are printed.
+pub fn run(selector: Option<&str>, meet_dir: &Path, format: OutputFormat) -> io::Result<()> {
+    let (meets, failed) = match selector {
+        Some(selector) => match select(selector, meet_dir) {
+            Ok(loaded) => (vec![loaded], Vec::new()),
+            Err(why) if why.kind() == io::ErrorKind::InvalidData => {
+                (Vec::new(), vec![(PathBuf::from(selector), why.to_string())])
+            }
+            Err(why) => return Err(why),
+        },
+        None => load_all(meet_dir)?,
+    };
+
+    let mut records = Records::new(FIELDS);
+    let mut invalid = 0;
+    for Loaded { path, meet } in &meets {
+        let issues = meet.validate();
+        let path = path.display().to_string();
+        if issues.is_empty() {
+            records.push(vec![
+                describe(meet).into(),
+                path.into(),
+                "ok".into(),
+                "".into(),
+            ]);
+            continue;
+        }
+        invalid += 1;
+        for issue in issues {
+            records.push(vec![
+                describe(meet).into(),
+                path.clone().into(),
+                "invalid".into(),
+                issue.to_string().into(),
+            ]);
+        }
+    }
+    for (path, why) in &failed {
+        records.push(vec![
+            "".into(),
+            path.display().to_string().into(),
+            "unreadable".into(),
+            why.clone().into(),
+        ]);
+    }
+
+    records.print(format)?;
+
+    let total = meets.len() + failed.len();
+    if invalid == 0 && failed.is_empty() {
+        log::info!("validated {total} meets without issues");
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "validated {total} meets: {invalid} with issues, {} could not be parsed",
+                failed.len()
+            ),
+        ))
+    }
+}
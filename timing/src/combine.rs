@@ -0,0 +1,271 @@
+use crate::event::{LaneEvent, Pad};
+use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+use std::time::Duration;
+
+/// Longest time between the touches of the two pad sets that are counted as the same touch in
+/// `TouchPadSet::TwoSet` pools.
+pub const PAD_WINDOW: Duration = Duration::from_millis(300);
+
+/// Touch at a turn or at the finish with the times of the pads that registered it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Touch {
+    pub lane: u8,
+    /// Lengths swum at a split. `None` at the finish.
+    pub lap: Option<u8>,
+    /// Times of pad A and pad B. A pad that did not register the touch is `None`.
+    pub pads: [Option<Duration>; 2],
+}
+
+impl Touch {
+    /// Earliest pad time. A swimmer can hit either pad first, so the first registered touch
+    /// counts.
+    #[must_use]
+    pub fn time(&self) -> Option<Duration> {
+        self.pads.iter().flatten().min().copied()
+    }
+
+    #[must_use]
+    pub const fn is_finish(&self) -> bool {
+        self.lap.is_none()
+    }
+
+    /// True if the pool has two pad sets and only one of them registered the touch.
+    #[must_use]
+    pub const fn is_partial(&self, pad_set: TouchPadSet) -> bool {
+        matches!(pad_set, TouchPadSet::TwoSet) && (self.pads[0].is_none() || self.pads[1].is_none())
+    }
+}
+
+/// Lane event after the pad sets have been combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combined {
+    Touch(Touch),
+    /// Start signals, backup buttons and false starts are passed on unchanged.
+    Event(LaneEvent),
+}
+
+/// Combines the touches of one or two pad sets according to `Meet::touch_pads`.
+///
+/// - `TouchPadSet::OneSet`: every touch of pad A is passed on. Touches of pad B are ignored.
+/// - `TouchPadSet::TwoSet`: touches of pad A and pad B in the same lane and lap within
+///   `PAD_WINDOW` are merged. A touch of only one pad is passed on once the window has passed.
+/// - `TouchPadSet::None`: all touches are ignored and only backup buttons are passed on.
+#[derive(Debug, Clone)]
+pub struct Combiner {
+    pad_set: TouchPadSet,
+    pending: Vec<Touch>,
+}
+
+impl Combiner {
+    #[must_use]
+    pub const fn new(pad_set: TouchPadSet) -> Self {
+        Self {
+            pad_set,
+            pending: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn pad_set(&self) -> TouchPadSet {
+        self.pad_set
+    }
+
+    /// Add an event from the console. Returns the events that are complete. Events are expected
+    /// in the order the console sends them.
+    pub fn push(&mut self, event: LaneEvent) -> Vec<Combined> {
+        let mut combined = match event {
+            LaneEvent::Start { .. } => self.flush(),
+            _ => self.expire(event.time()),
+        };
+
+        let (lane, pad, lap, time) = match event {
+            LaneEvent::Split {
+                lane,
+                pad,
+                lap,
+                time,
+            } => (lane, pad, Some(lap), time),
+            LaneEvent::Finish { lane, pad, time } => (lane, pad, None, time),
+            event => {
+                combined.push(Combined::Event(event));
+                return combined;
+            }
+        };
+
+        match (self.pad_set, pad) {
+            (TouchPadSet::None, _) => {
+                log::warn!("ignoring {event} because the pool has no touch pads");
+            }
+            (TouchPadSet::OneSet, Pad::B) => {
+                log::warn!("ignoring {event} because the pool has one pad set");
+            }
+            (TouchPadSet::OneSet, Pad::A) => combined.push(Combined::Touch(Touch {
+                lane,
+                lap,
+                pads: [Some(time), None],
+            })),
+            (TouchPadSet::TwoSet, pad) => {
+                let slot = match pad {
+                    Pad::A => 0,
+                    Pad::B => 1,
+                };
+                if let Some(index) = self.pending.iter().position(|touch| {
+                    touch.lane == lane && touch.lap == lap && touch.pads[slot].is_none()
+                }) {
+                    let mut touch = self.pending.remove(index);
+                    touch.pads[slot] = Some(time);
+                    combined.push(Combined::Touch(touch));
+                } else {
+                    let mut pads = [None, None];
+                    pads[slot] = Some(time);
+                    self.pending.push(Touch { lane, lap, pads });
+                }
+            }
+        }
+        combined
+    }
+
+    /// Pass on all touches that are still waiting for the other pad set. Call this at the end of
+    /// a heat.
+    pub fn flush(&mut self) -> Vec<Combined> {
+        self.pending.drain(..).map(Combined::Touch).collect()
+    }
+
+    /// Pass on touches that are waiting for the other pad set for longer than `PAD_WINDOW`.
+    fn expire(&mut self, now: Duration) -> Vec<Combined> {
+        let (expired, pending) = self.pending.drain(..).partition(|touch| {
+            touch
+                .time()
+                .is_some_and(|time| now.saturating_sub(time) > PAD_WINDOW)
+        });
+        self.pending = pending;
+        expired.into_iter().map(Combined::Touch).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn finish(lane: u8, pad: Pad, time: u64) -> LaneEvent {
+        LaneEvent::Finish {
+            lane,
+            pad,
+            time: millis(time),
+        }
+    }
+
+    fn touches(combined: &[Combined]) -> Vec<Touch> {
+        combined
+            .iter()
+            .filter_map(|combined| match combined {
+                Combined::Touch(touch) => Some(*touch),
+                Combined::Event(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_sets_merge_touches_of_both_pads() {
+        let mut combiner = Combiner::new(TouchPadSet::TwoSet);
+        assert!(combiner.push(finish(4, Pad::B, 62_340)).is_empty());
+        // Another lane in between does not complete the touch.
+        assert!(combiner.push(finish(3, Pad::A, 62_400)).is_empty());
+
+        let touch = touches(&combiner.push(finish(4, Pad::A, 62_350)));
+        assert_eq!(
+            touch,
+            [Touch {
+                lane: 4,
+                lap: None,
+                pads: [Some(millis(62_350)), Some(millis(62_340))],
+            }]
+        );
+        assert_eq!(touch[0].time(), Some(millis(62_340)));
+        assert!(!touch[0].is_partial(TouchPadSet::TwoSet));
+    }
+
+    #[test]
+    fn two_sets_keep_splits_and_finish_apart() {
+        let mut combiner = Combiner::new(TouchPadSet::TwoSet);
+        let split = LaneEvent::Split {
+            lane: 4,
+            pad: Pad::A,
+            lap: 1,
+            time: millis(29_870),
+        };
+        assert!(combiner.push(split).is_empty());
+
+        let expired = touches(&combiner.push(finish(4, Pad::B, 30_000)));
+        assert!(expired.is_empty());
+        let flushed = touches(&combiner.flush());
+        assert_eq!(flushed.len(), 2);
+        assert!(flushed
+            .iter()
+            .all(|touch| touch.is_partial(TouchPadSet::TwoSet)));
+    }
+
+    #[test]
+    fn two_sets_expire_touches_of_one_pad() {
+        let mut combiner = Combiner::new(TouchPadSet::TwoSet);
+        assert!(combiner.push(finish(5, Pad::A, 64_800)).is_empty());
+        // Within the window the touch is still waiting for pad B.
+        assert!(combiner.push(finish(3, Pad::A, 65_100)).is_empty());
+
+        let expired = touches(&combiner.push(finish(6, Pad::A, 65_101)));
+        assert_eq!(
+            expired,
+            [Touch {
+                lane: 5,
+                lap: None,
+                pads: [Some(millis(64_800)), None],
+            }]
+        );
+        assert!(expired[0].is_partial(TouchPadSet::TwoSet));
+
+        // A start signal passes on everything that is left.
+        let flushed = combiner.push(LaneEvent::Start {
+            time: Duration::ZERO,
+        });
+        assert_eq!(touches(&flushed).len(), 2);
+        assert!(matches!(
+            flushed.last(),
+            Some(Combined::Event(LaneEvent::Start { .. }))
+        ));
+    }
+
+    #[test]
+    fn one_set_drops_pad_b() {
+        let mut combiner = Combiner::new(TouchPadSet::OneSet);
+        assert!(combiner.push(finish(4, Pad::B, 62_340)).is_empty());
+
+        let touch = touches(&combiner.push(finish(4, Pad::A, 62_350)));
+        assert_eq!(
+            touch,
+            [Touch {
+                lane: 4,
+                lap: None,
+                pads: [Some(millis(62_350)), None],
+            }]
+        );
+        assert!(!touch[0].is_partial(TouchPadSet::OneSet));
+        assert!(combiner.flush().is_empty());
+    }
+
+    #[test]
+    fn no_pads_pass_on_backup_buttons_only() {
+        let mut combiner = Combiner::new(TouchPadSet::None);
+        assert!(combiner.push(finish(4, Pad::A, 62_350)).is_empty());
+
+        let backup = LaneEvent::Backup {
+            lane: 4,
+            button: 1,
+            time: millis(62_410),
+        };
+        assert_eq!(combiner.push(backup), [Combined::Event(backup)]);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// Console input that registered a touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pad {
+    /// First pad set. The only set in `TouchPadSet::OneSet` pools.
+    A,
+    /// Second pad set in `TouchPadSet::TwoSet` pools.
+    B,
+}
+
+/// Event reported by a timing console. Times are running times since the start signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaneEvent {
+    /// Start signal. Time is normally zero but consoles that are reset after the start report
+    /// the delay.
+    Start { time: Duration },
+    /// Touch at a turn. `lap` counts the lengths swum so far, starting at 1.
+    Split {
+        lane: u8,
+        pad: Pad,
+        lap: u8,
+        time: Duration,
+    },
+    /// Touch at the end of the race.
+    Finish { lane: u8, pad: Pad, time: Duration },
+    /// Backup button pressed by a timekeeper. Lanes have up to three buttons numbered 1 to 3.
+    Backup {
+        lane: u8,
+        button: u8,
+        time: Duration,
+    },
+    /// Relay takeover or start judged as a false start by the console.
+    FalseStart { lane: u8, time: Duration },
+}
+
+impl LaneEvent {
+    /// Lane of the event. `None` for the start signal.
+    #[must_use]
+    pub const fn lane(&self) -> Option<u8> {
+        match self {
+            Self::Start { .. } => None,
+            Self::Split { lane, .. }
+            | Self::Finish { lane, .. }
+            | Self::Backup { lane, .. }
+            | Self::FalseStart { lane, .. } => Some(*lane),
+        }
+    }
+
+    #[must_use]
+    pub const fn time(&self) -> Duration {
+        match self {
+            Self::Start { time }
+            | Self::Split { time, .. }
+            | Self::Finish { time, .. }
+            | Self::Backup { time, .. }
+            | Self::FalseStart { time, .. } => *time,
+        }
+    }
+}
+
+impl Display for Pad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::A => "A",
+            Self::B => "B",
+        })
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for LaneEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Start { time } => write!(f, "start {}", format_time(*time)),
+                Self::Split {
+                    lane,
+                    pad,
+                    lap,
+                    time,
+                } => write!(
+                    f,
+                    "lane {lane} split {lap} pad {pad} {}",
+                    format_time(*time)
+                ),
+                Self::Finish { lane, pad, time } => {
+                    write!(f, "lane {lane} finish pad {pad} {}", format_time(*time))
+                }
+                Self::Backup { lane, button, time } => {
+                    write!(f, "lane {lane} backup {button} {}", format_time(*time))
+                }
+                Self::FalseStart { lane, time } => {
+                    write!(f, "lane {lane} false start {}", format_time(*time))
+                }
+            },
+        }
+    }
+}
+
+/// Running time formatted as 'mm:ss.ttt'.
+#[must_use]
+pub fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
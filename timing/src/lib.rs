@@ -0,0 +1,44 @@
+//! # Timing
+//!
+//! Drivers for swim timing consoles. `Console` reads frames from a transport and decodes them
+//! into `LaneEvent`s, and `Combiner` merges the touches of one or two pad sets according to the
+//! `TouchPadSet` of the meet. `Reconciler` picks the official time of each lane from the pad and
+//! backup button times, and `Scoreboard` shows the heat in the water.
+//!
+//! ```no_run
+//! use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+//! use std::path::Path;
+//! use timing::{Combiner, Console};
+//!
+//! let console = Console::open(Path::new("/dev/ttyUSB0")).unwrap();
+//! let mut combiner = Combiner::new(TouchPadSet::TwoSet);
+//! for event in console.flatten() {
+//!     for combined in combiner.push(event) {
+//!         println!("{combined:?}");
+//!     }
+//! }
+//! ```
+#![warn(clippy::complexity)]
+#![warn(clippy::correctness)]
+#![warn(deprecated)]
+#![warn(clippy::nursery)]
+#![warn(clippy::pedantic)]
+#![warn(clippy::perf)]
+#![warn(clippy::as_conversions)]
+#![warn(clippy::style)]
+#![warn(clippy::suspicious)]
+#![warn(clippy::missing_const_for_fn)]
+#![allow(clippy::missing_docs_in_private_items)]
+#![allow(clippy::missing_inline_in_public_items)]
+pub mod combine;
+pub mod event;
+pub mod protocol;
+pub mod reconcile;
+pub mod scoreboard;
+pub mod transport;
+
+pub use self::combine::{Combined, Combiner, Touch};
+pub use self::event::{LaneEvent, Pad};
+pub use self::reconcile::{LaneTimes, Reconciled, Reconciler};
+pub use self::scoreboard::Scoreboard;
+pub use self::transport::{Console, Replay};
@@ -0,0 +1,422 @@
+//! # Console protocol
+//!
+//! Timing consoles send one ASCII frame per event over a serial line. The layout follows the
+//! scoreboard output of ARES and Quantum style consoles:
+//!
+//! ```text
+//! <SOH>FI;04;A;00;01:02.345*6F<EOT>
+//! ```
+//!
+//! Frames start with `SOH` (0x01) and end with `EOT` (0x04). Fields are separated by ';':
+//!
+//! | field   | values                                                                        |
+//! |---------|-------------------------------------------------------------------------------|
+//! | code    | `ST` start, `SP` split, `FI` finish, `BU` backup button, `FS` false start      |
+//! | lane    | two digits. `00` for the start signal                                         |
+//! | channel | `A` or `B` for the pad set, `1` to `3` for backup buttons, `-` otherwise      |
+//! | lap     | two digits with the number of lengths swum at a split. `00` otherwise         |
+//! | time    | running time since the start as 'mm:ss.ttt'                                   |
+//!
+//! The fields are followed by '*' and a checksum of two hexadecimal digits, which is the XOR of
+//! all bytes between `SOH` and '*'. Bytes outside of frames are line noise and are skipped.
+//!
+//! Consoles differ in field widths and codes, so check the layout against the manual of the
+//! console before using this on a meet.
+use crate::event::{LaneEvent, Pad};
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// Start of a frame.
+pub const SOH: u8 = 0x01;
+/// End of a frame.
+pub const EOT: u8 = 0x04;
+/// Frames longer than this are dropped. Valid frames are 24 bytes long.
+const MAX_FRAME_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Code,
+    Lane,
+    Channel,
+    Lap,
+    Time,
+    Checksum,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    Io(#[from] std::io::Error),
+    /// A frame was interrupted by the start of a new frame.
+    Truncated,
+    /// A frame without an end within `MAX_FRAME_LENGTH` bytes.
+    TooLong,
+    /// A frame with bytes that are not ASCII.
+    Encoding,
+    Missing(Field),
+    /// Field and its value.
+    Invalid(Field, String),
+    /// Checksum calculated from the frame and checksum sent by the console.
+    Checksum {
+        expected: u8,
+        found: u8,
+    },
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Code => write!(f, "code"),
+                Self::Lane => write!(f, "lane"),
+                Self::Channel => write!(f, "channel"),
+                Self::Lap => write!(f, "lap"),
+                Self::Time => write!(f, "time"),
+                Self::Checksum => write!(f, "checksum"),
+            },
+        }
+    }
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::Io(why) => write!(f, "{why}"),
+                Self::Truncated => write!(f, "frame was interrupted by a new frame"),
+                Self::TooLong => write!(f, "frame is longer than {MAX_FRAME_LENGTH} bytes"),
+                Self::Encoding => write!(f, "frame is not ASCII"),
+                Self::Missing(field) => write!(f, "frame is missing the {field} field"),
+                Self::Invalid(field, value) => write!(f, "invalid {field} '{value}'"),
+                Self::Checksum { expected, found } => write!(
+                    f,
+                    "checksum is {found:02X} but the frame adds up to {expected:02X}"
+                ),
+            },
+        }
+    }
+}
+
+/// Splits a byte stream into frames and decodes them. Bytes can be pushed in chunks of any size,
+/// frames split across chunks are kept until the rest arrives.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    frame: Option<Vec<u8>>,
+}
+
+impl Decoder {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { frame: None }
+    }
+
+    /// Decode all frames that are completed by `bytes`.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Result<LaneEvent, Error>> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            match byte {
+                SOH => {
+                    if self.frame.replace(Vec::new()).is_some() {
+                        events.push(Err(Error::Truncated));
+                    }
+                }
+                EOT => {
+                    if let Some(content) = self.frame.take() {
+                        events.push(decode(&content));
+                    }
+                }
+                byte => {
+                    if let Some(content) = &mut self.frame {
+                        content.push(byte);
+                        if content.len() > MAX_FRAME_LENGTH {
+                            self.frame = None;
+                            events.push(Err(Error::TooLong));
+                        }
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Decode the content of a frame between `SOH` and `EOT`.
+///
+/// # Errors
+/// Returns an error if the frame is not ASCII, the checksum does not match or a field is missing
+/// or invalid.
+pub fn decode(content: &[u8]) -> Result<LaneEvent, Error> {
+    let content = std::str::from_utf8(content)
+        .ok()
+        .filter(|content| content.is_ascii())
+        .ok_or(Error::Encoding)?;
+    let (fields, checksum) = content
+        .rsplit_once('*')
+        .ok_or(Error::Missing(Field::Checksum))?;
+    let found = u8::from_str_radix(checksum, 16)
+        .ok()
+        .filter(|_| checksum.len() == 2)
+        .ok_or_else(|| Error::Invalid(Field::Checksum, checksum.to_string()))?;
+    let expected = self::checksum(fields.as_bytes());
+    if expected != found {
+        return Err(Error::Checksum { expected, found });
+    }
+
+    let mut fields = fields.split(';');
+    let mut next = |field| fields.next().ok_or(Error::Missing(field));
+    let code = next(Field::Code)?;
+    let lane = number(Field::Lane, next(Field::Lane)?)?;
+    let channel = next(Field::Channel)?;
+    let lap = number(Field::Lap, next(Field::Lap)?)?;
+    let time = parse_time(next(Field::Time)?)?;
+    let pad = || match channel {
+        "A" => Ok(Pad::A),
+        "B" => Ok(Pad::B),
+        _ => Err(Error::Invalid(Field::Channel, channel.to_string())),
+    };
+
+    match code {
+        "ST" => Ok(LaneEvent::Start { time }),
+        "SP" => Ok(LaneEvent::Split {
+            lane,
+            pad: pad()?,
+            lap,
+            time,
+        }),
+        "FI" => Ok(LaneEvent::Finish {
+            lane,
+            pad: pad()?,
+            time,
+        }),
+        "BU" => match number(Field::Channel, channel)? {
+            button @ 1..=3 => Ok(LaneEvent::Backup { lane, button, time }),
+            _ => Err(Error::Invalid(Field::Channel, channel.to_string())),
+        },
+        "FS" => Ok(LaneEvent::FalseStart { lane, time }),
+        _ => Err(Error::Invalid(Field::Code, code.to_string())),
+    }
+}
+
+/// Encode `event` as a frame. Used to write replay logs.
+#[must_use]
+pub fn encode(event: &LaneEvent) -> Vec<u8> {
+    let (code, lane, channel, lap) = match *event {
+        LaneEvent::Start { .. } => ("ST", 0, String::from("-"), 0),
+        LaneEvent::Split { lane, pad, lap, .. } => ("SP", lane, pad.to_string(), lap),
+        LaneEvent::Finish { lane, pad, .. } => ("FI", lane, pad.to_string(), 0),
+        LaneEvent::Backup { lane, button, .. } => ("BU", lane, button.to_string(), 0),
+        LaneEvent::FalseStart { lane, .. } => ("FS", lane, String::from("-"), 0),
+    };
+    let fields = format!(
+        "{code};{lane:02};{channel};{lap:02};{}",
+        crate::event::format_time(event.time())
+    );
+    let mut frame = vec![SOH];
+    frame.extend_from_slice(fields.as_bytes());
+    frame.extend_from_slice(format!("*{:02X}", checksum(fields.as_bytes())).as_bytes());
+    frame.push(EOT);
+    frame
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+fn number(field: Field, value: &str) -> Result<u8, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::Invalid(field, value.to_string()))
+}
+
+/// Parse a running time formatted as 'mm:ss.ttt'.
+fn parse_time(value: &str) -> Result<Duration, Error> {
+    let invalid = || Error::Invalid(Field::Time, value.to_string());
+    let digits = |digits: &str| {
+        (!digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()))
+            .then(|| digits.parse::<u64>().ok())
+            .flatten()
+            .ok_or_else(invalid)
+    };
+    let (minutes, rest) = value.split_once(':').ok_or_else(invalid)?;
+    let (seconds, millis) = rest.split_once('.').ok_or_else(invalid)?;
+    if seconds.len() != 2 || millis.len() != 3 {
+        return Err(invalid());
+    }
+    let (minutes, seconds, millis) = (digits(minutes)?, digits(seconds)?, digits(millis)?);
+    if seconds >= 60 {
+        return Err(invalid());
+    }
+    minutes
+        .checked_mul(60_000)
+        .and_then(|minutes| minutes.checked_add(seconds * 1000 + millis))
+        .map(Duration::from_millis)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame with `fields` and a correct checksum.
+    fn frame(fields: &str) -> Vec<u8> {
+        let mut frame = vec![SOH];
+        frame.extend_from_slice(fields.as_bytes());
+        frame.extend_from_slice(format!("*{:02X}", checksum(fields.as_bytes())).as_bytes());
+        frame.push(EOT);
+        frame
+    }
+
+    fn events() -> [LaneEvent; 5] {
+        [
+            LaneEvent::Start {
+                time: Duration::ZERO,
+            },
+            LaneEvent::Split {
+                lane: 4,
+                pad: Pad::A,
+                lap: 1,
+                time: Duration::from_millis(29_870),
+            },
+            LaneEvent::Finish {
+                lane: 10,
+                pad: Pad::B,
+                time: Duration::from_millis(62_340),
+            },
+            LaneEvent::Backup {
+                lane: 4,
+                button: 3,
+                time: Duration::from_millis(62_410),
+            },
+            LaneEvent::FalseStart {
+                lane: 2,
+                time: Duration::from_millis(120_005),
+            },
+        ]
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for event in events() {
+            let encoded = encode(&event);
+            assert_eq!(encoded.first(), Some(&SOH));
+            assert_eq!(encoded.last(), Some(&EOT));
+            assert_eq!(decode(&encoded[1..encoded.len() - 1]).ok(), Some(event));
+        }
+    }
+
+    #[test]
+    fn encoded_frames_match_the_documented_layout() {
+        let finish = LaneEvent::Finish {
+            lane: 4,
+            pad: Pad::A,
+            time: Duration::from_millis(62_345),
+        };
+        assert_eq!(encode(&finish), frame("FI;04;A;00;01:02.345"));
+    }
+
+    #[test]
+    fn frames_split_across_chunks() {
+        let mut bytes = b"noise".to_vec();
+        for event in events() {
+            bytes.extend(encode(&event));
+            bytes.extend_from_slice(b"\r\n");
+        }
+
+        for chunk_size in [1, 2, 7, bytes.len()] {
+            let mut decoder = Decoder::new();
+            let received: Vec<LaneEvent> = bytes
+                .chunks(chunk_size)
+                .flat_map(|chunk| decoder.push(chunk))
+                .map(|event| event.expect("valid frame"))
+                .collect();
+            assert_eq!(received, events());
+        }
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let mut bytes = frame("FI;04;A;00;01:02.345");
+        let star = bytes
+            .iter()
+            .position(|byte| *byte == b'*')
+            .expect("checksum");
+        bytes[star + 1..star + 3].copy_from_slice(b"00");
+
+        let decoded = Decoder::new().push(&bytes);
+        assert!(matches!(
+            decoded.as_slice(),
+            [Err(Error::Checksum { found: 0, .. })]
+        ));
+    }
+
+    #[test]
+    fn truncated_frame_is_reported_and_the_next_frame_decoded() {
+        let mut bytes = b"\x01FI;04;A;".to_vec();
+        bytes.extend(frame("ST;00;-;00;00:00.000"));
+
+        let decoded = Decoder::new().push(&bytes);
+        assert!(matches!(
+            decoded.as_slice(),
+            [Err(Error::Truncated), Ok(LaneEvent::Start { .. })]
+        ));
+    }
+
+    #[test]
+    fn too_long_frame_is_dropped() {
+        let mut bytes = vec![SOH];
+        bytes.extend([b'x'; MAX_FRAME_LENGTH + 1]);
+        bytes.push(EOT);
+        bytes.extend(frame("ST;00;-;00;00:00.000"));
+
+        let decoded = Decoder::new().push(&bytes);
+        assert!(matches!(
+            decoded.as_slice(),
+            [Err(Error::TooLong), Ok(LaneEvent::Start { .. })]
+        ));
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let decode_fields = |fields: &str| {
+            let frame = frame(fields);
+            decode(&frame[1..frame.len() - 1])
+        };
+
+        assert!(matches!(
+            decode_fields("FI;04;A;00;1:02.3"),
+            Err(Error::Invalid(Field::Time, _))
+        ));
+        assert!(matches!(
+            decode_fields("FI;04;A;00;01:60.000"),
+            Err(Error::Invalid(Field::Time, _))
+        ));
+        assert!(matches!(
+            decode_fields("FI;x4;A;00;01:02.345"),
+            Err(Error::Invalid(Field::Lane, _))
+        ));
+        assert!(matches!(
+            decode_fields("FI;04;C;00;01:02.345"),
+            Err(Error::Invalid(Field::Channel, _))
+        ));
+        assert!(matches!(
+            decode_fields("BU;04;4;00;01:02.345"),
+            Err(Error::Invalid(Field::Channel, _))
+        ));
+        assert!(matches!(
+            decode_fields("XX;04;A;00;01:02.345"),
+            Err(Error::Invalid(Field::Code, _))
+        ));
+        assert!(matches!(
+            decode_fields("FI;04;A;00"),
+            Err(Error::Missing(Field::Time))
+        ));
+        assert!(matches!(
+            decode(b"FI;04;A;00;01:02.345"),
+            Err(Error::Missing(Field::Checksum))
+        ));
+        assert!(matches!(decode(b"FI;\xF8*00"), Err(Error::Encoding)));
+    }
+}
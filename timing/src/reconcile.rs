@@ -0,0 +1,480 @@
+//! # Reconciliation
+//!
+//! Picks the official time of a lane from its pad and backup button times:
+//!
+//! 1. The pad time is official. With `TouchPadSet::TwoSet` the earliest of the two pads counts.
+//! 2. Without a pad time the backup buttons are used: the middle time of three buttons, the
+//!    average of two or the single time of one button.
+//! 3. Official times are truncated to hundredths.
+//!
+//! Missing touches and times that differ by more than a threshold are flagged. A lane with a
+//! flag that `Flag::needs_review` is marked for referee review, see `SwimResult::review`. `apply`
+//! writes the official times and review marks of a heat to its results.
+use crate::combine::{Combined, Touch};
+use crate::event::{format_time, LaneEvent, Pad};
+use jechsoft::heat_list::Heat;
+use jechsoft::meet_result::EventResult;
+use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// Largest difference between times of the same touch that is accepted without review.
+pub const DEFAULT_THRESHOLD: Duration = Duration::from_millis(300);
+
+/// Finish times of one lane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LaneTimes {
+    pub lane: u8,
+    /// Times of pad A and pad B.
+    pub pads: [Option<Duration>; 2],
+    /// Times of backup buttons 1 to 3.
+    pub buttons: [Option<Duration>; 3],
+}
+
+impl LaneTimes {
+    /// Collect the finish touches and backup button times of a heat, sorted by lane. Events from
+    /// before the last start signal are left out.
+    #[must_use]
+    pub fn from_events(events: &[Combined]) -> Vec<Self> {
+        let heat = events
+            .iter()
+            .rposition(|event| matches!(event, Combined::Event(LaneEvent::Start { .. })))
+            .map_or(events, |start| &events[start + 1..]);
+
+        let mut lanes: Vec<Self> = Vec::new();
+        for event in heat {
+            let lane = match event {
+                Combined::Touch(Touch {
+                    lane, lap: None, ..
+                })
+                | Combined::Event(LaneEvent::Backup { lane, .. }) => *lane,
+                _ => continue,
+            };
+            let index = lanes
+                .iter()
+                .position(|times| times.lane == lane)
+                .unwrap_or_else(|| {
+                    lanes.push(Self {
+                        lane,
+                        ..Self::default()
+                    });
+                    lanes.len() - 1
+                });
+            let times = &mut lanes[index];
+            match *event {
+                Combined::Touch(touch) => {
+                    for (time, pad) in times.pads.iter_mut().zip(touch.pads) {
+                        *time = time.or(pad);
+                    }
+                }
+                Combined::Event(LaneEvent::Backup { button, time, .. }) => {
+                    if let Some(slot) = usize::from(button)
+                        .checked_sub(1)
+                        .and_then(|index| times.buttons.get_mut(index))
+                    {
+                        *slot = Some(time);
+                    }
+                }
+                Combined::Event(_) => {}
+            }
+        }
+        lanes.sort_by_key(|times| times.lane);
+        lanes
+    }
+}
+
+/// Where the official time is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Pad,
+    /// Number of backup button times used.
+    Buttons(usize),
+}
+
+/// Problem found while reconciling a lane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    /// The pool has pads but none registered the finish.
+    MissingTouch,
+    /// Only one of two pad sets registered the finish. The pad that did not register is given.
+    MissingPad(Pad),
+    /// The two pad sets differ by the given time.
+    PadDiscrepancy(Duration),
+    /// The pad time differs from the backup time by the given time.
+    BackupDiscrepancy(Duration),
+    /// The backup buttons differ from each other by the given time.
+    ButtonDiscrepancy(Duration),
+    /// Neither pads nor buttons registered the finish.
+    NoTime,
+}
+
+impl Flag {
+    /// True if the official time has to be confirmed by the referee. A missing pad in a pool with
+    /// two pad sets is covered by the other pad and needs no review.
+    #[must_use]
+    pub const fn needs_review(&self) -> bool {
+        !matches!(self, Self::MissingPad(_))
+    }
+}
+
+/// Official time of a lane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconciled {
+    pub lane: u8,
+    /// `None` if there is no time at all.
+    pub time: Option<Duration>,
+    pub source: Option<Source>,
+    pub flags: Vec<Flag>,
+}
+
+impl Reconciled {
+    #[must_use]
+    pub fn needs_review(&self) -> bool {
+        self.flags.iter().any(Flag::needs_review)
+    }
+}
+
+/// Reconciles lane times of a meet. See the module documentation for the rules.
+#[derive(Debug, Clone, Copy)]
+pub struct Reconciler {
+    pad_set: TouchPadSet,
+    threshold: Duration,
+}
+
+impl Reconciler {
+    #[must_use]
+    pub const fn new(pad_set: TouchPadSet) -> Self {
+        Self {
+            pad_set,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Largest accepted difference between pads, buttons and pads and buttons. Defaults to
+    /// `DEFAULT_THRESHOLD`.
+    #[must_use]
+    pub const fn with_threshold(mut self, threshold: Duration) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn reconcile(&self, times: &LaneTimes) -> Reconciled {
+        let mut flags = Vec::new();
+        let pads = match self.pad_set {
+            TouchPadSet::None => [None, None],
+            TouchPadSet::OneSet => [times.pads[0], None],
+            TouchPadSet::TwoSet => times.pads,
+        };
+        let pad = pads.iter().flatten().min().copied();
+        let buttons: Vec<Duration> = times.buttons.iter().flatten().copied().collect();
+        let backup = backup_time(&buttons);
+
+        if self.pad_set == TouchPadSet::TwoSet {
+            match pads {
+                [Some(a), Some(b)] if a.abs_diff(b) > self.threshold => {
+                    flags.push(Flag::PadDiscrepancy(a.abs_diff(b)));
+                }
+                [Some(_), None] => flags.push(Flag::MissingPad(Pad::B)),
+                [None, Some(_)] => flags.push(Flag::MissingPad(Pad::A)),
+                _ => {}
+            }
+        }
+
+        let (time, source) = if let Some(pad) = pad {
+            if let Some(backup) = backup {
+                if pad.abs_diff(backup) > self.threshold {
+                    flags.push(Flag::BackupDiscrepancy(pad.abs_diff(backup)));
+                }
+            }
+            (Some(pad), Some(Source::Pad))
+        } else {
+            if self.pad_set != TouchPadSet::None {
+                flags.push(Flag::MissingTouch);
+            }
+            if let (Some(first), Some(last)) = (buttons.iter().min(), buttons.iter().max()) {
+                if last.saturating_sub(*first) > self.threshold {
+                    flags.push(Flag::ButtonDiscrepancy(last.saturating_sub(*first)));
+                }
+            }
+            if backup.is_none() {
+                flags.push(Flag::NoTime);
+            }
+            (backup, backup.map(|_| Source::Buttons(buttons.len())))
+        };
+
+        Reconciled {
+            lane: times.lane,
+            time: time.map(truncate),
+            source,
+            flags,
+        }
+    }
+}
+
+/// Set the official time and the review mark of the swimmers of `heat` in `results`.
+///
+/// Swimmers are found by the name and club of the entry in the lane. Returns the lanes of
+/// `reconciled` without a swimmer in `heat` or without a result in `results`.
+pub fn apply(reconciled: &[Reconciled], heat: &Heat, results: &mut EventResult) -> Vec<u8> {
+    let mut unmatched = Vec::new();
+    for lane in reconciled {
+        let result = heat
+            .lanes
+            .iter()
+            .find(|entered| entered.lane == lane.lane)
+            .and_then(|entered| {
+                results
+                    .classes
+                    .iter_mut()
+                    .flat_map(|class| class.results.iter_mut())
+                    .find(|result| {
+                        result.name == entered.entry.name && result.club == entered.entry.club
+                    })
+            });
+        let Some(result) = result else {
+            unmatched.push(lane.lane);
+            continue;
+        };
+        result.time = lane.time;
+        result.review = lane.needs_review();
+    }
+    unmatched
+}
+
+/// Backup time of the buttons: the average of two times and the middle time otherwise.
+fn backup_time(buttons: &[Duration]) -> Option<Duration> {
+    let mut buttons = buttons.to_vec();
+    buttons.sort();
+    match buttons.as_slice() {
+        [first, second] => Some((*first + *second) / 2),
+        buttons => buttons.get(buttons.len() / 2).copied(),
+    }
+}
+
+/// Truncate to hundredths. Official swim times are never rounded up.
+fn truncate(time: Duration) -> Duration {
+    Duration::from_millis(u64::try_from(time.as_millis() / 10 * 10).unwrap_or(u64::MAX))
+}
+
+#[allow(clippy::recursive_format_impl)]
+impl Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.align() {
+            Some(_) => f.pad(&self.to_string()),
+            None => match self {
+                Self::MissingTouch => write!(f, "no pad registered the finish"),
+                Self::MissingPad(pad) => write!(f, "pad {pad} did not register the finish"),
+                Self::PadDiscrepancy(difference) => {
+                    write!(f, "pads differ by {}", format_time(*difference))
+                }
+                Self::BackupDiscrepancy(difference) => write!(
+                    f,
+                    "pad and backup time differ by {}",
+                    format_time(*difference)
+                ),
+                Self::ButtonDiscrepancy(difference) => {
+                    write!(f, "backup buttons differ by {}", format_time(*difference))
+                }
+                Self::NoTime => write!(f, "no time"),
+            },
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pad => f.pad("pad"),
+            Self::Buttons(count) => f.pad(&format!("{count} backup buttons")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jechsoft::heat_list::{Entry, Lane};
+    use jechsoft::meet_result::MeetResult;
+    use std::convert::TryFrom;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn times(pads: [Option<u64>; 2], buttons: [Option<u64>; 3]) -> LaneTimes {
+        LaneTimes {
+            lane: 4,
+            pads: pads.map(|pad| pad.map(millis)),
+            buttons: buttons.map(|button| button.map(millis)),
+        }
+    }
+
+    fn two_sets(times: &LaneTimes) -> Reconciled {
+        Reconciler::new(TouchPadSet::TwoSet).reconcile(times)
+    }
+
+    #[test]
+    fn earliest_pad_is_official() {
+        let reconciled = two_sets(&times(
+            [Some(62_350), Some(62_340)],
+            [Some(62_410), Some(62_390), None],
+        ));
+        assert_eq!(reconciled.time, Some(millis(62_340)));
+        assert_eq!(reconciled.source, Some(Source::Pad));
+        assert!(reconciled.flags.is_empty());
+        assert!(!reconciled.needs_review());
+    }
+
+    #[test]
+    fn official_times_are_truncated_to_hundredths() {
+        let reconciled = two_sets(&times([Some(62_349), Some(62_359)], [None; 3]));
+        assert_eq!(reconciled.time, Some(millis(62_340)));
+    }
+
+    #[test]
+    fn missing_pad_needs_no_review() {
+        let reconciled = two_sets(&times([Some(62_350), None], [None; 3]));
+        assert_eq!(reconciled.flags, [Flag::MissingPad(Pad::B)]);
+        assert!(!reconciled.needs_review());
+
+        let reconciled = two_sets(&times([None, Some(62_350)], [None; 3]));
+        assert_eq!(reconciled.flags, [Flag::MissingPad(Pad::A)]);
+        assert_eq!(reconciled.time, Some(millis(62_350)));
+    }
+
+    #[test]
+    fn pad_discrepancy() {
+        let reconciled = two_sets(&times([Some(62_000), Some(62_400)], [None; 3]));
+        assert_eq!(reconciled.flags, [Flag::PadDiscrepancy(millis(400))]);
+        assert!(reconciled.needs_review());
+        assert_eq!(reconciled.time, Some(millis(62_000)));
+    }
+
+    #[test]
+    fn backup_discrepancy() {
+        let reconciled = two_sets(&times(
+            [Some(62_000), Some(62_010)],
+            [Some(62_500), None, None],
+        ));
+        assert_eq!(reconciled.flags, [Flag::BackupDiscrepancy(millis(500))]);
+        assert!(reconciled.needs_review());
+    }
+
+    #[test]
+    fn missing_touch_uses_the_middle_button() {
+        let reconciled = two_sets(&times(
+            [None, None],
+            [Some(64_900), Some(64_870), Some(64_950)],
+        ));
+        assert_eq!(reconciled.time, Some(millis(64_900)));
+        assert_eq!(reconciled.source, Some(Source::Buttons(3)));
+        assert_eq!(reconciled.flags, [Flag::MissingTouch]);
+        assert!(reconciled.needs_review());
+    }
+
+    #[test]
+    fn two_buttons_are_averaged() {
+        let reconciled = Reconciler::new(TouchPadSet::None)
+            .reconcile(&times([None, None], [Some(62_410), None, Some(62_395)]));
+        // The average 1:02.4025 is truncated to 1:02.40.
+        assert_eq!(reconciled.time, Some(millis(62_400)));
+        assert_eq!(reconciled.source, Some(Source::Buttons(2)));
+        assert!(reconciled.flags.is_empty());
+    }
+
+    #[test]
+    fn button_discrepancy() {
+        let reconciled = Reconciler::new(TouchPadSet::None)
+            .with_threshold(millis(100))
+            .reconcile(&times([None, None], [Some(62_000), Some(62_150), None]));
+        assert_eq!(reconciled.flags, [Flag::ButtonDiscrepancy(millis(150))]);
+        assert!(reconciled.needs_review());
+    }
+
+    #[test]
+    fn no_time() {
+        let reconciled = two_sets(&times([None, None], [None; 3]));
+        assert_eq!(reconciled.time, None);
+        assert_eq!(reconciled.source, None);
+        assert_eq!(reconciled.flags, [Flag::MissingTouch, Flag::NoTime]);
+
+        let reconciled = Reconciler::new(TouchPadSet::None).reconcile(&times([None; 2], [None; 3]));
+        assert_eq!(reconciled.flags, [Flag::NoTime]);
+    }
+
+    #[test]
+    fn pad_sets_decide_which_pads_count() {
+        let lane = times([Some(62_350), Some(62_340)], [Some(62_300), None, None]);
+
+        let one_set = Reconciler::new(TouchPadSet::OneSet).reconcile(&lane);
+        assert_eq!(one_set.time, Some(millis(62_350)));
+        assert!(one_set.flags.is_empty());
+
+        let none = Reconciler::new(TouchPadSet::None).reconcile(&lane);
+        assert_eq!(none.time, Some(millis(62_300)));
+        assert_eq!(none.source, Some(Source::Buttons(1)));
+        assert!(none.flags.is_empty());
+
+        let one_set_without_pad_a =
+            Reconciler::new(TouchPadSet::OneSet).reconcile(&times([None, Some(62_340)], [None; 3]));
+        assert_eq!(
+            one_set_without_pad_a.flags,
+            [Flag::MissingTouch, Flag::NoTime]
+        );
+    }
+
+    fn lane(lane: u8, name: &str) -> Lane {
+        Lane {
+            lane,
+            entry: Entry {
+                name: name.to_string(),
+                club: "Bergen Svømmeklubb".to_string(),
+                birth_year: None,
+                entry_time: None,
+                nation: None,
+                first_leg: None,
+            },
+        }
+    }
+
+    #[test]
+    fn apply_sets_time_and_review() {
+        let heat = Heat {
+            number: 1,
+            lanes: vec![lane(3, "Ola Nordmann"), lane(4, "Kari Nordmann")],
+        };
+        let mut results = MeetResult::try_from(
+            r#"{ "events": [{ "event": 1, "classes": [
+                { "class": "SR", "results": [
+                    { "name": "Kari Nordmann", "club": "Bergen Svømmeklubb" }
+                ] },
+                { "class": "JR", "results": [
+                    { "name": "Ola Nordmann", "club": "Bergen Svømmeklubb", "time": "1:10.00" }
+                ] }
+            ] }] }"#,
+        )
+        .expect("valid results");
+        let reconciled = [
+            two_sets(&times([Some(62_349), Some(62_360)], [None; 3])),
+            Reconciled {
+                lane: 3,
+                ..two_sets(&times([None, None], [Some(63_200), None, None]))
+            },
+            Reconciled {
+                lane: 5,
+                ..two_sets(&times([Some(64_800), None], [None; 3]))
+            },
+        ];
+
+        let unmatched = apply(&reconciled, &heat, &mut results.events[0]);
+
+        assert_eq!(unmatched, [5]);
+        let kari = &results.events[0].classes[0].results[0];
+        assert_eq!(kari.time, Some(millis(62_340)));
+        assert!(!kari.review);
+        let ola = &results.events[0].classes[1].results[0];
+        assert_eq!(ola.time, Some(millis(63_200)));
+        assert!(ola.review);
+    }
+}
@@ -0,0 +1,463 @@
+//! # Scoreboard
+//!
+//! Live display of the heat in the water. `Scoreboard` is fed with the combined events of the
+//! timing console and renders the heat as text for a terminal or as an update in the scoreboard
+//! protocol below.
+//!
+//! ## Protocol
+//!
+//! Generic line based text protocol for scoreboards connected by serial line or TCP. Every update
+//! sends the whole board. Lines end with CR LF and fields are separated by `;`:
+//!
+//! ```text
+//! H;Event 3 - 100m Freestyle Women;Heat 2 of 3
+//! C;1:02.34
+//! L;4;1;Kari Nordmann;Bergen Svommeklubb;1:02.34;F
+//! L;5;;Ola Nordmann;Bergen Svommeklubb;31.02;S1
+//! L;6;;Per Hansen;Tertnes;;
+//! E
+//! ```
+//!
+//! `H` is the heading with event and heat, `C` the running clock and `L` a lane with lane number,
+//! place, name, club, time and state. The state is `F` for a final time, `S` followed by the lap
+//! for a split and empty before the first touch. `E` ends the update. Text is sent as ASCII:
+//! Norwegian letters are transliterated and other characters are replaced with `?`.
+use crate::combine::Combined;
+use crate::event::LaneEvent;
+use crate::reconcile::{LaneTimes, Reconciler};
+use jechsoft::heat_list::Heat;
+use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+use jechsoft::swim_time;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Progress of a lane in the heat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum State {
+    /// No touch yet.
+    #[default]
+    Waiting,
+    /// Touch at a turn. Holds the lengths swum so far.
+    Split(u8),
+    Finished,
+}
+
+/// Lane shown on the scoreboard.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub lane: u8,
+    pub name: String,
+    pub club: String,
+    /// Latest split or the official finish time.
+    pub time: Option<Duration>,
+    pub state: State,
+    /// Place among the finished lanes.
+    pub place: Option<usize>,
+    finish: LaneTimes,
+}
+
+/// Heat shown on the scoreboard.
+#[derive(Debug, Clone)]
+pub struct Scoreboard {
+    event: String,
+    heat: String,
+    reconciler: Reconciler,
+    rows: Vec<Row>,
+    clock: Option<Duration>,
+}
+
+impl Scoreboard {
+    /// Show the lanes of a seeded heat. `event` and `heat_title` are the headings, see
+    /// `heat_list::event_title` and `heat_list::heat_title`. Finish times are picked from the
+    /// pads and backup buttons according to `pad_set`, see `Reconciler`.
+    #[must_use]
+    pub fn new(event: String, heat_title: String, heat: &Heat, pad_set: TouchPadSet) -> Self {
+        Self {
+            event,
+            heat: heat_title,
+            reconciler: Reconciler::new(pad_set),
+            rows: heat
+                .lanes
+                .iter()
+                .map(|lane| Row {
+                    lane: lane.lane,
+                    name: lane.entry.name.clone(),
+                    club: lane.entry.club.clone(),
+                    time: None,
+                    state: State::Waiting,
+                    place: None,
+                    finish: LaneTimes {
+                        lane: lane.lane,
+                        ..LaneTimes::default()
+                    },
+                })
+                .collect(),
+            clock: None,
+        }
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Running time of the latest console event. `None` before the start signal.
+    #[must_use]
+    pub const fn clock(&self) -> Option<Duration> {
+        self.clock
+    }
+
+    /// Update the board with an event from `Combiner`. A start signal clears all times. Events
+    /// for lanes without swimmers are ignored.
+    pub fn push(&mut self, combined: &Combined) {
+        let time = match *combined {
+            Combined::Event(LaneEvent::Start { time }) => {
+                for row in &mut self.rows {
+                    row.time = None;
+                    row.state = State::Waiting;
+                    row.place = None;
+                    row.finish = LaneTimes {
+                        lane: row.lane,
+                        ..LaneTimes::default()
+                    };
+                }
+                Some(time)
+            }
+            Combined::Touch(touch) => {
+                if let Some(row) = self.rows.iter_mut().find(|row| row.lane == touch.lane) {
+                    match touch.lap {
+                        Some(lap) if row.state != State::Finished => {
+                            row.time = touch.time();
+                            row.state = State::Split(lap);
+                        }
+                        Some(_) => {}
+                        None => {
+                            for (time, pad) in row.finish.pads.iter_mut().zip(touch.pads) {
+                                *time = time.or(pad);
+                            }
+                            finish(row, &self.reconciler);
+                        }
+                    }
+                }
+                touch.time()
+            }
+            Combined::Event(LaneEvent::Backup { lane, button, time }) => {
+                if let Some(row) = self.rows.iter_mut().find(|row| row.lane == lane) {
+                    if let Some(slot) = usize::from(button)
+                        .checked_sub(1)
+                        .and_then(|index| row.finish.buttons.get_mut(index))
+                    {
+                        *slot = Some(time);
+                        finish(row, &self.reconciler);
+                    }
+                }
+                Some(time)
+            }
+            Combined::Event(event) => Some(event.time()),
+        };
+        self.clock = self.clock.max(time);
+        self.rank();
+    }
+
+    /// Board formatted for a terminal.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\n{:<60}{:>10}\n\n",
+            self.event,
+            self.heat,
+            self.clock.map(swim_time::format).unwrap_or_default()
+        );
+        for row in &self.rows {
+            let _ = writeln!(
+                text,
+                "{:>2}  {:>2}  {:<28.28} {:<24.24} {:>8} {}",
+                row.lane,
+                row.place.map(|place| place.to_string()).unwrap_or_default(),
+                row.name,
+                row.club,
+                row.time.map(swim_time::format).unwrap_or_default(),
+                match row.state {
+                    State::Waiting | State::Finished => String::new(),
+                    State::Split(lap) => format!("({lap})"),
+                }
+            );
+        }
+        text
+    }
+
+    /// Board as one update in the scoreboard protocol, see the module documentation.
+    #[must_use]
+    pub fn to_protocol(&self) -> String {
+        let mut update = format!("H;{};{}\r\n", ascii(&self.event), ascii(&self.heat));
+        let _ = write!(
+            update,
+            "C;{}\r\n",
+            self.clock.map(swim_time::format).unwrap_or_default()
+        );
+        for row in &self.rows {
+            let _ = write!(
+                update,
+                "L;{};{};{};{};{};{}\r\n",
+                row.lane,
+                row.place.map(|place| place.to_string()).unwrap_or_default(),
+                ascii(&row.name),
+                ascii(&row.club),
+                row.time.map(swim_time::format).unwrap_or_default(),
+                match row.state {
+                    State::Waiting => String::new(),
+                    State::Split(lap) => format!("S{lap}"),
+                    State::Finished => String::from("F"),
+                }
+            );
+        }
+        update.push_str("E\r\n");
+        update
+    }
+
+    /// Place the finished lanes by time. Equal times share the place.
+    fn rank(&mut self) {
+        let finished: Vec<Duration> = self
+            .rows
+            .iter()
+            .filter(|row| row.state == State::Finished)
+            .filter_map(|row| row.time)
+            .collect();
+        for row in &mut self.rows {
+            row.place = match (row.state, row.time) {
+                (State::Finished, Some(time)) => {
+                    Some(finished.iter().filter(|other| **other < time).count() + 1)
+                }
+                _ => None,
+            };
+        }
+    }
+}
+
+/// Show the official time of a lane once the pads or backup buttons have registered the finish.
+fn finish(row: &mut Row, reconciler: &Reconciler) {
+    if let Some(time) = reconciler.reconcile(&row.finish).time {
+        row.time = Some(time);
+        row.state = State::Finished;
+    }
+}
+
+/// Text with Norwegian letters transliterated, other non ASCII characters replaced with `?` and
+/// the field separator replaced with `,`.
+fn ascii(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            'æ' => ascii.push_str("ae"),
+            'Æ' => ascii.push_str("AE"),
+            'ø' | 'ö' => ascii.push('o'),
+            'Ø' | 'Ö' => ascii.push('O'),
+            'å' | 'ä' => ascii.push('a'),
+            'Å' | 'Ä' => ascii.push('A'),
+            'é' | 'è' => ascii.push('e'),
+            'ü' => ascii.push('u'),
+            ';' => ascii.push(','),
+            character if character.is_ascii() && !character.is_ascii_control() => {
+                ascii.push(character);
+            }
+            _ => ascii.push('?'),
+        }
+    }
+    ascii
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combine::Touch;
+    use jechsoft::heat_list::{Entry, Lane};
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn lane(lane: u8, name: &str, club: &str) -> Lane {
+        Lane {
+            lane,
+            entry: Entry {
+                name: name.to_string(),
+                club: club.to_string(),
+                birth_year: None,
+                entry_time: None,
+                nation: None,
+                first_leg: None,
+            },
+        }
+    }
+
+    /// Heat of the example in the module documentation.
+    fn scoreboard() -> Scoreboard {
+        let heat = Heat {
+            number: 2,
+            lanes: vec![
+                lane(4, "Kari Nordmann", "Bergen Svømmeklubb"),
+                lane(5, "Ola Nordmann", "Bergen Svømmeklubb"),
+                lane(6, "Per Hansen", "Tertnes"),
+            ],
+        };
+        Scoreboard::new(
+            "Event 3 - 100m Freestyle Women".to_string(),
+            "Heat 2 of 3".to_string(),
+            &heat,
+            TouchPadSet::OneSet,
+        )
+    }
+
+    fn start() -> Combined {
+        Combined::Event(LaneEvent::Start {
+            time: Duration::ZERO,
+        })
+    }
+
+    fn split(lane: u8, lap: u8, time: u64) -> Combined {
+        Combined::Touch(Touch {
+            lane,
+            lap: Some(lap),
+            pads: [Some(millis(time)), None],
+        })
+    }
+
+    fn finish(lane: u8, time: u64) -> Combined {
+        Combined::Touch(Touch {
+            lane,
+            lap: None,
+            pads: [Some(millis(time)), None],
+        })
+    }
+
+    fn push(scoreboard: &mut Scoreboard, events: &[Combined]) {
+        for event in events {
+            scoreboard.push(event);
+        }
+    }
+
+    #[test]
+    fn update_matches_the_documented_example() {
+        let mut scoreboard = scoreboard();
+        push(
+            &mut scoreboard,
+            &[
+                start(),
+                split(4, 1, 30_120),
+                split(5, 1, 31_020),
+                finish(4, 62_345),
+            ],
+        );
+        assert_eq!(
+            scoreboard.to_protocol(),
+            "H;Event 3 - 100m Freestyle Women;Heat 2 of 3\r\n\
+             C;1:02.34\r\n\
+             L;4;1;Kari Nordmann;Bergen Svommeklubb;1:02.34;F\r\n\
+             L;5;;Ola Nordmann;Bergen Svommeklubb;31.02;S1\r\n\
+             L;6;;Per Hansen;Tertnes;;\r\n\
+             E\r\n"
+        );
+    }
+
+    #[test]
+    fn equal_times_share_the_place() {
+        let mut scoreboard = scoreboard();
+        push(
+            &mut scoreboard,
+            &[
+                start(),
+                finish(5, 61_000),
+                finish(6, 61_000),
+                finish(4, 62_000),
+            ],
+        );
+        let places: Vec<Option<usize>> = scoreboard.rows().iter().map(|row| row.place).collect();
+        assert_eq!(places, [Some(3), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn splits_after_the_finish_are_ignored() {
+        let mut scoreboard = scoreboard();
+        push(&mut scoreboard, &[start(), split(4, 1, 30_120)]);
+        assert_eq!(scoreboard.rows()[0].state, State::Split(1));
+        assert_eq!(scoreboard.rows()[0].time, Some(millis(30_120)));
+        assert_eq!(scoreboard.rows()[0].place, None);
+
+        push(&mut scoreboard, &[finish(4, 62_345), split(4, 2, 63_000)]);
+        assert_eq!(scoreboard.rows()[0].state, State::Finished);
+        assert_eq!(scoreboard.rows()[0].time, Some(millis(62_340)));
+        assert_eq!(scoreboard.rows()[0].place, Some(1));
+    }
+
+    #[test]
+    fn backup_buttons_finish_a_lane_without_a_touch() {
+        let mut scoreboard = scoreboard();
+        push(
+            &mut scoreboard,
+            &[
+                start(),
+                Combined::Event(LaneEvent::Backup {
+                    lane: 6,
+                    button: 1,
+                    time: millis(64_000),
+                }),
+                Combined::Event(LaneEvent::Backup {
+                    lane: 6,
+                    button: 4,
+                    time: millis(64_500),
+                }),
+            ],
+        );
+        assert_eq!(scoreboard.rows()[2].state, State::Finished);
+        assert_eq!(scoreboard.rows()[2].time, Some(millis(64_000)));
+    }
+
+    #[test]
+    fn start_signal_clears_the_board() {
+        let mut scoreboard = scoreboard();
+        push(
+            &mut scoreboard,
+            &[start(), split(5, 1, 31_020), finish(4, 62_345), start()],
+        );
+        for row in scoreboard.rows() {
+            assert_eq!(row.state, State::Waiting);
+            assert_eq!(row.time, None);
+            assert_eq!(row.place, None);
+        }
+        assert!(scoreboard
+            .to_protocol()
+            .contains("L;4;;Kari Nordmann;Bergen Svommeklubb;;\r\n"));
+
+        // Finish touches of the previous race are not mixed into the new one.
+        push(&mut scoreboard, &[finish(4, 65_000)]);
+        assert_eq!(scoreboard.rows()[0].time, Some(millis(65_000)));
+    }
+
+    #[test]
+    fn clock_never_runs_backwards() {
+        let mut scoreboard = scoreboard();
+        assert_eq!(scoreboard.clock(), None);
+        push(
+            &mut scoreboard,
+            &[start(), finish(4, 62_345), split(5, 1, 31_020)],
+        );
+        assert_eq!(scoreboard.clock(), Some(millis(62_345)));
+    }
+
+    #[test]
+    fn lanes_without_swimmers_are_ignored() {
+        let mut scoreboard = scoreboard();
+        push(&mut scoreboard, &[start(), finish(1, 60_000)]);
+        assert!(scoreboard
+            .rows()
+            .iter()
+            .all(|row| row.state == State::Waiting));
+    }
+
+    #[test]
+    fn text_is_sent_as_ascii() {
+        assert_eq!(ascii("Ærlig Åse Ødegård"), "AErlig Ase Odegard");
+        assert_eq!(ascii("Müller; Jérôme"), "Muller, Jer?me");
+        assert_eq!(ascii("tab\there"), "tab?here");
+    }
+}
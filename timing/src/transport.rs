@@ -0,0 +1,190 @@
+use crate::event::LaneEvent;
+use crate::protocol::{Decoder, Error};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Reads lane events from a timing console.
+///
+/// Any `Read` can be used as transport: a serial device, a network socket or a `Replay` of a
+/// captured byte log. Serial devices have to be configured before they are opened, for example
+/// with `stty -F /dev/ttyUSB0 9600 raw`.
+#[derive(Debug)]
+pub struct Console<R> {
+    transport: R,
+    decoder: Decoder,
+    decoded: VecDeque<Result<LaneEvent, Error>>,
+    closed: bool,
+}
+
+impl<R: Read> Console<R> {
+    pub const fn new(transport: R) -> Self {
+        Self {
+            transport,
+            decoder: Decoder::new(),
+            decoded: VecDeque::new(),
+            closed: false,
+        }
+    }
+}
+
+impl Console<File> {
+    /// Open a serial device or a captured byte log.
+    ///
+    /// # Errors
+    /// Returns an error if `path` cannot be opened.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        File::open(path).map(Self::new)
+    }
+}
+
+/// Yields lane events and decoding errors until the transport is closed. A read error ends the
+/// iteration after it is returned.
+impl<R: Read> Iterator for Console<R> {
+    type Item = Result<LaneEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = [0; 256];
+        loop {
+            if let Some(decoded) = self.decoded.pop_front() {
+                return Some(decoded);
+            }
+            if self.closed {
+                return None;
+            }
+            match self.transport.read(&mut buffer) {
+                Ok(0) => self.closed = true,
+                Ok(length) => self.decoded.extend(self.decoder.push(&buffer[..length])),
+                Err(why) if why.kind() == io::ErrorKind::Interrupted => {}
+                Err(why) => {
+                    self.closed = true;
+                    return Some(Err(Error::Io(why)));
+                }
+            }
+        }
+    }
+}
+
+/// Transport that plays back bytes captured from a console, for testing without hardware.
+///
+/// Bytes are returned in chunks of at most `chunk_size` bytes to simulate a serial line that
+/// delivers frames in pieces.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    bytes: Vec<u8>,
+    position: usize,
+    chunk_size: usize,
+}
+
+impl Replay {
+    #[must_use]
+    pub const fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            chunk_size: 16,
+        }
+    }
+
+    /// Read a captured byte log.
+    ///
+    /// # Errors
+    /// Returns an error if `path` cannot be read.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        fs::read(path).map(Self::new)
+    }
+
+    /// Largest number of bytes returned by one read. Zero is treated as one.
+    #[must_use]
+    pub const fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = if chunk_size == 0 { 1 } else { chunk_size };
+        self
+    }
+}
+
+impl Read for Replay {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.bytes[self.position..];
+        let length = remaining.len().min(buffer.len()).min(self.chunk_size);
+        buffer[..length].copy_from_slice(&remaining[..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combine::{Combined, Combiner};
+    use jechsoft::meet_setup::touch_pad_set::TouchPadSet;
+    use std::path::PathBuf;
+
+    fn capture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("captures")
+            .join(name)
+    }
+
+    #[test]
+    fn replay_returns_chunks() {
+        let mut replay = Replay::new(b"abcde".to_vec()).with_chunk_size(2);
+        let mut buffer = [0; 8];
+        assert_eq!(replay.read(&mut buffer).ok(), Some(2));
+        assert_eq!(replay.read(&mut buffer).ok(), Some(2));
+        assert_eq!(replay.read(&mut buffer).ok(), Some(1));
+        assert_eq!(&buffer[..1], b"e");
+        assert_eq!(replay.read(&mut buffer).ok(), Some(0));
+    }
+
+    #[test]
+    fn two_set_capture_decodes_end_to_end() {
+        let replay = Replay::from_file(&capture("100m_two_sets.log")).expect("capture");
+        let whole: Vec<LaneEvent> = Console::new(replay.clone().with_chunk_size(4096))
+            .map(|event| event.expect("valid frame"))
+            .collect();
+        let bytewise: Vec<LaneEvent> = Console::new(replay.with_chunk_size(1))
+            .map(|event| event.expect("valid frame"))
+            .collect();
+
+        assert_eq!(whole.len(), 17);
+        assert_eq!(whole, bytewise);
+        assert!(matches!(whole[0], LaneEvent::Start { .. }));
+
+        let mut pads = Combiner::new(TouchPadSet::TwoSet);
+        let mut combined: Vec<Combined> = whole
+            .into_iter()
+            .flat_map(|event| pads.push(event))
+            .collect();
+        combined.extend(pads.flush());
+        let touches: Vec<_> = combined
+            .iter()
+            .filter_map(|combined| match combined {
+                Combined::Touch(touch) => Some(*touch),
+                Combined::Event(_) => None,
+            })
+            .collect();
+        let backups = combined
+            .iter()
+            .filter(|combined| matches!(combined, Combined::Event(LaneEvent::Backup { .. })))
+            .count();
+
+        // Splits and finishes of lanes 3, 4 and 5. Lane 5 only touched pad A.
+        assert_eq!(touches.len(), 6);
+        assert_eq!(backups, 6);
+        let partial: Vec<u8> = touches
+            .iter()
+            .filter(|touch| touch.is_partial(TouchPadSet::TwoSet))
+            .map(|touch| touch.lane)
+            .collect();
+        assert_eq!(partial, [5, 5]);
+        let lane_4 = touches
+            .iter()
+            .find(|touch| touch.lane == 4 && touch.is_finish())
+            .expect("finish of lane 4");
+        assert_eq!(
+            lane_4.time(),
+            Some(std::time::Duration::from_millis(62_340))
+        );
+    }
+}